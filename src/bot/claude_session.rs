@@ -1,8 +1,267 @@
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
+use telegram_bot::claude_code_client::InteractiveClaudeProcess;
 use tokio::process::Child;
 use tokio::sync::Mutex;
 
+use crate::github_client::{RepositoryInfo, ReviewFinding, ReviewThread};
+
+/// Pull request review threads offered to the user for addressing
+#[derive(Debug, Clone)]
+pub struct PrReviewState {
+    pub pr_number: u64,
+    pub threads: Vec<ReviewThread>,
+    /// Root comment ids of the threads selected for Claude to address
+    pub selected: HashSet<u64>,
+}
+
+impl PrReviewState {
+    pub fn new(pr_number: u64, threads: Vec<ReviewThread>) -> Self {
+        Self {
+            pr_number,
+            threads,
+            selected: HashSet::new(),
+        }
+    }
+
+    /// Toggle selection of a thread by its root comment id
+    pub fn toggle(&mut self, comment_id: u64) {
+        if !self.selected.remove(&comment_id) {
+            self.selected.insert(comment_id);
+        }
+    }
+
+    /// Threads currently selected, in display order
+    pub fn selected_threads(&self) -> Vec<&ReviewThread> {
+        self.threads
+            .iter()
+            .filter(|t| self.selected.contains(&t.root.id))
+            .collect()
+    }
+}
+
+/// Failed CI runs of a pushed branch, offered to Claude for fixing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CiFailures {
+    pub branch: String,
+    pub run_ids: Vec<u64>,
+}
+
+/// Git operation that stopped on merge conflicts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncOperation {
+    Merge,
+    Rebase,
+}
+
+/// Merge conflicts being resolved with /sync
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncState {
+    pub operation: SyncOperation,
+    /// Conflicted files, in the order they are listed to the user
+    pub conflicts: Vec<String>,
+}
+
+/// Findings of a /review of a pull request, offered for posting as a GitHub review
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeReviewState {
+    pub pr_number: u64,
+    /// Commit the review was made against, so comments land on the reviewed lines
+    pub head_sha: String,
+    pub findings: Vec<ReviewFinding>,
+}
+
+/// A changed file as listed by `git status --porcelain`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    /// Two-letter index and working tree status, e.g. ` M` or `??`
+    pub status: String,
+    /// Path relative to the repository root
+    pub path: String,
+    /// Source path of a rename or copy
+    pub original_path: Option<String>,
+}
+
+/// Changed files offered for discarding with /discard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscardState {
+    pub files: Vec<ChangedFile>,
+    /// Indexes into `files` of the files selected for discarding
+    pub selected: HashSet<usize>,
+    /// Files shown for confirmation, the only ones discarded; changing the selection drops them
+    pub confirmed: Option<Vec<ChangedFile>>,
+}
+
+impl DiscardState {
+    pub fn new(files: Vec<ChangedFile>) -> Self {
+        Self {
+            files,
+            selected: HashSet::new(),
+            confirmed: None,
+        }
+    }
+
+    /// Toggle selection of a file by its index
+    pub fn toggle(&mut self, index: usize) {
+        self.confirmed = None;
+        if index < self.files.len() && !self.selected.remove(&index) {
+            self.selected.insert(index);
+        }
+    }
+
+    /// Select every file, or clear the selection when everything is already selected
+    pub fn toggle_all(&mut self) {
+        self.confirmed = None;
+        if self.selected.len() == self.files.len() {
+            self.selected.clear();
+        } else {
+            self.selected = (0..self.files.len()).collect();
+        }
+    }
+
+    /// Files currently selected, in display order
+    pub fn selected_files(&self) -> Vec<&ChangedFile> {
+        self.files
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.selected.contains(index))
+            .map(|(_, file)| file)
+            .collect()
+    }
+}
+
+/// Maximum number of conversations remembered for /history
+pub const MAX_CONVERSATION_HISTORY: usize = 50;
+
+/// A Claude conversation that can be resumed from /history
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversationRecord {
+    /// Latest session id of the conversation; resuming may hand out a new one
+    pub id: String,
+    pub first_prompt: String,
+    pub started_at: DateTime<Utc>,
+    pub last_active_at: DateTime<Utc>,
+    /// Working directory the conversation ran in
+    pub repository: Option<String>,
+    /// Cost in USD of all runs in the conversation
+    pub total_cost: f64,
+    /// Prompt and cost of each run, oldest first
+    pub run_costs: Vec<RunCost>,
+    /// Conversation this one was forked from with /fork
+    pub forked_from: Option<String>,
+    /// Checkpoint of the working tree taken when the conversation was forked
+    pub checkpoint: Option<String>,
+}
+
+/// Cost in USD of one run of a conversation, with the prompt that started it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunCost {
+    pub prompt: String,
+    pub cost: f64,
+}
+
+/// A /fork waiting for its first run to branch off `conversation_id`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingFork {
+    pub conversation_id: String,
+    pub checkpoint: Option<String>,
+}
+
+/// Kind of commit signing key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningKeyKind {
+    Ssh,
+    Gpg,
+}
+
+/// Free-text input the bot is waiting for from the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingInput {
+    /// Next message is a search query for the repository picker
+    RepoSearch,
+    /// Next message of the user who asked is a private signing key to store on the volume
+    SigningKey { kind: SigningKeyKind, user_id: i64 },
+}
+
+/// Repository picker listing with optional search filter and current page
+#[derive(Debug, Clone)]
+pub struct RepoPickerState {
+    pub repos: Vec<RepositoryInfo>,
+    pub query: Option<String>,
+    pub page: usize,
+}
+
+impl RepoPickerState {
+    pub const PAGE_SIZE: usize = 8;
+
+    pub fn new(repos: Vec<RepositoryInfo>) -> Self {
+        Self {
+            repos,
+            query: None,
+            page: 0,
+        }
+    }
+
+    /// Repositories matching the current query, paired with their index in `repos`
+    pub fn filtered(&self) -> Vec<(usize, &RepositoryInfo)> {
+        self.repos
+            .iter()
+            .enumerate()
+            .filter(|(_, repo)| self.query.as_deref().is_none_or(|q| repo.matches(q)))
+            .collect()
+    }
+
+    /// Number of pages for the current filter (at least one)
+    pub fn page_count(&self) -> usize {
+        self.filtered().len().div_ceil(Self::PAGE_SIZE).max(1)
+    }
+
+    /// Repositories on the current page, paired with their index in `repos`
+    pub fn current_page(&self) -> Vec<(usize, &RepositoryInfo)> {
+        self.filtered()
+            .into_iter()
+            .skip(self.page * Self::PAGE_SIZE)
+            .take(Self::PAGE_SIZE)
+            .collect()
+    }
+
+    /// Move to a page, clamped to the available range
+    pub fn set_page(&mut self, page: usize) {
+        self.page = page.min(self.page_count() - 1);
+    }
+
+    /// Replace the search query and go back to the first page
+    pub fn set_query(&mut self, query: Option<String>) {
+        self.query = query.filter(|q| !q.trim().is_empty());
+        self.page = 0;
+    }
+}
+
+/// Name of the implicit task representing the repository's main checkout
+pub const MAIN_TASK_NAME: &str = "main";
+
+/// A parallel task: a git worktree with its own Claude conversation
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskState {
+    pub name: String,
+    pub working_directory: Option<String>,
+    pub conversation_id: Option<String>,
+    pub last_run_cost: Option<f64>,
+}
+
+impl TaskState {
+    pub fn new(name: String, working_directory: String) -> Self {
+        Self {
+            name,
+            working_directory: Some(working_directory),
+            conversation_id: None,
+            last_run_cost: None,
+        }
+    }
+}
+
 /// Claude conversation session state
 #[derive(Debug)]
 pub struct ClaudeSession {
@@ -10,6 +269,46 @@ pub struct ClaudeSession {
     pub process_handle: Option<Child>,
     pub is_active: bool,
     pub working_directory: Option<String>,
+    /// Final result text of the most recent Claude run
+    pub last_run_result: Option<String>,
+    pub pr_review: Option<PrReviewState>,
+    /// Clone request detected in chat that is too long to fit in callback data
+    pub pending_clone: Option<String>,
+    pub repo_picker: Option<RepoPickerState>,
+    pub pending_input: Option<PendingInput>,
+    /// Cost in USD of the most recent Claude run
+    pub last_run_cost: Option<f64>,
+    /// Parallel tasks; empty until the first `/task new`, then the first entry is "main"
+    pub tasks: Vec<TaskState>,
+    pub active_task: Option<String>,
+    pub ci_failures: Option<CiFailures>,
+    /// Branches whose CI is being watched, with the commit being watched
+    pub ci_watches: HashMap<String, String>,
+    /// Commit all changes after every successful Claude run
+    pub auto_commit: bool,
+    pub sync: Option<SyncState>,
+    pub code_review: Option<CodeReviewState>,
+    pub discard: Option<DiscardState>,
+    /// Directories of the repositories cloned in this session, in clone order
+    pub repositories: Vec<String>,
+    /// Conversations of the directories the session switched away from
+    pub directory_conversations: HashMap<String, String>,
+    /// Conversations of this session, oldest first
+    pub conversations: Vec<ConversationRecord>,
+    /// Set by /fork; the next run of that conversation starts a branch of it
+    pub pending_fork: Option<PendingFork>,
+    /// Set with /instructions; appended to Claude's system prompt for every run in this chat
+    pub custom_instructions: Option<String>,
+    /// Tools Claude reported when the last run started, including those of MCP servers
+    pub mcp_tools: Option<Vec<String>>,
+    /// Set with /interactive; keeps one Claude process running between prompts
+    pub interactive_mode: bool,
+    /// The long-lived Claude process of interactive mode, once started
+    pub interactive_process: Option<Arc<InteractiveClaudeProcess>>,
+    /// Set with /limits; most agentic turns per Claude run
+    pub max_turns: Option<u32>,
+    /// Set with /limits; longest a Claude run may take before it's stopped
+    pub run_timeout: Option<Duration>,
 }
 
 impl ClaudeSession {
@@ -19,6 +318,30 @@ impl ClaudeSession {
             process_handle: None,
             is_active: false,
             working_directory: None,
+            last_run_result: None,
+            pr_review: None,
+            pending_clone: None,
+            repo_picker: None,
+            pending_input: None,
+            last_run_cost: None,
+            tasks: Vec::new(),
+            active_task: None,
+            ci_failures: None,
+            ci_watches: HashMap::new(),
+            auto_commit: false,
+            sync: None,
+            code_review: None,
+            discard: None,
+            repositories: Vec::new(),
+            directory_conversations: HashMap::new(),
+            conversations: Vec::new(),
+            pending_fork: None,
+            custom_instructions: None,
+            mcp_tools: None,
+            interactive_mode: false,
+            interactive_process: None,
+            max_turns: None,
+            run_timeout: None,
         }
     }
 
@@ -34,6 +357,8 @@ impl ClaudeSession {
             // Kill the process - we don't need to await the result in this synchronous context
             std::mem::drop(process.kill());
         }
+        // Dropping the interactive process closes its stdin, which ends it
+        self.interactive_process = None;
         self.is_active = false;
     }
 
@@ -49,6 +374,198 @@ impl ClaudeSession {
     pub fn get_working_directory(&self) -> Option<&String> {
         self.working_directory.as_ref()
     }
+
+    /// Remember a cloned repository and switch to it
+    pub fn add_repository(&mut self, directory: String) {
+        if !self.repositories.contains(&directory) {
+            self.repositories.push(directory.clone());
+        }
+        self.switch_directory(directory);
+    }
+
+    /// Move to another repository (or the workspace root), parking the current conversation
+    /// and resuming the one last used there
+    ///
+    /// State that refers to the previous checkout is dropped, including the implicit main task.
+    pub fn switch_directory(&mut self, directory: String) {
+        if self.working_directory.as_ref() == Some(&directory) {
+            return;
+        }
+
+        if let (Some(current), Some(conversation_id)) =
+            (self.working_directory.take(), self.conversation_id.take())
+        {
+            self.directory_conversations
+                .insert(current, conversation_id);
+        }
+        self.conversation_id = self.directory_conversations.remove(&directory);
+        self.working_directory = Some(directory);
+        self.last_run_cost = None;
+
+        self.tasks.clear();
+        self.active_task = None;
+        self.pr_review = None;
+        self.ci_failures = None;
+        self.sync = None;
+        self.code_review = None;
+        self.discard = None;
+    }
+
+    /// Record a finished run in the conversation history
+    ///
+    /// A run that resumed `resumed_id` continues that conversation, even when Claude handed out
+    /// a new session id for it; otherwise a new conversation starting with `prompt` is added.
+    pub fn record_conversation(
+        &mut self,
+        resumed_id: Option<&str>,
+        conversation_id: &str,
+        prompt: &str,
+        cost: Option<f64>,
+    ) {
+        let now = Utc::now();
+        let existing = self.conversations.iter().position(|record| {
+            record.id == conversation_id || Some(record.id.as_str()) == resumed_id
+        });
+
+        let mut record = match existing {
+            Some(index) => self.conversations.remove(index),
+            None => ConversationRecord {
+                id: conversation_id.to_string(),
+                first_prompt: prompt.to_string(),
+                started_at: now,
+                last_active_at: now,
+                repository: self.working_directory.clone(),
+                total_cost: 0.0,
+                run_costs: Vec::new(),
+                forked_from: None,
+                checkpoint: None,
+            },
+        };
+        record.id = conversation_id.to_string();
+        record.last_active_at = now;
+        record.total_cost += cost.unwrap_or(0.0);
+        record.run_costs.extend(cost.map(|cost| RunCost {
+            prompt: prompt.to_string(),
+            cost,
+        }));
+        self.push_conversation(record);
+    }
+
+    /// Record the first run of a forked conversation, keeping the original in the history
+    pub fn record_fork(
+        &mut self,
+        fork: PendingFork,
+        conversation_id: &str,
+        prompt: &str,
+        cost: Option<f64>,
+    ) {
+        let now = Utc::now();
+        self.conversations
+            .retain(|record| record.id != conversation_id);
+        self.push_conversation(ConversationRecord {
+            id: conversation_id.to_string(),
+            first_prompt: prompt.to_string(),
+            started_at: now,
+            last_active_at: now,
+            repository: self.working_directory.clone(),
+            total_cost: cost.unwrap_or(0.0),
+            run_costs: cost
+                .map(|cost| RunCost {
+                    prompt: prompt.to_string(),
+                    cost,
+                })
+                .into_iter()
+                .collect(),
+            forked_from: Some(fork.conversation_id),
+            checkpoint: fork.checkpoint,
+        });
+    }
+
+    fn push_conversation(&mut self, record: ConversationRecord) {
+        // Most recently active last
+        self.conversations.push(record);
+        if self.conversations.len() > MAX_CONVERSATION_HISTORY {
+            self.conversations.remove(0);
+        }
+    }
+
+    /// Look up a conversation in the history by id
+    pub fn conversation(&self, id: &str) -> Option<&ConversationRecord> {
+        self.conversations.iter().find(|record| record.id == id)
+    }
+
+    /// Whether tasks besides the main checkout exist, which ties the session to its repository
+    pub fn has_parallel_tasks(&self) -> bool {
+        self.tasks.len() > 1
+    }
+
+    /// Store the live conversation state back into the active task
+    pub fn save_active_task(&mut self) {
+        let Some(active) = self.active_task.clone() else {
+            return;
+        };
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.name == active) {
+            task.working_directory = self.working_directory.clone();
+            task.conversation_id = self.conversation_id.clone();
+            task.last_run_cost = self.last_run_cost;
+        }
+    }
+
+    /// Add a task and make it active; the current checkout becomes the "main" task
+    pub fn add_task(&mut self, task: TaskState) {
+        if self.tasks.is_empty() {
+            self.tasks.push(TaskState {
+                name: MAIN_TASK_NAME.to_string(),
+                working_directory: self.working_directory.clone(),
+                conversation_id: self.conversation_id.clone(),
+                last_run_cost: self.last_run_cost,
+            });
+            self.active_task = Some(MAIN_TASK_NAME.to_string());
+        }
+
+        let name = task.name.clone();
+        self.tasks.push(task);
+        self.switch_task(&name);
+    }
+
+    /// Make another task active, swapping in its directory and conversation
+    pub fn switch_task(&mut self, name: &str) -> bool {
+        let Some(task) = self.tasks.iter().find(|t| t.name == name).cloned() else {
+            return false;
+        };
+
+        self.save_active_task();
+        self.working_directory = task.working_directory;
+        self.conversation_id = task.conversation_id;
+        self.last_run_cost = task.last_run_cost;
+        self.active_task = Some(task.name);
+        true
+    }
+
+    /// Look up a task by name
+    pub fn task(&self, name: &str) -> Option<&TaskState> {
+        self.tasks.iter().find(|t| t.name == name)
+    }
+
+    /// Forget a task; the main task and the active task cannot be removed
+    pub fn remove_task(&mut self, name: &str) -> Option<TaskState> {
+        if name == MAIN_TASK_NAME || self.active_task.as_deref() == Some(name) {
+            return None;
+        }
+        let index = self.tasks.iter().position(|t| t.name == name)?;
+        Some(self.tasks.remove(index))
+    }
+
+    /// Take the input the bot is waiting for, unless it is a signing key another user was asked for
+    pub fn take_pending_input(&mut self, user_id: i64) -> Option<PendingInput> {
+        match self.pending_input {
+            Some(PendingInput::SigningKey {
+                user_id: requested_by,
+                ..
+            }) if requested_by != user_id => None,
+            _ => self.pending_input.take(),
+        }
+    }
 }
 
 /// Global state for tracking Claude conversation sessions
@@ -131,6 +648,149 @@ mod tests {
         assert!(session.conversation_id.is_none());
     }
 
+    #[test]
+    fn test_pr_review_state_toggle() {
+        use crate::github_client::types::GithubUser;
+        use crate::github_client::PullRequestReviewComment;
+
+        let thread = |id: u64| ReviewThread {
+            root: PullRequestReviewComment {
+                id,
+                path: "src/lib.rs".to_string(),
+                line: Some(1),
+                original_line: None,
+                body: "fix".to_string(),
+                user: Some(GithubUser {
+                    login: "reviewer".to_string(),
+                }),
+                in_reply_to_id: None,
+            },
+            replies: Vec::new(),
+        };
+
+        let mut state = PrReviewState::new(7, vec![thread(1), thread(2), thread(3)]);
+        assert!(state.selected_threads().is_empty());
+
+        state.toggle(3);
+        state.toggle(1);
+        let selected: Vec<u64> = state.selected_threads().iter().map(|t| t.root.id).collect();
+        assert_eq!(selected, vec![1, 3]);
+
+        state.toggle(1);
+        let selected: Vec<u64> = state.selected_threads().iter().map(|t| t.root.id).collect();
+        assert_eq!(selected, vec![3]);
+    }
+
+    #[test]
+    fn test_switch_directory_keeps_conversations_apart() {
+        let mut session = ClaudeSession::new();
+        session.add_repository("api".to_string());
+        session.conversation_id = Some("api-conversation".to_string());
+
+        session.add_repository("web".to_string());
+        assert_eq!(session.repositories, vec!["api", "web"]);
+        assert_eq!(
+            session.get_working_directory().map(String::as_str),
+            Some("web")
+        );
+        assert_eq!(session.conversation_id, None);
+
+        session.conversation_id = Some("web-conversation".to_string());
+        session.switch_directory("api".to_string());
+        assert_eq!(session.conversation_id.as_deref(), Some("api-conversation"));
+
+        // Cloning a repository again doesn't list it twice
+        session.add_repository("web".to_string());
+        assert_eq!(session.repositories.len(), 2);
+        assert_eq!(session.conversation_id.as_deref(), Some("web-conversation"));
+    }
+
+    #[test]
+    fn test_record_conversation() {
+        let mut session = ClaudeSession::new();
+        session.set_working_directory("api".to_string());
+
+        session.record_conversation(None, "first", "Fix the login bug", Some(0.5));
+        session.record_conversation(None, "second", "Write docs", None);
+        // Resuming "first" hands out a new id but continues the same conversation
+        session.record_conversation(Some("first"), "first-resumed", "Add a test", Some(0.25));
+
+        assert_eq!(session.conversations.len(), 2);
+        let record = session.conversation("first-resumed").unwrap();
+        assert_eq!(record.first_prompt, "Fix the login bug");
+        assert_eq!(record.repository.as_deref(), Some("api"));
+        assert_eq!(record.total_cost, 0.75);
+        assert_eq!(
+            record.run_costs,
+            vec![
+                RunCost {
+                    prompt: "Fix the login bug".to_string(),
+                    cost: 0.5,
+                },
+                RunCost {
+                    prompt: "Add a test".to_string(),
+                    cost: 0.25,
+                },
+            ]
+        );
+        assert_eq!(session.conversations.last().unwrap().id, "first-resumed");
+        assert!(session.conversation("first").is_none());
+
+        for index in 0..MAX_CONVERSATION_HISTORY {
+            session.record_conversation(None, &index.to_string(), "prompt", None);
+        }
+        assert_eq!(session.conversations.len(), MAX_CONVERSATION_HISTORY);
+        assert!(session.conversation("second").is_none());
+    }
+
+    #[test]
+    fn test_record_fork() {
+        let mut session = ClaudeSession::new();
+        session.record_conversation(None, "main", "Fix the login bug", Some(0.5));
+
+        let fork = PendingFork {
+            conversation_id: "main".to_string(),
+            checkpoint: Some("20240101-120000-000".to_string()),
+        };
+        session.record_fork(fork, "branch", "Try sessions instead", Some(0.1));
+
+        assert_eq!(session.conversations.len(), 2);
+        assert_eq!(session.conversation("main").unwrap().total_cost, 0.5);
+        let branch = session.conversation("branch").unwrap();
+        assert_eq!(branch.first_prompt, "Try sessions instead");
+        assert_eq!(branch.forked_from.as_deref(), Some("main"));
+        assert_eq!(branch.checkpoint.as_deref(), Some("20240101-120000-000"));
+    }
+
+    #[test]
+    fn test_discard_state_selection() {
+        let file = |path: &str| ChangedFile {
+            status: " M".to_string(),
+            path: path.to_string(),
+            original_path: None,
+        };
+
+        let mut state = DiscardState::new(vec![file("a"), file("b"), file("c")]);
+        state.toggle(2);
+        state.toggle(0);
+        state.toggle(9);
+        let selected: Vec<&str> = state
+            .selected_files()
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect();
+        assert_eq!(selected, vec!["a", "c"]);
+
+        state.toggle_all();
+        assert_eq!(state.selected_files().len(), 3);
+        state.toggle_all();
+        assert!(state.selected_files().is_empty());
+
+        state.confirmed = Some(vec![file("a")]);
+        state.toggle(1);
+        assert_eq!(state.confirmed, None);
+    }
+
     #[test]
     fn test_claude_session_state_transitions() {
         // Test state without process creation
@@ -191,4 +851,103 @@ mod tests {
             assert!(session.unwrap().is_active);
         }
     }
+
+    #[test]
+    fn test_repo_picker_pagination_and_search() {
+        let repos = (0..20)
+            .map(|i| RepositoryInfo {
+                name_with_owner: format!("owner/repo-{}", i),
+                name: format!("repo-{}", i),
+                description: (i == 13).then(|| "Needle in a haystack".to_string()),
+                visibility: Some("PUBLIC".to_string()),
+                updated_at: None,
+            })
+            .collect();
+        let mut picker = RepoPickerState::new(repos);
+
+        assert_eq!(picker.page_count(), 3);
+        assert_eq!(picker.current_page().len(), RepoPickerState::PAGE_SIZE);
+
+        picker.set_page(10);
+        assert_eq!(picker.page, 2);
+        assert_eq!(picker.current_page().len(), 4);
+        assert_eq!(picker.current_page()[0].0, 16);
+
+        picker.set_query(Some("needle".to_string()));
+        assert_eq!(picker.page, 0);
+        assert_eq!(picker.page_count(), 1);
+        assert_eq!(picker.current_page()[0].0, 13);
+
+        picker.set_query(Some("nothing matches".to_string()));
+        assert!(picker.current_page().is_empty());
+        assert_eq!(picker.page_count(), 1);
+
+        picker.set_query(Some("  ".to_string()));
+        assert!(picker.query.is_none());
+        assert_eq!(picker.filtered().len(), 20);
+    }
+
+    #[test]
+    fn test_task_switching_keeps_conversations_apart() {
+        let mut session = ClaudeSession::new();
+        session.set_working_directory("/workspace/app".to_string());
+        session.conversation_id = Some("main-conversation".to_string());
+        session.last_run_cost = Some(0.5);
+
+        session.add_task(TaskState::new(
+            "login".to_string(),
+            "/workspace/app-login".to_string(),
+        ));
+        assert_eq!(session.active_task.as_deref(), Some("login"));
+        assert_eq!(session.tasks.len(), 2);
+        assert_eq!(
+            session.get_working_directory().map(String::as_str),
+            Some("/workspace/app-login")
+        );
+        assert!(session.conversation_id.is_none());
+        assert!(session.last_run_cost.is_none());
+
+        session.conversation_id = Some("login-conversation".to_string());
+        assert!(session.switch_task(MAIN_TASK_NAME));
+        assert_eq!(
+            session.conversation_id.as_deref(),
+            Some("main-conversation")
+        );
+        assert_eq!(session.last_run_cost, Some(0.5));
+        assert_eq!(
+            session.task("login").unwrap().conversation_id.as_deref(),
+            Some("login-conversation")
+        );
+
+        assert!(!session.switch_task("missing"));
+        assert!(session.remove_task(MAIN_TASK_NAME).is_none());
+        assert!(session.remove_task("login").is_some());
+        assert_eq!(session.tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_take_pending_input() {
+        let mut session = ClaudeSession::new();
+        session.pending_input = Some(PendingInput::SigningKey {
+            kind: SigningKeyKind::Ssh,
+            user_id: 7,
+        });
+
+        assert_eq!(session.take_pending_input(8), None);
+        assert!(session.pending_input.is_some());
+        assert_eq!(
+            session.take_pending_input(7),
+            Some(PendingInput::SigningKey {
+                kind: SigningKeyKind::Ssh,
+                user_id: 7,
+            })
+        );
+        assert!(session.pending_input.is_none());
+
+        session.pending_input = Some(PendingInput::RepoSearch);
+        assert_eq!(
+            session.take_pending_input(8),
+            Some(PendingInput::RepoSearch)
+        );
+    }
 }
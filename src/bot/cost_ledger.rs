@@ -0,0 +1,370 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Environment variable overriding where the cost ledger is stored
+pub const COST_LEDGER_PATH_ENV: &str = "COST_LEDGER_PATH";
+
+/// Environment variables holding the spending caps in USD; unset means no cap
+pub const USER_DAILY_BUDGET_ENV: &str = "USER_DAILY_BUDGET_USD";
+pub const USER_MONTHLY_BUDGET_ENV: &str = "USER_MONTHLY_BUDGET_USD";
+pub const CHAT_DAILY_BUDGET_ENV: &str = "CHAT_DAILY_BUDGET_USD";
+pub const CHAT_MONTHLY_BUDGET_ENV: &str = "CHAT_MONTHLY_BUDGET_USD";
+
+const DEFAULT_COST_LEDGER_PATH: &str = "cost_ledger.json";
+
+/// Share of a budget after which users are warned
+pub const BUDGET_WARNING_RATIO: f64 = 0.8;
+
+/// Entries older than this many days are dropped from the ledger
+const LEDGER_RETENTION_DAYS: i64 = 400;
+
+/// Errors that can occur while storing or loading the cost ledger
+#[derive(Debug, thiserror::Error)]
+pub enum CostLedgerError {
+    #[error("JSON serialization error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("File I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Spend of one user in one chat and repository on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub date: NaiveDate,
+    pub user_id: i64,
+    pub chat_id: i64,
+    pub repository: Option<String>,
+    pub cost: f64,
+    pub runs: u32,
+}
+
+/// Daily and monthly caps in USD
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Budget {
+    pub daily: Option<f64>,
+    pub monthly: Option<f64>,
+}
+
+impl Budget {
+    fn from_env(daily_env: &str, monthly_env: &str) -> Self {
+        let read = |name: &str| {
+            let value = std::env::var(name).ok()?;
+            match value.trim().parse::<f64>() {
+                Ok(limit) if limit > 0.0 => Some(limit),
+                _ => {
+                    log::warn!("Ignoring invalid {}: {}", name, value);
+                    None
+                }
+            }
+        };
+
+        Self {
+            daily: read(daily_env),
+            monthly: read(monthly_env),
+        }
+    }
+}
+
+/// Caps applying to each user across chats, and to each chat across users
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BudgetLimits {
+    pub user: Budget,
+    pub chat: Budget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetScope {
+    User,
+    Chat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetPeriod {
+    Day,
+    Month,
+}
+
+/// Spend against one configured cap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BudgetStatus {
+    pub scope: BudgetScope,
+    pub period: BudgetPeriod,
+    pub spent: f64,
+    pub limit: f64,
+}
+
+impl BudgetStatus {
+    pub fn ratio(&self) -> f64 {
+        self.spent / self.limit
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.spent >= self.limit
+    }
+
+    /// Like "your daily budget" or "this chat's monthly budget"
+    pub fn name(&self) -> String {
+        let owner = match self.scope {
+            BudgetScope::User => "your",
+            BudgetScope::Chat => "this chat's",
+        };
+        let period = match self.period {
+            BudgetPeriod::Day => "daily",
+            BudgetPeriod::Month => "monthly",
+        };
+        format!("{} {} budget", owner, period)
+    }
+}
+
+/// Claude spend per user, chat, repository and day, persisted in a JSON file on the bot host
+#[derive(Clone)]
+pub struct CostLedger {
+    path: PathBuf,
+    limits: BudgetLimits,
+    entries: Arc<Mutex<Vec<LedgerEntry>>>,
+    /// Held while the file is written, so saves land in the order the entries changed
+    save_lock: Arc<Mutex<()>>,
+}
+
+impl CostLedger {
+    /// Open the ledger and read the caps configured through the environment
+    pub fn from_env() -> Self {
+        let path = std::env::var(COST_LEDGER_PATH_ENV)
+            .unwrap_or_else(|_| DEFAULT_COST_LEDGER_PATH.to_string());
+        let limits = BudgetLimits {
+            user: Budget::from_env(USER_DAILY_BUDGET_ENV, USER_MONTHLY_BUDGET_ENV),
+            chat: Budget::from_env(CHAT_DAILY_BUDGET_ENV, CHAT_MONTHLY_BUDGET_ENV),
+        };
+
+        Self::new(path, limits)
+    }
+
+    /// Open the ledger at `path`
+    pub fn new(path: impl Into<PathBuf>, limits: BudgetLimits) -> Self {
+        let path = path.into();
+        let entries = match load_entries(&path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Failed to load cost ledger from {}: {}", path.display(), e);
+                Vec::new()
+            }
+        };
+
+        Self {
+            path,
+            limits,
+            entries: Arc::new(Mutex::new(entries)),
+            save_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Add the cost of a run and persist the ledger
+    pub async fn record(
+        &self,
+        date: NaiveDate,
+        user_id: i64,
+        chat_id: i64,
+        repository: Option<String>,
+        cost: f64,
+    ) -> Result<(), CostLedgerError> {
+        let mut entries = self.entries.lock().await;
+
+        let existing = entries.iter_mut().find(|entry| {
+            entry.date == date
+                && entry.user_id == user_id
+                && entry.chat_id == chat_id
+                && entry.repository == repository
+        });
+        match existing {
+            Some(entry) => {
+                entry.cost += cost;
+                entry.runs += 1;
+            }
+            None => entries.push(LedgerEntry {
+                date,
+                user_id,
+                chat_id,
+                repository,
+                cost,
+                runs: 1,
+            }),
+        }
+
+        entries.retain(|entry| (date - entry.date).num_days() <= LEDGER_RETENTION_DAYS);
+
+        // Write off the async runtime and without holding up readers of the entries
+        let snapshot = entries.clone();
+        let _saving = self.save_lock.lock().await;
+        drop(entries);
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || save_entries(&path, &snapshot))
+            .await
+            .map_err(|e| CostLedgerError::IoError(std::io::Error::other(e)))?
+    }
+
+    /// Spend against every configured cap of the user and the chat
+    pub async fn budget_statuses(
+        &self,
+        date: NaiveDate,
+        user_id: i64,
+        chat_id: i64,
+    ) -> Vec<BudgetStatus> {
+        let entries = self.entries.lock().await;
+        budget_statuses(&entries, &self.limits, date, user_id, chat_id)
+    }
+
+    /// Entries of a user or a chat in the month of `date`
+    pub async fn month_entries(
+        &self,
+        date: NaiveDate,
+        user_id: i64,
+        chat_id: i64,
+    ) -> Vec<LedgerEntry> {
+        let entries = self.entries.lock().await;
+        entries
+            .iter()
+            .filter(|entry| same_month(entry.date, date))
+            .filter(|entry| entry.user_id == user_id || entry.chat_id == chat_id)
+            .cloned()
+            .collect()
+    }
+}
+
+fn same_month(a: NaiveDate, b: NaiveDate) -> bool {
+    a.year() == b.year() && a.month() == b.month()
+}
+
+/// Spend of a user or chat today and this month, summed from `entries`
+pub fn spend(entries: &[LedgerEntry], scope: BudgetScope, id: i64, date: NaiveDate) -> (f64, f64) {
+    entries
+        .iter()
+        .filter(|entry| match scope {
+            BudgetScope::User => entry.user_id == id,
+            BudgetScope::Chat => entry.chat_id == id,
+        })
+        .filter(|entry| same_month(entry.date, date))
+        .fold((0.0, 0.0), |(day, month), entry| {
+            let day = if entry.date == date {
+                day + entry.cost
+            } else {
+                day
+            };
+            (day, month + entry.cost)
+        })
+}
+
+fn budget_statuses(
+    entries: &[LedgerEntry],
+    limits: &BudgetLimits,
+    date: NaiveDate,
+    user_id: i64,
+    chat_id: i64,
+) -> Vec<BudgetStatus> {
+    let mut statuses = Vec::new();
+
+    for (scope, id, budget) in [
+        (BudgetScope::User, user_id, limits.user),
+        (BudgetScope::Chat, chat_id, limits.chat),
+    ] {
+        let (day, month) = spend(entries, scope, id, date);
+        for (period, spent, limit) in [
+            (BudgetPeriod::Day, day, budget.daily),
+            (BudgetPeriod::Month, month, budget.monthly),
+        ] {
+            if let Some(limit) = limit {
+                statuses.push(BudgetStatus {
+                    scope,
+                    period,
+                    spent,
+                    limit,
+                });
+            }
+        }
+    }
+
+    statuses
+}
+
+fn load_entries(path: &Path) -> Result<Vec<LedgerEntry>, CostLedgerError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_entries(path: &Path, entries: &[LedgerEntry]) -> Result<(), CostLedgerError> {
+    let content = serde_json::to_string_pretty(entries)?;
+    crate::bot::atomic_file::write_atomically(path, content.as_bytes(), None)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cost_ledger_budgets() {
+        let path = std::env::temp_dir().join(format!("cost-ledger-{}.json", uuid::Uuid::new_v4()));
+        let limits = BudgetLimits {
+            user: Budget {
+                daily: Some(1.0),
+                monthly: None,
+            },
+            chat: Budget {
+                daily: None,
+                monthly: Some(10.0),
+            },
+        };
+        let ledger = CostLedger::new(&path, limits);
+
+        ledger.record(date(1), 7, 100, None, 2.0).await.unwrap();
+        ledger
+            .record(date(2), 7, 100, Some("api".to_string()), 0.5)
+            .await
+            .unwrap();
+        ledger
+            .record(date(2), 7, 100, Some("api".to_string()), 0.25)
+            .await
+            .unwrap();
+        // Another user in the same chat
+        ledger.record(date(2), 8, 100, None, 1.5).await.unwrap();
+
+        let statuses = ledger.budget_statuses(date(2), 7, 100).await;
+        assert_eq!(
+            statuses,
+            vec![
+                BudgetStatus {
+                    scope: BudgetScope::User,
+                    period: BudgetPeriod::Day,
+                    spent: 0.75,
+                    limit: 1.0,
+                },
+                BudgetStatus {
+                    scope: BudgetScope::Chat,
+                    period: BudgetPeriod::Month,
+                    spent: 4.25,
+                    limit: 10.0,
+                },
+            ]
+        );
+        assert!(!statuses[0].is_exceeded());
+        assert_eq!(statuses[0].name(), "your daily budget");
+
+        // Runs on the same day and repository share an entry, and survive a restart
+        let reopened = CostLedger::new(&path, limits);
+        let entries = reopened.month_entries(date(2), 7, 100).await;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].runs, 2);
+        assert!(reopened.month_entries(date(2), 9, 200).await.is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
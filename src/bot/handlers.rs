@@ -5,9 +5,10 @@ use teloxide::{
 use tokio::sync::mpsc;
 use url::Url;
 
+use super::claude_session::{ClaudeSession, PendingInput};
 use super::{markdown::{escape_markdown_v2, truncate_if_needed}, state::BotState};
 use crate::commands;
-use crate::github_client::{GithubClient, GithubClientConfig};
+use crate::github_client::{CloneRequest, GithubClient, GithubClientConfig};
 use telegram_bot::claude_code_client::{AuthState, ClaudeCodeClient};
 
 /// Authentication state monitoring task
@@ -154,7 +155,41 @@ If you need to restart authentication, use `/auth login`",
             return Ok(());
         }
 
-        // Priority 2: Check if there's an active Claude conversation session
+        // Priority 2: Check if the bot is waiting for free-text input (e.g. a search query)
+        let user_id = msg
+            .from
+            .as_ref()
+            .map(|user| user.id.0 as i64)
+            .unwrap_or(chat_id);
+        let pending_input = {
+            let mut sessions = bot_state.claude_sessions.lock().await;
+            sessions
+                .get_mut(&chat_id)
+                .and_then(|s| s.take_pending_input(user_id))
+        };
+
+        if let Some(pending_input) = pending_input {
+            match pending_input {
+                PendingInput::RepoSearch => {
+                    commands::repo_picker::handle_repo_search_query(
+                        bot,
+                        msg.chat.id,
+                        &bot_state,
+                        &text,
+                    )
+                    .await?;
+                }
+                PendingInput::SigningKey { kind, .. } => {
+                    commands::signing_key::handle_signing_key_upload(
+                        bot, msg, bot_state, kind, &text,
+                    )
+                    .await?;
+                }
+            }
+            return Ok(());
+        }
+
+        // Priority 3: Check if there's an active Claude conversation session
         let claude_session_active = {
             let sessions = bot_state.claude_sessions.lock().await;
             sessions.get(&chat_id).map(|s| s.is_active).unwrap_or(false)
@@ -166,21 +201,42 @@ If you need to restart authentication, use `/auth login`",
             return Ok(());
         }
 
-        // Priority 3: Check if text looks like a repository name for cloning
-        if text.contains('/') && !text.contains(' ') && text.len() > 3 && text.len() < 100 {
-            // Simple validation for owner/repo pattern
-            let parts: Vec<&str> = text.split('/').collect();
-            if parts.len() == 2 && !parts[0].is_empty() && !parts[1].is_empty() {
+        // Priority 4: Offer to clone a git URL, or an owner/repo name when the chat has no Claude
+        // conversation, since in conversation a remark like "and/or" would look like one too
+        let in_conversation = {
+            let sessions = bot_state.claude_sessions.lock().await;
+            sessions
+                .get(&chat_id)
+                .is_some_and(|session| session.conversation_id.is_some())
+        };
+        if text.len() > 3 && text.len() < 300 {
+            if let Some(request) = CloneRequest::parse(&text)
+                .ok()
+                .filter(|request| request.is_git_url() || !in_conversation)
+            {
+                // Telegram limits callback data to 64 bytes, so long requests are kept in the session
+                let callback_data = format!("start_clone:{}", text);
+                let callback_data = if callback_data.len() <= 64 {
+                    callback_data
+                } else {
+                    let mut sessions = bot_state.claude_sessions.lock().await;
+                    sessions
+                        .entry(chat_id)
+                        .or_insert_with(ClaudeSession::new)
+                        .pending_clone = Some(text.clone());
+                    "start_clone_pending".to_string()
+                };
+
                 let keyboard =
                     InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
-                        format!("🔗 Clone {}", text),
-                        format!("start_clone:{}", text),
+                        format!("🔗 Clone {}", request.default_directory()),
+                        callback_data,
                     )]]);
 
                 bot.send_message(
                     msg.chat.id,
                     format!(
-                        "📦 *Repository Detected*\n\nI detected a repository name: `{}`\n\nWould you like to clone it?",
+                        "📦 *Repository Detected*\n\nI detected a repository: `{}`\n\nWould you like to clone it?",
                         escape_markdown_v2(&text)
                     ),
                 )
@@ -191,7 +247,7 @@ If you need to restart authentication, use `/auth login`",
             }
         }
 
-        // Priority 4: No active sessions - do nothing (default behavior)
+        // Priority 5: No active sessions - do nothing (default behavior)
     }
 
     Ok(())
@@ -205,6 +261,11 @@ async fn handle_claude_message(
     text: &str,
 ) -> ResponseResult<()> {
     let chat_id = msg.chat.id.0;
+    let user_id = msg
+        .from
+        .as_ref()
+        .map(|user| user.id.0 as i64)
+        .unwrap_or(chat_id);
 
     // Get the current conversation ID if any
     let conversation_id = {
@@ -229,6 +290,7 @@ async fn handle_claude_message(
         bot.clone(),
         msg.chat.id,
         bot_state.clone(),
+        user_id,
         text,
         conversation_id,
     )
@@ -254,187 +316,6 @@ async fn handle_claude_message(
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::bot::{AuthSession, AuthSessions, BotState, ClaudeSession, ClaudeSessions};
-    use bollard::Docker;
-    use std::collections::HashMap;
-    use std::sync::Arc;
-    use tokio::sync::{mpsc, oneshot, Mutex};
-
-    fn create_test_bot_state() -> BotState {
-        // Create a mock Docker instance (won't be used in these tests)
-        let docker = Docker::connect_with_socket_defaults().unwrap();
-        let auth_sessions: AuthSessions = Arc::new(Mutex::new(HashMap::new()));
-        let claude_sessions: ClaudeSessions = Arc::new(Mutex::new(HashMap::new()));
-
-        BotState {
-            docker,
-            auth_sessions,
-            claude_sessions,
-        }
-    }
-
-    #[tokio::test]
-    async fn test_claude_session_priority_routing() {
-        let bot_state = create_test_bot_state();
-        let chat_id = 12345i64;
-
-        // Test 1: No active sessions - should be ignored (no error)
-        {
-            let sessions = bot_state.claude_sessions.lock().await;
-            assert!(!sessions.contains_key(&chat_id));
-        }
-
-        // Test 2: Add inactive Claude session - should still be ignored
-        {
-            let mut sessions = bot_state.claude_sessions.lock().await;
-            sessions.insert(chat_id, ClaudeSession::new());
-        }
-
-        {
-            let sessions = bot_state.claude_sessions.lock().await;
-            let session = sessions.get(&chat_id);
-            assert!(session.is_some());
-            assert!(!session.unwrap().is_active);
-        }
-
-        // Test 3: Activate Claude session
-        {
-            let mut sessions = bot_state.claude_sessions.lock().await;
-            if let Some(session) = sessions.get_mut(&chat_id) {
-                session.is_active = true;
-            }
-        }
-
-        {
-            let sessions = bot_state.claude_sessions.lock().await;
-            let session = sessions.get(&chat_id);
-            assert!(session.is_some());
-            assert!(session.unwrap().is_active);
-        }
-    }
-
-    #[tokio::test]
-    async fn test_auth_session_priority_over_claude() {
-        let bot_state = create_test_bot_state();
-        let chat_id = 12345i64;
-
-        // Create both auth and Claude sessions
-        {
-            let mut claude_sessions = bot_state.claude_sessions.lock().await;
-            let mut claude_session = ClaudeSession::new();
-            claude_session.is_active = true;
-            claude_sessions.insert(chat_id, claude_session);
-        }
-
-        {
-            let mut auth_sessions = bot_state.auth_sessions.lock().await;
-            let (code_sender, _code_receiver) = mpsc::unbounded_channel();
-            let (cancel_sender, _cancel_receiver) = oneshot::channel();
-            let auth_session = AuthSession {
-                container_name: format!("coding-session-{}", chat_id),
-                code_sender,
-                cancel_sender,
-            };
-            auth_sessions.insert(chat_id, auth_session);
-        }
-
-        // Verify both sessions exist
-        {
-            let auth_sessions = bot_state.auth_sessions.lock().await;
-            let claude_sessions = bot_state.claude_sessions.lock().await;
-
-            assert!(auth_sessions.contains_key(&chat_id));
-            assert!(claude_sessions.contains_key(&chat_id));
-            assert!(claude_sessions.get(&chat_id).unwrap().is_active);
-        }
-
-        // Auth session should take priority (this is tested implicitly in the handler logic)
-    }
-
-    #[tokio::test]
-    async fn test_multiple_chat_session_isolation() {
-        let bot_state = create_test_bot_state();
-        let chat_ids = vec![11111i64, 22222i64, 33333i64];
-
-        // Create different session states for each chat
-        {
-            let mut claude_sessions = bot_state.claude_sessions.lock().await;
-
-            // Chat 1: Active Claude session
-            let mut session1 = ClaudeSession::new();
-            session1.is_active = true;
-            session1.conversation_id = Some("conv-1".to_string());
-            claude_sessions.insert(chat_ids[0], session1);
-
-            // Chat 2: Inactive Claude session
-            let session2 = ClaudeSession::new();
-            claude_sessions.insert(chat_ids[1], session2);
-
-            // Chat 3: No session (will be empty)
-        }
-
-        // Verify isolation
-        {
-            let claude_sessions = bot_state.claude_sessions.lock().await;
-
-            // Chat 1 should be active
-            let session1 = claude_sessions.get(&chat_ids[0]);
-            assert!(session1.is_some());
-            assert!(session1.unwrap().is_active);
-            assert_eq!(
-                session1.unwrap().conversation_id,
-                Some("conv-1".to_string())
-            );
-
-            // Chat 2 should be inactive
-            let session2 = claude_sessions.get(&chat_ids[1]);
-            assert!(session2.is_some());
-            assert!(!session2.unwrap().is_active);
-            assert!(session2.unwrap().conversation_id.is_none());
-
-            // Chat 3 should have no session
-            let session3 = claude_sessions.get(&chat_ids[2]);
-            assert!(session3.is_none());
-        }
-    }
-
-    #[tokio::test]
-    async fn test_conversation_id_retrieval() {
-        let bot_state = create_test_bot_state();
-        let chat_id = 12345i64;
-        let test_conversation_id = "test-conversation-uuid-123".to_string();
-
-        // Test with no session
-        {
-            let sessions = bot_state.claude_sessions.lock().await;
-            let conv_id = sessions
-                .get(&chat_id)
-                .and_then(|s| s.conversation_id.clone());
-            assert!(conv_id.is_none());
-        }
-
-        // Add session with conversation ID
-        {
-            let mut sessions = bot_state.claude_sessions.lock().await;
-            let mut session = ClaudeSession::new();
-            session.conversation_id = Some(test_conversation_id.clone());
-            session.is_active = true;
-            sessions.insert(chat_id, session);
-        }
-
-        // Retrieve conversation ID
-        {
-            let sessions = bot_state.claude_sessions.lock().await;
-            let conv_id = sessions
-                .get(&chat_id)
-                .and_then(|s| s.conversation_id.clone());
-            assert_eq!(conv_id, Some(test_conversation_id));
-        }
-    }
-}
-
 /// Handle callback queries from inline keyboard buttons
 pub async fn handle_callback_query(
     bot: Bot,
@@ -451,6 +332,7 @@ pub async fn handle_callback_query(
 
         if let Some(message) = &query.message {
             let chat_id = message.chat().id;
+            let user_id = query.from.id.0 as i64;
             log::debug!("Chat ID: {}", chat_id.0);
 
             match data.as_str() {
@@ -565,6 +447,49 @@ Please start a coding session \
                         .await?;
                     }
                 }
+                "start_clone_pending" => {
+                    log::debug!(
+                        "Handling start_clone_pending callback for chat {}",
+                        chat_id.0
+                    );
+                    let pending_clone = {
+                        let mut sessions = bot_state.claude_sessions.lock().await;
+                        sessions
+                            .get_mut(&chat_id.0)
+                            .and_then(|session| session.pending_clone.take())
+                    };
+
+                    match pending_clone {
+                        Some(repository) => {
+                            if let Err(e) = commands::start::handle_repository_clone_in_start(
+                                bot.clone(),
+                                chat_id,
+                                &bot_state,
+                                &repository,
+                            )
+                            .await
+                            {
+                                bot.send_message(
+                                    chat_id,
+                                    format!(
+                                        "❌ Failed to clone repository: {}",
+                                        escape_markdown_v2(&e.to_string())
+                                    ),
+                                )
+                                .parse_mode(ParseMode::MarkdownV2)
+                                .await?;
+                            }
+                        }
+                        None => {
+                            bot.send_message(
+                                chat_id,
+                                "⚠️ This clone request has expired\\. Please send the repository again\\.",
+                            )
+                            .parse_mode(ParseMode::MarkdownV2)
+                            .await?;
+                        }
+                    }
+                }
                 "manual_repo_entry" => {
                     log::debug!("Handling manual_repo_entry callback for chat {}", chat_id.0);
                     // Handle manual repository entry
@@ -605,6 +530,210 @@ Please start a coding session \
                         }
                     }
                 }
+                data if data.starts_with("repo_page:") => {
+                    log::debug!("Handling repo_page callback for chat {}", chat_id.0);
+                    if let Ok(page) = data
+                        .strip_prefix("repo_page:")
+                        .unwrap_or("")
+                        .parse::<usize>()
+                    {
+                        commands::repo_picker::handle_repo_page(
+                            bot,
+                            chat_id,
+                            message.id(),
+                            &bot_state,
+                            page,
+                        )
+                        .await?;
+                    }
+                }
+                data if data.starts_with("repo_pick:") => {
+                    log::debug!("Handling repo_pick callback for chat {}", chat_id.0);
+                    if let Ok(index) = data
+                        .strip_prefix("repo_pick:")
+                        .unwrap_or("")
+                        .parse::<usize>()
+                    {
+                        commands::repo_picker::handle_repo_pick(bot, chat_id, &bot_state, index)
+                            .await?;
+                    }
+                }
+                data if data.starts_with("history_resume:") => {
+                    let conversation_id = &data["history_resume:".len()..];
+                    commands::history::handle_history_resume(
+                        bot,
+                        chat_id,
+                        &bot_state,
+                        conversation_id,
+                    )
+                    .await?;
+                }
+                data if data.starts_with("repo_switch:") => {
+                    let target = &data["repo_switch:".len()..];
+                    commands::repo::handle_repo_switch(bot, chat_id, &bot_state, target).await?;
+                }
+                "repo_search" => {
+                    log::debug!("Handling repo_search callback for chat {}", chat_id.0);
+                    commands::repo_picker::handle_repo_search_prompt(bot, chat_id, &bot_state)
+                        .await?;
+                }
+                "repo_search_clear" => {
+                    log::debug!("Handling repo_search_clear callback for chat {}", chat_id.0);
+                    commands::repo_picker::handle_repo_search_clear(
+                        bot,
+                        chat_id,
+                        message.id(),
+                        &bot_state,
+                    )
+                    .await?;
+                }
+                data if data.starts_with("sync_resolve:") => {
+                    if let Ok(index) = data["sync_resolve:".len()..].parse::<usize>() {
+                        commands::sync::handle_sync_resolve(
+                            bot, chat_id, &bot_state, user_id, index,
+                        )
+                        .await?;
+                    }
+                }
+                data if data.starts_with("sync_accept:") => {
+                    if let Ok(index) = data["sync_accept:".len()..].parse::<usize>() {
+                        commands::sync::handle_sync_accept(bot, chat_id, &bot_state, index).await?;
+                    }
+                }
+                data if data.starts_with("sync_reject:") => {
+                    if let Ok(index) = data["sync_reject:".len()..].parse::<usize>() {
+                        commands::sync::handle_sync_reject(bot, chat_id, &bot_state, index).await?;
+                    }
+                }
+                "sync_abort" => {
+                    commands::sync::handle_sync_abort(bot, chat_id, &bot_state).await?;
+                }
+                data if data.starts_with("discard_toggle:") => {
+                    if let Ok(index) = data["discard_toggle:".len()..].parse::<usize>() {
+                        commands::discard::handle_discard_toggle(
+                            bot,
+                            chat_id,
+                            message.id(),
+                            &bot_state,
+                            Some(index),
+                        )
+                        .await?;
+                    }
+                }
+                "discard_all" => {
+                    commands::discard::handle_discard_toggle(
+                        bot,
+                        chat_id,
+                        message.id(),
+                        &bot_state,
+                        None,
+                    )
+                    .await?;
+                }
+                "discard_review" => {
+                    commands::discard::handle_discard_review(bot, chat_id, &bot_state).await?;
+                }
+                "discard_apply" => {
+                    commands::discard::handle_discard_apply(bot, chat_id, &bot_state).await?;
+                }
+                "discard_cancel" => {
+                    commands::discard::handle_discard_cancel(bot, chat_id, &bot_state).await?;
+                }
+                data if data.starts_with("log_show:") => {
+                    let sha = &data["log_show:".len()..];
+                    commands::git_log::handle_log_show(bot, chat_id, &bot_state, sha).await?;
+                }
+                "review_post" => {
+                    commands::review::handle_review_post(bot, chat_id, &bot_state).await?;
+                }
+                "ci_fix" => {
+                    log::debug!("Handling ci_fix callback for chat {}", chat_id.0);
+                    commands::ci::handle_ci_fix(bot, chat_id, &bot_state, user_id).await?;
+                }
+                data if data.starts_with("task_switch:") => {
+                    log::debug!("Handling task_switch callback for chat {}", chat_id.0);
+                    let name = data.strip_prefix("task_switch:").unwrap_or("");
+                    commands::task::handle_task_switch(bot, chat_id, &bot_state, name).await?;
+                }
+                data if data.starts_with("continue:") => {
+                    log::debug!("Handling continue callback for chat {}", chat_id.0);
+                    let conversation_id = data.strip_prefix("continue:").unwrap_or("");
+                    commands::limits::handle_continue(
+                        bot,
+                        chat_id,
+                        &bot_state,
+                        user_id,
+                        conversation_id,
+                    )
+                    .await?;
+                }
+                data if data.starts_with("undo:") => {
+                    log::debug!("Handling undo callback for chat {}", chat_id.0);
+                    let checkpoint_id = data.strip_prefix("undo:").unwrap_or("");
+                    commands::checkpoints::handle_undo_preview(
+                        bot,
+                        chat_id,
+                        &bot_state,
+                        Some(checkpoint_id),
+                    )
+                    .await?;
+                }
+                data if data.starts_with("undo_confirm:") => {
+                    log::debug!("Handling undo_confirm callback for chat {}", chat_id.0);
+                    let checkpoint_id = data.strip_prefix("undo_confirm:").unwrap_or("");
+                    commands::checkpoints::handle_undo_confirm(
+                        bot,
+                        chat_id,
+                        &bot_state,
+                        checkpoint_id,
+                    )
+                    .await?;
+                }
+                "undo_cancel" => {
+                    log::debug!("Handling undo_cancel callback for chat {}", chat_id.0);
+                    bot.send_message(chat_id, "👍 Undo cancelled\\. Nothing was changed\\.")
+                        .parse_mode(ParseMode::MarkdownV2)
+                        .await?;
+                }
+                data if data.starts_with("pr_review_toggle:") => {
+                    log::debug!("Handling pr_review_toggle callback for chat {}", chat_id.0);
+                    if let Ok(comment_id) = data
+                        .strip_prefix("pr_review_toggle:")
+                        .unwrap_or("")
+                        .parse::<u64>()
+                    {
+                        commands::pr_review::handle_pr_review_toggle(
+                            bot,
+                            chat_id,
+                            message.id(),
+                            &bot_state,
+                            comment_id,
+                        )
+                        .await?;
+                    }
+                }
+                "pr_review_address" => {
+                    log::debug!("Handling pr_review_address callback for chat {}", chat_id.0);
+                    commands::pr_review::handle_pr_review_address(
+                        bot, chat_id, &bot_state, user_id,
+                    )
+                    .await?;
+                }
+                "pr_review_push" | "pr_review_push_reply" => {
+                    log::debug!("Handling {} callback for chat {}", data, chat_id.0);
+                    commands::pr_review::handle_pr_review_push(
+                        bot,
+                        chat_id,
+                        &bot_state,
+                        user_id,
+                        data == "pr_review_push_reply",
+                    )
+                    .await?;
+                }
+                "pr_review_cancel" => {
+                    log::debug!("Handling pr_review_cancel callback for chat {}", chat_id.0);
+                    commands::pr_review::handle_pr_review_cancel(bot, chat_id, &bot_state).await?;
+                }
                 _ => {
                     log::debug!("Unknown callback data '{}' for chat {}", data, chat_id.0);
                     // Unknown callback data, already answered above
@@ -619,3 +748,191 @@ Please start a coding session \
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::bot::{
+        AuthSession, AuthSessions, BotState, ClaudeSession, ClaudeSessions, CostLedger, TokenStore,
+    };
+    use bollard::Docker;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::{mpsc, oneshot, Mutex};
+
+    fn create_test_bot_state() -> BotState {
+        // Create a mock Docker instance (won't be used in these tests)
+        let docker = Docker::connect_with_socket_defaults().unwrap();
+        let auth_sessions: AuthSessions = Arc::new(Mutex::new(HashMap::new()));
+        let claude_sessions: ClaudeSessions = Arc::new(Mutex::new(HashMap::new()));
+
+        BotState {
+            docker,
+            auth_sessions,
+            claude_sessions,
+            token_store: TokenStore::new(std::env::temp_dir().join("unused-tokens.json"), None),
+            cost_ledger: CostLedger::new(
+                std::env::temp_dir().join("unused-cost-ledger.json"),
+                Default::default(),
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_claude_session_priority_routing() {
+        let bot_state = create_test_bot_state();
+        let chat_id = 12345i64;
+
+        // Test 1: No active sessions - should be ignored (no error)
+        {
+            let sessions = bot_state.claude_sessions.lock().await;
+            assert!(!sessions.contains_key(&chat_id));
+        }
+
+        // Test 2: Add inactive Claude session - should still be ignored
+        {
+            let mut sessions = bot_state.claude_sessions.lock().await;
+            sessions.insert(chat_id, ClaudeSession::new());
+        }
+
+        {
+            let sessions = bot_state.claude_sessions.lock().await;
+            let session = sessions.get(&chat_id);
+            assert!(session.is_some());
+            assert!(!session.unwrap().is_active);
+        }
+
+        // Test 3: Activate Claude session
+        {
+            let mut sessions = bot_state.claude_sessions.lock().await;
+            if let Some(session) = sessions.get_mut(&chat_id) {
+                session.is_active = true;
+            }
+        }
+
+        {
+            let sessions = bot_state.claude_sessions.lock().await;
+            let session = sessions.get(&chat_id);
+            assert!(session.is_some());
+            assert!(session.unwrap().is_active);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_session_priority_over_claude() {
+        let bot_state = create_test_bot_state();
+        let chat_id = 12345i64;
+
+        // Create both auth and Claude sessions
+        {
+            let mut claude_sessions = bot_state.claude_sessions.lock().await;
+            let mut claude_session = ClaudeSession::new();
+            claude_session.is_active = true;
+            claude_sessions.insert(chat_id, claude_session);
+        }
+
+        {
+            let mut auth_sessions = bot_state.auth_sessions.lock().await;
+            let (code_sender, _code_receiver) = mpsc::unbounded_channel();
+            let (cancel_sender, _cancel_receiver) = oneshot::channel();
+            let auth_session = AuthSession {
+                container_name: format!("coding-session-{}", chat_id),
+                code_sender,
+                cancel_sender,
+            };
+            auth_sessions.insert(chat_id, auth_session);
+        }
+
+        // Verify both sessions exist
+        {
+            let auth_sessions = bot_state.auth_sessions.lock().await;
+            let claude_sessions = bot_state.claude_sessions.lock().await;
+
+            assert!(auth_sessions.contains_key(&chat_id));
+            assert!(claude_sessions.contains_key(&chat_id));
+            assert!(claude_sessions.get(&chat_id).unwrap().is_active);
+        }
+
+        // Auth session should take priority (this is tested implicitly in the handler logic)
+    }
+
+    #[tokio::test]
+    async fn test_multiple_chat_session_isolation() {
+        let bot_state = create_test_bot_state();
+        let chat_ids = [11111i64, 22222i64, 33333i64];
+
+        // Create different session states for each chat
+        {
+            let mut claude_sessions = bot_state.claude_sessions.lock().await;
+
+            // Chat 1: Active Claude session
+            let mut session1 = ClaudeSession::new();
+            session1.is_active = true;
+            session1.conversation_id = Some("conv-1".to_string());
+            claude_sessions.insert(chat_ids[0], session1);
+
+            // Chat 2: Inactive Claude session
+            let session2 = ClaudeSession::new();
+            claude_sessions.insert(chat_ids[1], session2);
+
+            // Chat 3: No session (will be empty)
+        }
+
+        // Verify isolation
+        {
+            let claude_sessions = bot_state.claude_sessions.lock().await;
+
+            // Chat 1 should be active
+            let session1 = claude_sessions.get(&chat_ids[0]);
+            assert!(session1.is_some());
+            assert!(session1.unwrap().is_active);
+            assert_eq!(
+                session1.unwrap().conversation_id,
+                Some("conv-1".to_string())
+            );
+
+            // Chat 2 should be inactive
+            let session2 = claude_sessions.get(&chat_ids[1]);
+            assert!(session2.is_some());
+            assert!(!session2.unwrap().is_active);
+            assert!(session2.unwrap().conversation_id.is_none());
+
+            // Chat 3 should have no session
+            let session3 = claude_sessions.get(&chat_ids[2]);
+            assert!(session3.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conversation_id_retrieval() {
+        let bot_state = create_test_bot_state();
+        let chat_id = 12345i64;
+        let test_conversation_id = "test-conversation-uuid-123".to_string();
+
+        // Test with no session
+        {
+            let sessions = bot_state.claude_sessions.lock().await;
+            let conv_id = sessions
+                .get(&chat_id)
+                .and_then(|s| s.conversation_id.clone());
+            assert!(conv_id.is_none());
+        }
+
+        // Add session with conversation ID
+        {
+            let mut sessions = bot_state.claude_sessions.lock().await;
+            let mut session = ClaudeSession::new();
+            session.conversation_id = Some(test_conversation_id.clone());
+            session.is_active = true;
+            sessions.insert(chat_id, session);
+        }
+
+        // Retrieve conversation ID
+        {
+            let sessions = bot_state.claude_sessions.lock().await;
+            let conv_id = sessions
+                .get(&chat_id)
+                .and_then(|s| s.conversation_id.clone());
+            assert_eq!(conv_id, Some(test_conversation_id));
+        }
+    }
+}
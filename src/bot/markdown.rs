@@ -42,6 +42,15 @@ pub fn truncate_if_needed(text: &str) -> (String, bool) {
     (format!("{}{}", truncated, truncation_notice), true)
 }
 
+/// Upper-case the first letter, for descriptions that start a sentence
+pub fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,4 +170,13 @@ mod tests {
         // Just verify that the truncation worked correctly
         assert!(truncated.len() < text.len());
     }
+
+    #[test]
+    fn test_capitalize() {
+        assert_eq!(
+            capitalize("the repository's CLAUDE.md"),
+            "The repository's CLAUDE.md"
+        );
+        assert_eq!(capitalize(""), "");
+    }
 }
@@ -1,12 +1,17 @@
+pub mod atomic_file;
 pub mod auth_session;
 pub mod claude_session;
+pub mod cost_ledger;
 pub mod handlers;
 pub mod markdown;
 pub mod state;
+pub mod token_store;
 
 // Re-export commonly used items
 pub use auth_session::{AuthSession, AuthSessions};
 pub use claude_session::{ClaudeSession, ClaudeSessions};
+pub use cost_ledger::CostLedger;
 pub use handlers::{handle_auth_state_updates, handle_callback_query, handle_text_message};
 pub use markdown::escape_markdown_v2;
 pub use state::BotState;
+pub use token_store::TokenStore;
@@ -1,5 +1,7 @@
 use super::auth_session::AuthSessions;
 use super::claude_session::ClaudeSessions;
+use super::cost_ledger::CostLedger;
+use super::token_store::TokenStore;
 use bollard::Docker;
 
 #[derive(Clone)]
@@ -7,4 +9,6 @@ pub struct BotState {
     pub docker: Docker,
     pub auth_sessions: AuthSessions,
     pub claude_sessions: ClaudeSessions,
+    pub token_store: TokenStore,
+    pub cost_ledger: CostLedger,
 }
@@ -0,0 +1,260 @@
+use super::atomic_file::write_atomically;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Environment variable holding the secret that encrypts stored tokens
+pub const TOKEN_ENCRYPTION_KEY_ENV: &str = "GITHUB_TOKEN_ENCRYPTION_KEY";
+
+/// Environment variable overriding where encrypted tokens are stored
+pub const TOKEN_STORE_PATH_ENV: &str = "GITHUB_TOKEN_STORE_PATH";
+
+const DEFAULT_TOKEN_STORE_PATH: &str = "github_tokens.json";
+
+/// AES-GCM nonce length in bytes
+const NONCE_LENGTH: usize = 12;
+
+/// AES-GCM authentication tag length in bytes
+const TAG_LENGTH: usize = 16;
+
+/// Errors that can occur while storing or loading GitHub tokens
+#[derive(Debug, thiserror::Error)]
+pub enum TokenStoreError {
+    #[error("Token storage is disabled: set {TOKEN_ENCRYPTION_KEY_ENV} to enable it")]
+    Disabled,
+    #[error("Encryption error: {0}")]
+    CryptoError(#[from] openssl::error::ErrorStack),
+    #[error("Stored token is corrupted")]
+    Corrupted,
+    #[error("JSON serialization error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("File I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// A token encrypted with AES-256-GCM, bound to its owner's user ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EncryptedToken {
+    nonce: String,
+    ciphertext: String,
+    tag: String,
+}
+
+/// Per-user GitHub tokens, encrypted at rest in a JSON file on the bot host
+///
+/// Tokens are injected into their owner's coding container only, as `GH_TOKEN`.
+#[derive(Clone)]
+pub struct TokenStore {
+    path: PathBuf,
+    key: Option<[u8; 32]>,
+    tokens: Arc<Mutex<HashMap<i64, EncryptedToken>>>,
+}
+
+impl TokenStore {
+    /// Open the store configured through the environment
+    pub fn from_env() -> Self {
+        let path = std::env::var(TOKEN_STORE_PATH_ENV)
+            .unwrap_or_else(|_| DEFAULT_TOKEN_STORE_PATH.to_string());
+        let secret = std::env::var(TOKEN_ENCRYPTION_KEY_ENV).ok();
+
+        if secret.is_none() {
+            log::warn!(
+                "{} is not set, per-user GitHub tokens are disabled",
+                TOKEN_ENCRYPTION_KEY_ENV
+            );
+        }
+
+        Self::new(path, secret.as_deref())
+    }
+
+    /// Open the store at `path`; without a secret the store is disabled
+    pub fn new(path: impl Into<PathBuf>, secret: Option<&str>) -> Self {
+        let path = path.into();
+        let tokens = match load_tokens(&path) {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                log::warn!(
+                    "Failed to load GitHub tokens from {}: {}",
+                    path.display(),
+                    e
+                );
+                HashMap::new()
+            }
+        };
+
+        Self {
+            path,
+            key: secret.map(|secret| Sha256::digest(secret.as_bytes()).into()),
+            tokens: Arc::new(Mutex::new(tokens)),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Encrypt and persist a user's token, replacing any previous one
+    pub async fn set(&self, user_id: i64, token: &str) -> Result<(), TokenStoreError> {
+        let key = self.key.as_ref().ok_or(TokenStoreError::Disabled)?;
+        let encrypted = encrypt_token(key, user_id, token)?;
+
+        let mut tokens = self.tokens.lock().await;
+        tokens.insert(user_id, encrypted);
+        save_tokens(&self.path, &tokens)
+    }
+
+    /// Decrypt a user's token, if they stored one
+    pub async fn get(&self, user_id: i64) -> Result<Option<String>, TokenStoreError> {
+        let Some(key) = self.key.as_ref() else {
+            return Ok(None);
+        };
+
+        let tokens = self.tokens.lock().await;
+        tokens
+            .get(&user_id)
+            .map(|encrypted| decrypt_token(key, user_id, encrypted))
+            .transpose()
+    }
+
+    /// Delete a user's token; returns whether one was stored
+    pub async fn remove(&self, user_id: i64) -> Result<bool, TokenStoreError> {
+        let mut tokens = self.tokens.lock().await;
+        if tokens.remove(&user_id).is_none() {
+            return Ok(false);
+        }
+
+        save_tokens(&self.path, &tokens)?;
+        Ok(true)
+    }
+}
+
+fn load_tokens(path: &Path) -> Result<HashMap<i64, EncryptedToken>, TokenStoreError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_tokens(path: &Path, tokens: &HashMap<i64, EncryptedToken>) -> Result<(), TokenStoreError> {
+    let content = serde_json::to_string_pretty(tokens)?;
+    write_atomically(path, content.as_bytes(), Some(0o600))?;
+    Ok(())
+}
+
+/// Encrypt a token, using the user ID as associated data so it can't be moved to another user
+fn encrypt_token(
+    key: &[u8; 32],
+    user_id: i64,
+    token: &str,
+) -> Result<EncryptedToken, TokenStoreError> {
+    let nonce: [u8; NONCE_LENGTH] = rand::random();
+    let mut tag = [0u8; TAG_LENGTH];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(&nonce),
+        &user_id.to_be_bytes(),
+        token.as_bytes(),
+        &mut tag,
+    )?;
+
+    Ok(EncryptedToken {
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+        tag: STANDARD.encode(tag),
+    })
+}
+
+fn decrypt_token(
+    key: &[u8; 32],
+    user_id: i64,
+    encrypted: &EncryptedToken,
+) -> Result<String, TokenStoreError> {
+    let decode = |value: &str| {
+        STANDARD
+            .decode(value)
+            .map_err(|_| TokenStoreError::Corrupted)
+    };
+    let nonce = decode(&encrypted.nonce)?;
+    let ciphertext = decode(&encrypted.ciphertext)?;
+    let tag = decode(&encrypted.tag)?;
+
+    let plaintext = decrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(&nonce),
+        &user_id.to_be_bytes(),
+        &ciphertext,
+        &tag,
+    )
+    .map_err(|_| TokenStoreError::Corrupted)?;
+
+    String::from_utf8(plaintext).map_err(|_| TokenStoreError::Corrupted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store_path() -> PathBuf {
+        std::env::temp_dir().join(format!("github-tokens-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_token_store_round_trip() {
+        let path = temp_store_path();
+        let store = TokenStore::new(&path, Some("secret"));
+
+        store.set(42, "ghp_example").await.unwrap();
+        assert_eq!(store.get(42).await.unwrap().as_deref(), Some("ghp_example"));
+        assert_eq!(store.get(7).await.unwrap(), None);
+
+        // Tokens are not stored in plain text and survive a restart
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("ghp_example"));
+        let reopened = TokenStore::new(&path, Some("secret"));
+        assert_eq!(
+            reopened.get(42).await.unwrap().as_deref(),
+            Some("ghp_example")
+        );
+
+        // A different secret can't decrypt them
+        let wrong_key = TokenStore::new(&path, Some("other"));
+        assert!(matches!(
+            wrong_key.get(42).await,
+            Err(TokenStoreError::Corrupted)
+        ));
+
+        assert!(store.remove(42).await.unwrap());
+        assert!(!store.remove(42).await.unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_token_bound_to_user() {
+        let key = [7u8; 32];
+        let encrypted = encrypt_token(&key, 1, "ghp_example").unwrap();
+
+        assert_eq!(decrypt_token(&key, 1, &encrypted).unwrap(), "ghp_example");
+        assert!(decrypt_token(&key, 2, &encrypted).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_store() {
+        let store = TokenStore::new(temp_store_path(), None);
+
+        assert!(!store.is_enabled());
+        assert!(matches!(
+            store.set(1, "ghp_example").await,
+            Err(TokenStoreError::Disabled)
+        ));
+        assert_eq!(store.get(1).await.unwrap(), None);
+    }
+}
@@ -4,6 +4,7 @@ use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use super::executor::CommandExecutor;
+use super::interactive::InteractiveClaudeProcess;
 use super::message_parser::{ClaudeMessageParser, ParseResult, ParsedClaudeMessage};
 
 /// Claude command execution functionality
@@ -30,7 +31,29 @@ impl ClaudeCommandExecutor {
         );
 
         let cmd_args = self.build_command_args(prompt, conversation_id);
+        self.execute_command_args(cmd_args).await
+    }
+
+    /// Execute a Claude prompt in a new conversation branched from `conversation_id`
+    pub async fn execute_claude_fork(
+        &self,
+        prompt: &str,
+        conversation_id: &str,
+    ) -> Result<ClaudeExecutionResult, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!(
+            "Executing Claude prompt: '{}' forked from conversation_id: {}",
+            prompt,
+            conversation_id
+        );
 
+        let cmd_args = self.build_fork_command_args(prompt, conversation_id);
+        self.execute_command_args(cmd_args).await
+    }
+
+    async fn execute_command_args(
+        &self,
+        cmd_args: Vec<String>,
+    ) -> Result<ClaudeExecutionResult, Box<dyn std::error::Error + Send + Sync>> {
         // Try streaming execution first, fallback to batch processing
         match self.executor.exec_streaming_command(cmd_args.clone()).await {
             Ok(string_stream) => {
@@ -52,13 +75,40 @@ impl ClaudeCommandExecutor {
 
     /// Build Claude command arguments for execution
     pub fn build_command_args(&self, prompt: &str, conversation_id: Option<&str>) -> Vec<String> {
-        let mut cmd_args = vec![
-            "claude".to_string(),
+        let mut cmd_args = vec!["claude".to_string()];
+
+        // Both flags take several values, so keep them ahead of the other options; the
+        // prompt would otherwise be read as one of their values
+        if let Some(mcp_config) = &self.executor.config().mcp_config {
+            cmd_args.push("--mcp-config".to_string());
+            cmd_args.push(mcp_config.path.clone());
+            if !mcp_config.servers.is_empty() {
+                let allowed: Vec<String> = mcp_config
+                    .servers
+                    .iter()
+                    .map(|server| format!("mcp__{}", server))
+                    .collect();
+                cmd_args.push("--allowedTools".to_string());
+                cmd_args.push(allowed.join(","));
+            }
+        }
+
+        cmd_args.extend([
             "--print".to_string(),
             "--verbose".to_string(),
             "--output-format".to_string(),
             "stream-json".to_string(),
-        ];
+        ]);
+
+        if let Some(system_prompt) = &self.executor.config().append_system_prompt {
+            cmd_args.push("--append-system-prompt".to_string());
+            cmd_args.push(system_prompt.clone());
+        }
+
+        if let Some(max_turns) = self.executor.config().max_turns {
+            cmd_args.push("--max-turns".to_string());
+            cmd_args.push(max_turns.to_string());
+        }
 
         if let Some(conversation_id) = conversation_id {
             log::info!(
@@ -77,6 +127,49 @@ impl ClaudeCommandExecutor {
         cmd_args
     }
 
+    /// Build Claude command arguments that resume `conversation_id` under a new session id,
+    /// leaving the original conversation as it was
+    pub fn build_fork_command_args(&self, prompt: &str, conversation_id: &str) -> Vec<String> {
+        let mut cmd_args = self.build_command_args(prompt, Some(conversation_id));
+        cmd_args.insert(cmd_args.len() - 1, "--fork-session".to_string());
+        cmd_args
+    }
+
+    /// Build arguments for a long-lived Claude process that reads its prompts from stdin
+    pub fn build_interactive_command_args(&self, conversation_id: Option<&str>) -> Vec<String> {
+        let mut cmd_args = self.build_command_args("", conversation_id);
+        cmd_args.pop();
+        cmd_args.push("--input-format".to_string());
+        cmd_args.push("stream-json".to_string());
+        cmd_args
+    }
+
+    /// What an interactive process must have been started with to take this client's prompts
+    pub fn interactive_launch_key(&self) -> Vec<String> {
+        let mut key = self.build_interactive_command_args(None);
+        key.extend(self.executor.config().working_directory.clone());
+        key
+    }
+
+    /// Start a long-lived Claude process in `conversation_id`, or in a new conversation
+    pub async fn start_interactive(
+        &self,
+        conversation_id: Option<&str>,
+    ) -> Result<InteractiveClaudeProcess, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!(
+            "Starting interactive Claude process with conversation_id: {:?}",
+            conversation_id
+        );
+
+        let cmd_args = self.build_interactive_command_args(conversation_id);
+        let exec = self.executor.exec_interactive_command(cmd_args).await?;
+        Ok(InteractiveClaudeProcess::new(
+            exec,
+            self.interactive_launch_key(),
+            conversation_id.map(str::to_string),
+        ))
+    }
+
     /// Create a stream of parsed Claude messages from a string stream
     fn create_parsed_stream(
         &self,
@@ -90,8 +183,11 @@ impl ClaudeCommandExecutor {
                 match line_result {
                     Ok(line) => {
                         match ClaudeMessageParser::parse_line(&line) {
-                            ParseResult::Message(parsed) => {
-                                if tx.send(Ok(parsed)).is_err() {
+                            ParseResult::Messages(messages) => {
+                                let sent = messages
+                                    .into_iter()
+                                    .all(|parsed| tx.send(Ok(parsed)).is_ok());
+                                if !sent {
                                     log::debug!("Receiver dropped, stopping parsed stream");
                                     break;
                                 }
@@ -180,4 +276,154 @@ mod tests {
 
         assert_eq!(args, expected);
     }
+
+    #[test]
+    fn test_build_fork_command_args() {
+        let executor = CommandExecutor::new(
+            bollard::Docker::connect_with_local_defaults().unwrap(),
+            "test".to_string(),
+            super::super::config::ClaudeCodeConfig::default(),
+        );
+        let claude_executor = ClaudeCommandExecutor::new(executor);
+
+        let prompt = "Try a different approach";
+        let conversation_id = "test-conversation-123";
+        let args = claude_executor.build_fork_command_args(prompt, conversation_id);
+
+        let expected = vec![
+            "claude",
+            "--print",
+            "--verbose",
+            "--output-format",
+            "stream-json",
+            "--resume",
+            conversation_id,
+            "--fork-session",
+            prompt,
+        ];
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn test_build_command_args_with_system_prompt() {
+        let config = super::super::config::ClaudeCodeConfig {
+            append_system_prompt: Some("Answer in French".to_string()),
+            ..Default::default()
+        };
+        let executor = CommandExecutor::new(
+            bollard::Docker::connect_with_local_defaults().unwrap(),
+            "test".to_string(),
+            config,
+        );
+        let claude_executor = ClaudeCommandExecutor::new(executor);
+
+        let prompt = "Explain this function";
+        let args = claude_executor.build_command_args(prompt, None);
+
+        let expected = vec![
+            "claude",
+            "--print",
+            "--verbose",
+            "--output-format",
+            "stream-json",
+            "--append-system-prompt",
+            "Answer in French",
+            prompt,
+        ];
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn test_build_command_args_with_mcp_config() {
+        let config = super::super::config::ClaudeCodeConfig {
+            mcp_config: Some(super::super::config::McpConfig {
+                path: "/root/.claude/mcp-servers.json".to_string(),
+                servers: vec!["github".to_string(), "docs".to_string()],
+            }),
+            ..Default::default()
+        };
+        let executor = CommandExecutor::new(
+            bollard::Docker::connect_with_local_defaults().unwrap(),
+            "test".to_string(),
+            config,
+        );
+        let claude_executor = ClaudeCommandExecutor::new(executor);
+
+        let prompt = "List my open issues";
+        let args = claude_executor.build_command_args(prompt, None);
+
+        let expected = vec![
+            "claude",
+            "--mcp-config",
+            "/root/.claude/mcp-servers.json",
+            "--allowedTools",
+            "mcp__github,mcp__docs",
+            "--print",
+            "--verbose",
+            "--output-format",
+            "stream-json",
+            prompt,
+        ];
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn test_build_interactive_command_args() {
+        let executor = CommandExecutor::new(
+            bollard::Docker::connect_with_local_defaults().unwrap(),
+            "test".to_string(),
+            super::super::config::ClaudeCodeConfig::default(),
+        );
+        let claude_executor = ClaudeCommandExecutor::new(executor);
+
+        let args = claude_executor.build_interactive_command_args(Some("conversation-1"));
+
+        let expected = vec![
+            "claude",
+            "--print",
+            "--verbose",
+            "--output-format",
+            "stream-json",
+            "--resume",
+            "conversation-1",
+            "--input-format",
+            "stream-json",
+        ];
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn test_build_command_args_with_max_turns() {
+        let config = super::super::config::ClaudeCodeConfig {
+            max_turns: Some(25),
+            ..Default::default()
+        };
+        let executor = CommandExecutor::new(
+            bollard::Docker::connect_with_local_defaults().unwrap(),
+            "test".to_string(),
+            config,
+        );
+        let claude_executor = ClaudeCommandExecutor::new(executor);
+
+        let args = claude_executor.build_command_args("Fix the build", Some("conversation-1"));
+
+        let expected = vec![
+            "claude",
+            "--print",
+            "--verbose",
+            "--output-format",
+            "stream-json",
+            "--max-turns",
+            "25",
+            "--resume",
+            "conversation-1",
+            "Fix the build",
+        ];
+
+        assert_eq!(args, expected);
+    }
 }
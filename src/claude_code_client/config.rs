@@ -7,6 +7,12 @@ pub struct ClaudeCodeConfig {
     pub max_tokens: Option<u32>,
     pub temperature: Option<f32>,
     pub working_directory: Option<String>,
+    /// Extra instructions appended to Claude's system prompt
+    pub append_system_prompt: Option<String>,
+    /// MCP servers to start for Claude
+    pub mcp_config: Option<McpConfig>,
+    /// Most agentic turns Claude may take in one run
+    pub max_turns: Option<u32>,
     /// OAuth configuration for Claude authentication
     pub oauth_config: OAuthConfig,
 }
@@ -18,7 +24,19 @@ impl Default for ClaudeCodeConfig {
             max_tokens: None,
             temperature: None,
             working_directory: Some("/workspace".to_string()),
+            append_system_prompt: None,
+            mcp_config: None,
+            max_turns: None,
             oauth_config: OAuthConfig::default(),
         }
     }
 }
+
+/// MCP servers made available to Claude through `--mcp-config`
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpConfig {
+    /// Path of the MCP configuration file inside the container
+    pub path: String,
+    /// Names of the configured servers, whose tools Claude may use without asking
+    pub servers: Vec<String>,
+}
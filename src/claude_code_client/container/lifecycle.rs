@@ -18,19 +18,76 @@ use super::volume::{create_auth_mounts, ensure_user_volume};
 // Import file operations for container file management
 use super::file_ops::container_put_file;
 
+/// GitHub's published SSH host keys, so SSH clones from GitHub work without trusting a key
+/// on first contact
+const GITHUB_KNOWN_HOSTS: &str = "\
+github.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl\n\
+github.com ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBEmKSENjQEezOmxkZMy7opKgwFB9nkt5YRrYMjNuG5N87uRgg6CLrbo5wAdT/y6v0mKV0U2w0WZ2YB/++Tpockg=\n\
+github.com ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABgQCj7ndNxQowgcQnjshcLrqPEiiphnt+VTTvDP6mHBL9j1aNUkY4Ue1gvwnGLVlOhGeYrnZaMgRK6+PKCUXaDbC7qtbW8gIkhL7aGCsOr/C56SJMy/BCZfxd1nWzAOxSDPgVsmerOBYfNqltV9/hWCqBywINIR+5dIg6JTJ72pcEpEjcYgXkE2YEFXV1JHnsKgbLWNlhScqb2UmyRkQyytRLtL+38TGxkxCflmO+5Z8CSSNY7GidjMIZ7Q4zMjA2n1nGrlTDkzwDCsw+wqFPGQA179cnfGWOWRVruj16z6XyvxvjJwbz0wQZ75XK5tKSb7FNyeIEs4TT4jk+S4dhPeAUC5y+bDYirYgM4GC7uEnztnZyaVWQ7B381AK4Qdrwt51ZqExKbQpTUNn+EjqoTwvqNj4kqx5QUCI0ThS/YkOxJCXmPUWZbhjpCg56i+2aB6CmK2JGhn57K5mj0MNdBXA4/WnwH6XoPWJzK5Nyu2zB3nAZp+S5hpQs+p1vN1/wsjk=\n";
+
 /// Configuration for coding container behavior
-#[derive(Debug, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct CodingContainerConfig {
     pub persistent_volume_key: Option<String>,
+    /// The user's own GitHub token, used instead of the global `GH_TOKEN`
+    pub github_token: Option<String>,
+}
+
+impl std::fmt::Debug for CodingContainerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CodingContainerConfig")
+            .field("persistent_volume_key", &self.persistent_volume_key)
+            .field(
+                "github_token",
+                &self.github_token.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
 }
 
 /// Container image used by the main application
 /// This is the Claude Code runtime image that provides multi-language development environment with Claude Code pre-installed
 pub const MAIN_CONTAINER_IMAGE: &str = "ghcr.io/goniz/telegram-claude-code-runtime:main";
 
+/// Per-user git configuration on the persistent volume (identity, commit preferences)
+/// It is included from the global git config, so its values override the defaults
+pub const USER_GITCONFIG_PATH: &str = "/volume_data/gitconfig";
+
+/// Per-user SSH key used to sign commits, stored on the persistent volume
+pub const SSH_SIGNING_KEY_PATH: &str = "/volume_data/signing/id_signing";
+
+/// Enables commit signing with whichever key the user stored on the volume.
+/// An SSH signing key takes precedence over GPG secret keys in the linked keyring.
+const CONFIGURE_SIGNING_SCRIPT: &str = r#"
+ssh_key="$1"
+if [ -f "$ssh_key" ]; then
+    chmod 600 "$ssh_key"
+    git config --global gpg.format ssh
+    git config --global user.signingkey "$ssh_key"
+    git config --global commit.gpgsign true
+    git config --global tag.gpgsign true
+    echo "ssh $ssh_key"
+    exit 0
+fi
+fingerprint=$(gpg --batch --with-colons --list-secret-keys 2>/dev/null | awk -F: '$1 == "fpr" { print $10; exit }')
+if [ -n "$fingerprint" ]; then
+    git config --global gpg.format openpgp
+    git config --global user.signingkey "$fingerprint"
+    git config --global commit.gpgsign true
+    git config --global tag.gpgsign true
+    echo "gpg $fingerprint"
+    exit 0
+fi
+for key in gpg.format user.signingkey commit.gpgsign tag.gpgsign; do
+    git config --global --unset-all "$key" || true
+done
+echo none
+"#;
+
 /// Prepare environment variables for container creation with dynamic GH_TOKEN support
-/// Includes common development environment variables and optionally GH_TOKEN
-fn prepare_container_env_vars_dynamic() -> Vec<String> {
+/// Includes common development environment variables and optionally GH_TOKEN, preferring
+/// the user's own token over the global one
+fn prepare_container_env_vars_dynamic(github_token: Option<&str>) -> Vec<String> {
     let mut env_vars = vec![
         "CODEX_ENV_PYTHON_VERSION=3.12".to_string(),
         "CODEX_ENV_NODE_VERSION=22".to_string(),
@@ -38,7 +95,9 @@ fn prepare_container_env_vars_dynamic() -> Vec<String> {
         "CODEX_ENV_GO_VERSION=1.23.8".to_string(),
     ];
 
-    if let Ok(gh_token) = std::env::var("GH_TOKEN") {
+    if let Some(gh_token) = github_token {
+        env_vars.push(format!("GH_TOKEN={}", gh_token));
+    } else if let Ok(gh_token) = std::env::var("GH_TOKEN") {
         env_vars.push(format!("GH_TOKEN={}", gh_token));
     }
 
@@ -79,7 +138,8 @@ async fn init_claude_configuration(
 }
 
 /// Initialize git configuration in the container
-/// This sets up the git user email and name for commits
+/// This sets up the default git user email and name for commits and includes the
+/// per-user configuration stored on the volume
 async fn init_git_configuration(
     docker: &Docker,
     container_id: &str,
@@ -116,10 +176,52 @@ async fn init_git_configuration(
     .await
     .map_err(|e| format!("Failed to set git name: {}", e))?;
 
+    // Include the per-user config last so a user's own identity wins over the defaults
+    exec_command_in_container(
+        docker,
+        container_id,
+        vec![
+            "git".to_string(),
+            "config".to_string(),
+            "--global".to_string(),
+            "include.path".to_string(),
+            USER_GITCONFIG_PATH.to_string(),
+        ],
+    )
+    .await
+    .map_err(|e| format!("Failed to include user git configuration: {}", e))?;
+
+    let signing = configure_commit_signing(docker, container_id)
+        .await
+        .map_err(|e| format!("Failed to configure commit signing: {}", e))?;
+    log::info!("Commit signing: {}", signing);
+
     log::info!("Git configuration initialization completed");
     Ok(())
 }
 
+/// Configure git to sign commits with the user's stored signing key, if any
+///
+/// Returns `ssh <key path>`, `gpg <fingerprint>` or `none` describing the key in use.
+/// Signing is switched off again when no key is present.
+pub async fn configure_commit_signing(
+    docker: &Docker,
+    container_id: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    exec_command_in_container(
+        docker,
+        container_id,
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            CONFIGURE_SIGNING_SCRIPT.to_string(),
+            "configure-signing".to_string(),
+            SSH_SIGNING_KEY_PATH.to_string(),
+        ],
+    )
+    .await
+}
+
 /// Initialize Claude settings.json file
 /// This creates the settings.json file with proper tool permissions
 async fn init_claude_settings(
@@ -195,6 +297,30 @@ async fn init_volume_structure(
             "-p".to_string(),
             "/volume_data/gh".to_string(),
         ],
+        vec![
+            "mkdir".to_string(),
+            "-p".to_string(),
+            "/volume_data/ssh".to_string(),
+        ],
+        // ssh refuses keys in group/world readable directories
+        vec![
+            "chmod".to_string(),
+            "700".to_string(),
+            "/volume_data/ssh".to_string(),
+        ],
+        // Commit signing keys: an SSH key file and a GnuPG home for GPG keys
+        vec![
+            "mkdir".to_string(),
+            "-p".to_string(),
+            "/volume_data/signing".to_string(),
+            "/volume_data/gnupg".to_string(),
+        ],
+        vec![
+            "chmod".to_string(),
+            "700".to_string(),
+            "/volume_data/signing".to_string(),
+            "/volume_data/gnupg".to_string(),
+        ],
         // Create parent directories for symlinks
         vec![
             "mkdir".to_string(),
@@ -212,6 +338,24 @@ async fn init_volume_structure(
             "-rf".to_string(),
             "/root/.config/gh".to_string(),
         ],
+        vec![
+            "rm".to_string(),
+            "-rf".to_string(),
+            "/root/.ssh".to_string(),
+        ],
+        vec![
+            "rm".to_string(),
+            "-rf".to_string(),
+            "/root/.gnupg".to_string(),
+        ],
+        // Trust GitHub's host keys; keys of other git hosts have to be added deliberately
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "grep -qs '^github.com ' /volume_data/ssh/known_hosts || printf '%s' \"$1\" >> /volume_data/ssh/known_hosts".to_string(),
+            "sh".to_string(),
+            GITHUB_KNOWN_HOSTS.to_string(),
+        ],
     ];
 
     for command in basic_commands {
@@ -280,6 +424,18 @@ async fn init_volume_structure(
             "/volume_data/claude.json".to_string(),
             "/root/.claude.json".to_string(),
         ],
+        vec![
+            "ln".to_string(),
+            "-sf".to_string(),
+            "/volume_data/ssh".to_string(),
+            "/root/.ssh".to_string(),
+        ],
+        vec![
+            "ln".to_string(),
+            "-sf".to_string(),
+            "/volume_data/gnupg".to_string(),
+            "/root/.gnupg".to_string(),
+        ],
     ];
 
     for command in symlink_commands {
@@ -436,7 +592,7 @@ pub async fn start_coding_session(
     };
 
     // Prepare environment variables for the container
-    let env_vars = prepare_container_env_vars_dynamic();
+    let env_vars = prepare_container_env_vars_dynamic(container_config.github_token.as_deref());
 
     let config = ContainerCreateBody {
         image: Some(MAIN_CONTAINER_IMAGE.to_string()),
@@ -478,9 +634,6 @@ pub async fn start_coding_session(
     // tries to copy /root/.claude.json which is created by this function
     init_claude_configuration(docker, &container.id).await?;
 
-    // Initialize git configuration for commits (always needed for git operations)
-    init_git_configuration(docker, &container.id).await?;
-
     // Initialize volume structure for authentication persistence only if using persistent volumes
     if container_config.persistent_volume_key.is_some() {
         init_volume_structure(docker, &container.id).await?;
@@ -489,6 +642,10 @@ pub async fn start_coding_session(
         init_claude_settings(docker, &container.id, "/root/.claude").await?;
     }
 
+    // Initialize git configuration for commits (always needed for git operations)
+    // This comes after the volume setup so signing keys stored there are picked up
+    init_git_configuration(docker, &container.id).await?;
+
     // Create Claude Code client (Claude Code is pre-installed in the runtime image)
     let claude_client = ClaudeCodeClient::new(docker.clone(), container.id.clone(), claude_config);
 
@@ -563,7 +720,7 @@ pub async fn create_test_container(
     };
 
     // Prepare environment variables for the container
-    let env_vars = prepare_container_env_vars_dynamic();
+    let env_vars = prepare_container_env_vars_dynamic(None);
 
     let config = ContainerCreateBody {
         image: Some(MAIN_CONTAINER_IMAGE.to_string()),
@@ -10,9 +10,10 @@ pub mod volume;
 // Re-export commonly used functions for convenience
 pub use file_ops::{container_get_file, container_put_file};
 pub use lifecycle::{
-    clear_all_session_containers, clear_coding_session, create_test_container,
-    exec_command_in_container, start_coding_session, wait_for_container_ready,
-    CodingContainerConfig, MAIN_CONTAINER_IMAGE,
+    clear_all_session_containers, clear_coding_session, configure_commit_signing,
+    create_test_container, exec_command_in_container, start_coding_session,
+    wait_for_container_ready, CodingContainerConfig, MAIN_CONTAINER_IMAGE, SSH_SIGNING_KEY_PATH,
+    USER_GITCONFIG_PATH,
 };
 pub use volume::{
     create_auth_mounts, ensure_user_volume, generate_volume_name, validate_volume_key,
@@ -1,6 +1,7 @@
 // Import lifecycle, volume, and file operations from the container module
 pub use super::container::{
-    clear_all_session_containers, clear_coding_session, container_get_file, container_put_file,
-    create_test_container, exec_command_in_container, start_coding_session,
-    wait_for_container_ready, CodingContainerConfig, MAIN_CONTAINER_IMAGE,
+    clear_all_session_containers, clear_coding_session, configure_commit_signing,
+    container_get_file, container_put_file, create_test_container, exec_command_in_container,
+    start_coding_session, wait_for_container_ready, CodingContainerConfig, MAIN_CONTAINER_IMAGE,
+    SSH_SIGNING_KEY_PATH, USER_GITCONFIG_PATH,
 };
@@ -2,6 +2,7 @@ use bollard::exec::{CreateExecOptions, StartExecOptions};
 use bollard::Docker;
 use futures_util::{Stream, StreamExt};
 use std::pin::Pin;
+use tokio::io::AsyncWrite;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -24,6 +25,10 @@ impl CommandExecutor {
         }
     }
 
+    pub fn config(&self) -> &ClaudeCodeConfig {
+        &self.config
+    }
+
     /// Convert command vector to bash -l -c format with proper shell escaping
     fn prepare_bash_command(&self, command: Vec<String>) -> Vec<String> {
         if command.is_empty() {
@@ -55,22 +60,15 @@ impl CommandExecutor {
         ]
     }
 
-    /// Execute a command in the container and return output
-    pub async fn exec_command(
+    /// Options for running `bash_command` in the container, with stdin attached if requested
+    fn exec_options(
         &self,
-        command: Vec<String>,
-    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        log::debug!(
-            "Executing command in container {}: {:?}",
-            self.container_id,
-            command
-        );
-
-        let bash_command = self.prepare_bash_command(command);
-        log::debug!("Prepared bash command: {:?}", bash_command);
-
-        let exec_config = CreateExecOptions {
+        bash_command: Vec<String>,
+        attach_stdin: bool,
+    ) -> CreateExecOptions<String> {
+        CreateExecOptions {
             cmd: Some(bash_command),
+            attach_stdin: Some(attach_stdin),
             attach_stdout: Some(true),
             attach_stderr: Some(true),
             working_dir: self.config.working_directory.clone(),
@@ -84,7 +82,24 @@ impl CommandExecutor {
                 "NODE_PATH=/root/.nvm/versions/node/v22.16.0/lib/node_modules".to_string(),
             ]),
             ..Default::default()
-        };
+        }
+    }
+
+    /// Execute a command in the container and return output
+    pub async fn exec_command(
+        &self,
+        command: Vec<String>,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::debug!(
+            "Executing command in container {}: {:?}",
+            self.container_id,
+            command
+        );
+
+        let bash_command = self.prepare_bash_command(command);
+        log::debug!("Prepared bash command: {:?}", bash_command);
+
+        let exec_config = self.exec_options(bash_command, false);
 
         log::debug!(
             "Creating exec for container {} with working_dir: {:?}",
@@ -198,22 +213,7 @@ impl CommandExecutor {
         let bash_command = self.prepare_bash_command(command);
         log::debug!("Prepared bash streaming command: {:?}", bash_command);
 
-        let exec_config = CreateExecOptions {
-            cmd: Some(bash_command),
-            attach_stdout: Some(true),
-            attach_stderr: Some(true),
-            working_dir: self.config.working_directory.clone(),
-            env: Some(vec![
-                // Set up PATH to include NVM Node.js installation and standard paths
-                "PATH=/root/.nvm/versions/node/v22.16.0/bin:/root/.nvm/versions/node/v20.19.2/bin:\
-                 /root/.nvm/versions/node/v18.20.8/bin:/usr/local/sbin:/usr/local/bin:/usr/sbin:/\
-                 usr/bin:/sbin:/bin"
-                    .to_string(),
-                // Ensure Node.js modules are available
-                "NODE_PATH=/root/.nvm/versions/node/v22.16.0/lib/node_modules".to_string(),
-            ]),
-            ..Default::default()
-        };
+        let exec_config = self.exec_options(bash_command, false);
 
         log::debug!(
             "Creating streaming exec for container {} with working_dir: {:?}",
@@ -254,55 +254,11 @@ impl CommandExecutor {
         tokio::spawn(async move {
             match docker.start_exec(&exec_id, Some(start_config)).await {
                 Ok(bollard::exec::StartExecResults::Attached {
-                    output: mut output_stream,
+                    output: output_stream,
                     ..
                 }) => {
                     log::debug!("Successfully attached to streaming exec {}", exec_id);
-                    let mut line_buffer = String::new();
-
-                    while let Some(result) = output_stream.next().await {
-                        match result {
-                            Ok(msg) => {
-                                let content = match msg {
-                                    bollard::container::LogOutput::StdOut { message } => {
-                                        String::from_utf8_lossy(&message).to_string()
-                                    }
-                                    bollard::container::LogOutput::StdErr { message } => {
-                                        String::from_utf8_lossy(&message).to_string()
-                                    }
-                                    _ => continue,
-                                };
-
-                                line_buffer.push_str(&content);
-
-                                // Process complete lines
-                                while let Some(newline_pos) = line_buffer.find('\n') {
-                                    let line = line_buffer[..newline_pos].to_string();
-                                    line_buffer = line_buffer[newline_pos + 1..].to_string();
-
-                                    if !line.trim().is_empty() {
-                                        log::debug!("Streaming output line: '{}'", line.trim());
-                                        if tx.send(Ok(line)).is_err() {
-                                            log::debug!("Receiver dropped, stopping stream");
-                                            break;
-                                        }
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                log::error!("Error reading from streaming exec: {}", e);
-                                let _ = tx.send(Err(format!("Stream error: {}", e)));
-                                break;
-                            }
-                        }
-                    }
-
-                    // Send any remaining content in buffer
-                    if !line_buffer.trim().is_empty() {
-                        log::debug!("Streaming final line: '{}'", line_buffer.trim());
-                        let _ = tx.send(Ok(line_buffer));
-                    }
-
+                    forward_output_lines(output_stream, &tx).await;
                     log::debug!("Streaming exec {} completed", exec_id);
                 }
                 Ok(bollard::exec::StartExecResults::Detached) => {
@@ -322,4 +278,114 @@ impl CommandExecutor {
         // Return the stream
         Ok(Box::pin(UnboundedReceiverStream::new(rx)))
     }
+
+    /// Start a long-running command in the container with its stdin attached
+    ///
+    /// The output lines stream ends when the command exits, e.g. after its input is dropped.
+    pub async fn exec_interactive_command(
+        &self,
+        command: Vec<String>,
+    ) -> Result<InteractiveExec, Box<dyn std::error::Error + Send + Sync>> {
+        log::debug!(
+            "Executing interactive command in container {}: {:?}",
+            self.container_id,
+            command
+        );
+
+        let bash_command = self.prepare_bash_command(command);
+        let exec = self
+            .docker
+            .create_exec(&self.container_id, self.exec_options(bash_command, true))
+            .await?;
+
+        let start_config = StartExecOptions {
+            detach: false,
+            ..Default::default()
+        };
+
+        match self.docker.start_exec(&exec.id, Some(start_config)).await? {
+            bollard::exec::StartExecResults::Attached { output, input } => {
+                log::debug!("Successfully attached to interactive exec {}", exec.id);
+                let (tx, rx) = mpsc::unbounded_channel();
+                let exec_id = exec.id;
+
+                tokio::spawn(async move {
+                    forward_output_lines(output, &tx).await;
+                    log::debug!("Interactive exec {} completed", exec_id);
+                });
+
+                Ok(InteractiveExec {
+                    input,
+                    output: Box::pin(UnboundedReceiverStream::new(rx)),
+                })
+            }
+            bollard::exec::StartExecResults::Detached => {
+                log::error!("Unexpected detached execution for exec {}", exec.id);
+                Err("Unexpected detached execution".into())
+            }
+        }
+    }
+}
+
+/// A command running in the container with its stdin attached
+pub struct InteractiveExec {
+    /// The command's stdin
+    pub input: Pin<Box<dyn AsyncWrite + Send>>,
+    /// The command's output, line by line
+    pub output: Pin<Box<dyn Stream<Item = Result<String, String>> + Send>>,
+}
+
+/// Send the output of an attached exec to `tx` line by line, until it ends or `tx` is dropped
+async fn forward_output_lines(
+    mut output_stream: Pin<
+        Box<
+            dyn Stream<Item = Result<bollard::container::LogOutput, bollard::errors::Error>> + Send,
+        >,
+    >,
+    tx: &mpsc::UnboundedSender<Result<String, String>>,
+) {
+    let mut line_buffer = String::new();
+
+    while let Some(result) = output_stream.next().await {
+        match result {
+            Ok(msg) => {
+                let content = match msg {
+                    bollard::container::LogOutput::StdOut { message } => {
+                        String::from_utf8_lossy(&message).to_string()
+                    }
+                    bollard::container::LogOutput::StdErr { message } => {
+                        String::from_utf8_lossy(&message).to_string()
+                    }
+                    _ => continue,
+                };
+
+                line_buffer.push_str(&content);
+
+                // Process complete lines
+                while let Some(newline_pos) = line_buffer.find('\n') {
+                    let line = line_buffer[..newline_pos].to_string();
+                    line_buffer = line_buffer[newline_pos + 1..].to_string();
+
+                    if !line.trim().is_empty() {
+                        log::debug!("Streaming output line: '{}'", line.trim());
+                        if tx.send(Ok(line)).is_err() {
+                            log::debug!("Receiver dropped, stopping stream");
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("Error reading from streaming exec: {}", e);
+                let _ = tx.send(Err(format!("Stream error: {}", e)));
+                break;
+            }
+        }
+    }
+
+    // Send any remaining content in buffer
+    if !line_buffer.trim().is_empty() {
+        log::debug!("Streaming final line: '{}'", line_buffer.trim());
+        let _ = tx.send(Ok(line_buffer));
+    }
 }
@@ -0,0 +1,228 @@
+use futures_util::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, Mutex};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use super::executor::InteractiveExec;
+use super::message_parser::{ClaudeMessageParser, MessageType, ParseResult, ParsedClaudeMessage};
+
+type ParsedStream = Pin<Box<dyn Stream<Item = Result<ParsedClaudeMessage, String>> + Send>>;
+
+/// A long-lived Claude process that reads user turns as stream-json from its stdin
+///
+/// Keeping the process between turns saves the startup and context reload of a fresh
+/// `claude --resume` for every message. Dropping it closes stdin, which ends the process.
+pub struct InteractiveClaudeProcess {
+    input: Mutex<Pin<Box<dyn AsyncWrite + Send>>>,
+    events: Arc<Mutex<mpsc::UnboundedReceiver<Result<ParsedClaudeMessage, String>>>>,
+    alive: Arc<AtomicBool>,
+    conversation_id: Arc<std::sync::Mutex<Option<String>>>,
+    launch_key: Vec<String>,
+}
+
+impl InteractiveClaudeProcess {
+    /// Wrap a started Claude process
+    ///
+    /// `launch_key` identifies the options it was started with, so a process started for a
+    /// different directory or configuration isn't reused.
+    pub fn new(
+        exec: InteractiveExec,
+        launch_key: Vec<String>,
+        conversation_id: Option<String>,
+    ) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let alive = Arc::new(AtomicBool::new(true));
+        let conversation_id = Arc::new(std::sync::Mutex::new(conversation_id));
+
+        let reader_alive = alive.clone();
+        let reader_conversation_id = conversation_id.clone();
+        let mut output = exec.output;
+        tokio::spawn(async move {
+            let mut reported_cost = 0.0;
+            while let Some(line) = output.next().await {
+                let messages = match line {
+                    Ok(line) => match ClaudeMessageParser::parse_line(&line) {
+                        ParseResult::Messages(messages) => messages,
+                        ParseResult::PlainText(text) => {
+                            log::debug!("Skipping plain text from Claude process: {}", text);
+                            continue;
+                        }
+                        ParseResult::Empty => continue,
+                    },
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        break;
+                    }
+                };
+
+                let sent = messages.into_iter().all(|mut parsed| {
+                    if let Some(id) = &parsed.conversation_id {
+                        *reader_conversation_id.lock().unwrap() = Some(id.clone());
+                    }
+                    charge_turn(&mut reported_cost, &mut parsed);
+                    tx.send(Ok(parsed)).is_ok()
+                });
+                if !sent {
+                    break;
+                }
+            }
+
+            log::info!("Interactive Claude process exited");
+            reader_alive.store(false, Ordering::SeqCst);
+        });
+
+        Self {
+            input: Mutex::new(exec.input),
+            events: Arc::new(Mutex::new(rx)),
+            alive,
+            conversation_id,
+            launch_key,
+        }
+    }
+
+    /// Whether the process is still running
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst)
+    }
+
+    /// The conversation the process is in, as last reported by Claude
+    pub fn conversation_id(&self) -> Option<String> {
+        self.conversation_id.lock().unwrap().clone()
+    }
+
+    /// Whether the next turn of `conversation_id` can be sent to this process
+    pub fn can_continue(&self, launch_key: &[String], conversation_id: Option<&str>) -> bool {
+        self.is_alive()
+            && self.launch_key == launch_key
+            && self.conversation_id().as_deref() == conversation_id
+    }
+
+    /// Send a user turn and stream Claude's events up to and including the turn's result
+    pub async fn send_prompt(
+        &self,
+        prompt: &str,
+    ) -> Result<ParsedStream, Box<dyn std::error::Error + Send + Sync>> {
+        if !self.is_alive() {
+            return Err("Claude process has exited".into());
+        }
+
+        // Wait for the previous turn to finish before starting the next one
+        let mut events = self.events.clone().lock_owned().await;
+
+        let line = format!("{}\n", user_turn(prompt));
+        let written = {
+            let mut input = self.input.lock().await;
+            match input.write_all(line.as_bytes()).await {
+                Ok(()) => input.flush().await,
+                Err(e) => Err(e),
+            }
+        };
+        if let Err(e) = written {
+            self.alive.store(false, Ordering::SeqCst);
+            return Err(format!("Failed to send the prompt to Claude: {}", e).into());
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            // Read the whole turn even when nobody listens anymore, so the next turn starts
+            // with its own events
+            loop {
+                match events.recv().await {
+                    Some(Ok(parsed)) => {
+                        let is_result = matches!(parsed.message_type, MessageType::Result { .. });
+                        let _ = tx.send(Ok(parsed));
+                        if is_result {
+                            break;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        let _ = tx.send(Err(e));
+                        break;
+                    }
+                    None => {
+                        let _ = tx.send(Err("Claude process exited during the turn".to_string()));
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(UnboundedReceiverStream::new(rx)))
+    }
+}
+
+impl std::fmt::Debug for InteractiveClaudeProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InteractiveClaudeProcess")
+            .field("alive", &self.is_alive())
+            .field("conversation_id", &self.conversation_id())
+            .finish()
+    }
+}
+
+/// Replace the cost of a result with the cost of its own turn
+///
+/// A stream-json process reports `total_cost_usd` for everything it ran so far, so the turn
+/// costs the difference to the total last reported.
+fn charge_turn(reported_cost: &mut f64, parsed: &mut ParsedClaudeMessage) {
+    if let MessageType::Result {
+        cost: Some(cost), ..
+    } = &mut parsed.message_type
+    {
+        let total = *cost;
+        *cost = (total - *reported_cost).max(0.0);
+        *reported_cost = total;
+    }
+}
+
+/// A user turn in Claude's stream-json input format
+fn user_turn(prompt: &str) -> String {
+    serde_json::json!({
+        "type": "user",
+        "message": {
+            "role": "user",
+            "content": [{ "type": "text", "text": prompt }],
+        },
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_turn() {
+        assert_eq!(
+            user_turn("Fix \"main\"\nplease"),
+            r#"{"message":{"content":[{"text":"Fix \"main\"\nplease","type":"text"}],"role":"user"},"type":"user"}"#
+        );
+    }
+
+    #[test]
+    fn test_charge_turn() {
+        let turn_cost = |reported_cost: &mut f64, total: f64| {
+            let line = format!(
+                r#"{{"type":"result","subtype":"success","is_error":false,"result":"Done","session_id":"s1","total_cost_usd":{}}}"#,
+                total
+            );
+            let ParseResult::Messages(mut messages) = ClaudeMessageParser::parse_line(&line) else {
+                panic!("Expected a message");
+            };
+            let mut parsed = messages.remove(0);
+            charge_turn(reported_cost, &mut parsed);
+            match parsed.message_type {
+                MessageType::Result { cost, .. } => cost,
+                other => panic!("Expected a result, got {:?}", other),
+            }
+        };
+
+        let mut reported_cost = 0.0;
+        assert_eq!(turn_cost(&mut reported_cost, 0.25), Some(0.25));
+        assert_eq!(turn_cost(&mut reported_cost, 0.75), Some(0.5));
+        assert_eq!(reported_cost, 0.75);
+    }
+}
@@ -1,11 +1,11 @@
-use super::streaming::{ClaudeMessage, ContentBlock};
+use super::streaming::{ClaudeMessage, ContentBlock, McpServerStatus, MessageContent};
 use super::Usage;
 
 /// Results from parsing a Claude message line
 #[derive(Debug)]
 pub enum ParseResult {
-    /// Successfully parsed a Claude message
-    Message(ParsedClaudeMessage),
+    /// Successfully parsed a Claude message, with one entry per content block
+    Messages(Vec<ParsedClaudeMessage>),
     /// Line contained plain text (not JSON)
     PlainText(String),
     /// Empty line or whitespace
@@ -16,6 +16,9 @@ pub enum ParseResult {
 pub type ResultInfo<'a> = (&'a String, bool, Option<f64>, Option<u64>, Option<u32>);
 
 /// A parsed Claude message with extracted information
+///
+/// A message with several content blocks, like text followed by tool uses, is parsed into one
+/// of these per block, each carrying the whole `message`.
 #[derive(Debug)]
 pub struct ParsedClaudeMessage {
     pub message: ClaudeMessage,
@@ -28,22 +31,38 @@ pub struct ParsedClaudeMessage {
 pub enum MessageType {
     SystemInit {
         conversation_id: Option<String>,
+        /// Tools available in the session, including `mcp__<server>__<tool>` from MCP servers
+        tools: Vec<String>,
+        model: Option<String>,
+        permission_mode: Option<String>,
+        mcp_servers: Vec<McpServerStatus>,
     },
     AssistantText {
         text: String,
         conversation_id: Option<String>,
     },
+    AssistantThinking {
+        /// Empty when the thinking was redacted
+        thinking: String,
+        conversation_id: Option<String>,
+    },
     AssistantToolUse {
+        id: String,
         name: String,
         input: Option<serde_json::Value>,
         conversation_id: Option<String>,
     },
     UserToolResult {
+        /// Id of the tool use this is the result of
+        tool_use_id: Option<String>,
         content: String,
+        is_error: bool,
+        image_count: usize,
         conversation_id: Option<String>,
     },
     Result {
         result: String,
+        subtype: ResultSubtype,
         conversation_id: String,
         is_error: bool,
         cost: Option<f64>,
@@ -51,11 +70,46 @@ pub enum MessageType {
         num_turns: Option<u32>,
         usage: Option<Usage>,
     },
-    Other {
+    /// Known event without anything to show, like other system messages
+    Other { conversation_id: Option<String> },
+    /// Event of a type this parser doesn't know, with its raw JSON
+    Unknown {
+        event_type: Option<String>,
         conversation_id: Option<String>,
+        raw: serde_json::Value,
     },
 }
 
+/// How a Claude run ended, from the `subtype` of its result message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResultSubtype {
+    Success,
+    /// The run stopped at the `--max-turns` limit
+    ErrorMaxTurns,
+    ErrorDuringExecution,
+    Other(String),
+}
+
+impl ResultSubtype {
+    pub fn parse(subtype: &str) -> Self {
+        match subtype {
+            "success" => Self::Success,
+            "error_max_turns" => Self::ErrorMaxTurns,
+            "error_during_execution" => Self::ErrorDuringExecution,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// Whether the run stopped before finishing, even when it isn't flagged as an error
+    pub fn is_error(&self) -> bool {
+        match self {
+            Self::Success => false,
+            Self::ErrorMaxTurns | Self::ErrorDuringExecution => true,
+            Self::Other(subtype) => subtype.starts_with("error"),
+        }
+    }
+}
+
 /// Claude message parser
 pub struct ClaudeMessageParser;
 
@@ -68,10 +122,7 @@ impl ClaudeMessageParser {
         }
 
         match serde_json::from_str::<ClaudeMessage>(line) {
-            Ok(message) => {
-                let parsed = Self::process_message(message);
-                ParseResult::Message(parsed)
-            }
+            Ok(message) => ParseResult::Messages(Self::process_message(message)),
             Err(_) => ParseResult::PlainText(line.to_string()),
         }
     }
@@ -86,15 +137,17 @@ impl ClaudeMessageParser {
     }
 
     /// Process a parsed Claude message and extract relevant information
-    fn process_message(message: ClaudeMessage) -> ParsedClaudeMessage {
+    fn process_message(message: ClaudeMessage) -> Vec<ParsedClaudeMessage> {
         let conversation_id = Self::extract_conversation_id(&message);
-        let message_type = Self::classify_message(&message);
 
-        ParsedClaudeMessage {
-            message,
-            conversation_id,
-            message_type,
-        }
+        Self::classify_message(&message)
+            .into_iter()
+            .map(|message_type| ParsedClaudeMessage {
+                message: message.clone(),
+                conversation_id: conversation_id.clone(),
+                message_type,
+            })
+            .collect()
     }
 
     /// Extract conversation ID from any Claude message
@@ -104,73 +157,98 @@ impl ClaudeMessageParser {
             ClaudeMessage::Assistant { session_id, .. } => session_id.clone(),
             ClaudeMessage::User { session_id, .. } => session_id.clone(),
             ClaudeMessage::Result { session_id, .. } => Some(session_id.clone()),
+            ClaudeMessage::Unknown(raw) => raw["session_id"].as_str().map(str::to_string),
         }
     }
 
-    /// Classify the message type for easier handling
-    fn classify_message(message: &ClaudeMessage) -> MessageType {
-        match message {
+    /// Classify the message type for easier handling, once for each content block
+    fn classify_message(message: &ClaudeMessage) -> Vec<MessageType> {
+        let message_types = match message {
             ClaudeMessage::System {
                 subtype,
                 session_id,
+                tools,
+                model,
+                permission_mode,
+                mcp_servers,
                 ..
             } => {
                 if subtype == "init" {
-                    MessageType::SystemInit {
+                    vec![MessageType::SystemInit {
                         conversation_id: session_id.clone(),
-                    }
+                        tools: tools.clone().unwrap_or_default(),
+                        model: model.clone(),
+                        permission_mode: permission_mode.clone(),
+                        mcp_servers: mcp_servers.clone().unwrap_or_default(),
+                    }]
                 } else {
-                    MessageType::Other {
-                        conversation_id: session_id.clone(),
-                    }
+                    Vec::new()
                 }
             }
             ClaudeMessage::Assistant {
                 message,
                 session_id,
-            } => {
-                if let Some(content_blocks) = &message.content {
-                    if let Some(block) = content_blocks.iter().next() {
-                        match block {
-                            ContentBlock::Text { text } => {
-                                return MessageType::AssistantText {
-                                    text: text.clone(),
-                                    conversation_id: session_id.clone(),
-                                };
-                            }
-                            ContentBlock::ToolUse { name, input, .. } => {
-                                return MessageType::AssistantToolUse {
-                                    name: name.clone(),
-                                    input: input.clone(),
-                                    conversation_id: session_id.clone(),
-                                };
-                            }
-                        }
+            } => message
+                .content
+                .iter()
+                .flatten()
+                .filter_map(|block| match block {
+                    ContentBlock::Text { text } => Some(MessageType::AssistantText {
+                        text: text.clone(),
+                        conversation_id: session_id.clone(),
+                    }),
+                    ContentBlock::Thinking { thinking, .. } => {
+                        Some(MessageType::AssistantThinking {
+                            thinking: thinking.clone(),
+                            conversation_id: session_id.clone(),
+                        })
                     }
-                }
-                MessageType::Other {
-                    conversation_id: session_id.clone(),
-                }
-            }
+                    ContentBlock::RedactedThinking { .. } => Some(MessageType::AssistantThinking {
+                        thinking: String::new(),
+                        conversation_id: session_id.clone(),
+                    }),
+                    ContentBlock::ToolUse { id, name, input } => {
+                        Some(MessageType::AssistantToolUse {
+                            id: id.clone(),
+                            name: name.clone(),
+                            input: input.clone(),
+                            conversation_id: session_id.clone(),
+                        })
+                    }
+                    _ => None,
+                })
+                .collect(),
             ClaudeMessage::User {
                 message,
                 session_id,
-            } => {
-                if let Some(content) = &message.content {
-                    for tool_result in content {
-                        if let Some(result_content) = &tool_result.content {
-                            return MessageType::UserToolResult {
-                                content: result_content.clone(),
-                                conversation_id: session_id.clone(),
-                            };
-                        }
-                    }
-                }
-                MessageType::Other {
-                    conversation_id: session_id.clone(),
-                }
-            }
+            } => match &message.content {
+                Some(MessageContent::Blocks(blocks)) => blocks
+                    .iter()
+                    .filter_map(|block| match block {
+                        ContentBlock::ToolResult {
+                            tool_use_id,
+                            content,
+                            is_error,
+                        } => Some(MessageType::UserToolResult {
+                            tool_use_id: tool_use_id.clone(),
+                            content: content
+                                .as_ref()
+                                .map(MessageContent::text)
+                                .unwrap_or_default(),
+                            is_error: *is_error,
+                            image_count: content
+                                .as_ref()
+                                .map(MessageContent::image_count)
+                                .unwrap_or_default(),
+                            conversation_id: session_id.clone(),
+                        }),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            },
             ClaudeMessage::Result {
+                subtype,
                 result,
                 session_id,
                 is_error,
@@ -178,16 +256,30 @@ impl ClaudeMessageParser {
                 duration_ms,
                 num_turns,
                 usage,
-                ..
-            } => MessageType::Result {
+            } => vec![MessageType::Result {
                 result: result.clone(),
+                subtype: ResultSubtype::parse(subtype),
                 conversation_id: session_id.clone(),
                 is_error: *is_error,
                 cost: *total_cost_usd,
                 duration_ms: *duration_ms,
                 num_turns: *num_turns,
                 usage: usage.clone(),
-            },
+            }],
+            ClaudeMessage::Unknown(raw) => vec![MessageType::Unknown {
+                event_type: raw["type"].as_str().map(str::to_string),
+                conversation_id: raw["session_id"].as_str().map(str::to_string),
+                raw: raw.clone(),
+            }],
+        };
+
+        if message_types.is_empty() {
+            // Nothing to show, but the message still carries the conversation ID
+            vec![MessageType::Other {
+                conversation_id: Self::extract_conversation_id(message),
+            }]
+        } else {
+            message_types
         }
     }
 }
@@ -276,9 +368,10 @@ mod tests {
         let result = ClaudeMessageParser::parse_line(json_line);
 
         match result {
-            ParseResult::Message(parsed) => {
-                assert_eq!(parsed.conversation_id, Some("test-session".to_string()));
-                assert!(parsed.is_init());
+            ParseResult::Messages(parsed) => {
+                assert_eq!(parsed.len(), 1);
+                assert_eq!(parsed[0].conversation_id, Some("test-session".to_string()));
+                assert!(parsed[0].is_init());
             }
             _ => panic!("Expected Message result"),
         }
@@ -295,7 +388,7 @@ This is plain text
         assert_eq!(results.len(), 3);
 
         match &results[0] {
-            ParseResult::Message(parsed) => assert!(parsed.is_init()),
+            ParseResult::Messages(parsed) => assert!(parsed[0].is_init()),
             _ => panic!("Expected Message result"),
         }
 
@@ -305,8 +398,8 @@ This is plain text
         }
 
         match &results[2] {
-            ParseResult::Message(parsed) => {
-                if let Some((result, is_error, _, _, _)) = parsed.get_result() {
+            ParseResult::Messages(parsed) => {
+                if let Some((result, is_error, _, _, _)) = parsed[0].get_result() {
                     assert_eq!(result, "Done");
                     assert!(!is_error);
                 } else {
@@ -323,11 +416,208 @@ This is plain text
         let result = ClaudeMessageParser::parse_line(json_line);
 
         match result {
-            ParseResult::Message(parsed) => {
-                assert_eq!(parsed.conversation_id, Some("conv-123".to_string()));
-                assert_eq!(parsed.get_text(), Some(&"Hello".to_string()));
+            ParseResult::Messages(parsed) => {
+                assert_eq!(parsed[0].conversation_id, Some("conv-123".to_string()));
+                assert_eq!(parsed[0].get_text(), Some(&"Hello".to_string()));
             }
             _ => panic!("Expected Message result"),
         }
     }
+
+    fn parse_messages(line: &str) -> Vec<ParsedClaudeMessage> {
+        match ClaudeMessageParser::parse_line(line) {
+            ParseResult::Messages(parsed) => parsed,
+            other => panic!("Expected Messages result, got {:?}", other),
+        }
+    }
+
+    fn parse_message(line: &str) -> ParsedClaudeMessage {
+        let mut parsed = parse_messages(line);
+        assert_eq!(parsed.len(), 1);
+        parsed.remove(0)
+    }
+
+    #[test]
+    fn test_parse_every_block() {
+        let parsed = parse_messages(
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"Check both"},{"type":"text","text":"Reading the files"},{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"a.rs"}},{"type":"tool_use","id":"t2","name":"Read","input":{"file_path":"b.rs"}}]},"session_id":"s1"}"#,
+        );
+        assert_eq!(parsed.len(), 4);
+        assert!(matches!(
+            parsed[0].message_type,
+            MessageType::AssistantThinking { .. }
+        ));
+        assert_eq!(parsed[1].get_text(), Some(&"Reading the files".to_string()));
+        assert!(matches!(
+            parsed[2].message_type,
+            MessageType::AssistantToolUse { ref id, .. } if id == "t1"
+        ));
+        assert!(matches!(
+            parsed[3].message_type,
+            MessageType::AssistantToolUse { ref id, .. } if id == "t2"
+        ));
+        assert!(parsed
+            .iter()
+            .all(|message| message.conversation_id.as_deref() == Some("s1")));
+
+        let parsed = parse_messages(
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"fn a() {}"},{"type":"tool_result","tool_use_id":"t2","content":"No such file","is_error":true}]},"session_id":"s1"}"#,
+        );
+        let results: Vec<_> = parsed
+            .iter()
+            .map(|message| match &message.message_type {
+                MessageType::UserToolResult {
+                    tool_use_id,
+                    is_error,
+                    ..
+                } => (tool_use_id.as_deref(), *is_error),
+                other => panic!("Expected UserToolResult, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(results, vec![(Some("t1"), false), (Some("t2"), true)]);
+
+        // An assistant message without any known block still reports its conversation
+        let parsed =
+            parse_message(r#"{"type":"assistant","message":{"content":[]},"session_id":"s2"}"#);
+        assert!(matches!(
+            parsed.message_type,
+            MessageType::Other { conversation_id: Some(ref id) } if id == "s2"
+        ));
+    }
+
+    #[test]
+    fn test_parse_init_details() {
+        let parsed = parse_message(
+            r#"{"type":"system","subtype":"init","session_id":"s1","tools":["Bash","mcp__fetch__fetch"],"model":"claude-sonnet","permissionMode":"acceptEdits","mcp_servers":[{"name":"fetch","status":"connected"}]}"#,
+        );
+
+        match parsed.message_type {
+            MessageType::SystemInit {
+                tools,
+                model,
+                permission_mode,
+                mcp_servers,
+                ..
+            } => {
+                assert_eq!(tools, vec!["Bash", "mcp__fetch__fetch"]);
+                assert_eq!(model.as_deref(), Some("claude-sonnet"));
+                assert_eq!(permission_mode.as_deref(), Some("acceptEdits"));
+                assert_eq!(mcp_servers[0].name, "fetch");
+                assert_eq!(mcp_servers[0].status, "connected");
+            }
+            other => panic!("Expected SystemInit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_thinking() {
+        let parsed = parse_message(
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"Look at main.rs","signature":"abc"}]},"session_id":"s1"}"#,
+        );
+        assert!(matches!(
+            parsed.message_type,
+            MessageType::AssistantThinking { ref thinking, .. } if thinking == "Look at main.rs"
+        ));
+
+        let parsed = parse_message(
+            r#"{"type":"assistant","message":{"content":[{"type":"redacted_thinking","data":"xyz"}]},"session_id":"s1"}"#,
+        );
+        assert!(matches!(
+            parsed.message_type,
+            MessageType::AssistantThinking { ref thinking, .. } if thinking.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_parse_tool_result_error_and_images() {
+        let parsed = parse_message(
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"File not found","is_error":true}]},"session_id":"s1"}"#,
+        );
+        match parsed.message_type {
+            MessageType::UserToolResult {
+                tool_use_id,
+                content,
+                is_error,
+                ..
+            } => {
+                assert_eq!(tool_use_id.as_deref(), Some("t1"));
+                assert_eq!(content, "File not found");
+                assert!(is_error);
+            }
+            other => panic!("Expected UserToolResult, got {:?}", other),
+        }
+
+        let parsed = parse_message(
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t2","content":[{"type":"text","text":"Screenshot"},{"type":"image","source":{"type":"base64","media_type":"image/png","data":"iVBO"}}]}]},"session_id":"s1"}"#,
+        );
+        match parsed.message_type {
+            MessageType::UserToolResult {
+                content,
+                is_error,
+                image_count,
+                ..
+            } => {
+                assert_eq!(content, "Screenshot");
+                assert!(!is_error);
+                assert_eq!(image_count, 1);
+            }
+            other => panic!("Expected UserToolResult, got {:?}", other),
+        }
+
+        // A replayed prompt is plain text, not a tool result
+        let parsed = parse_message(
+            r#"{"type":"user","message":{"content":"Fix the tests"},"session_id":"s1"}"#,
+        );
+        assert!(matches!(parsed.message_type, MessageType::Other { .. }));
+    }
+
+    #[test]
+    fn test_parse_result_subtypes() {
+        let parsed = parse_message(
+            r#"{"type":"result","subtype":"error_max_turns","is_error":false,"session_id":"s1","num_turns":10,"total_cost_usd":0.2}"#,
+        );
+        match parsed.message_type {
+            MessageType::Result {
+                subtype,
+                result,
+                num_turns,
+                ..
+            } => {
+                assert_eq!(subtype, ResultSubtype::ErrorMaxTurns);
+                assert!(subtype.is_error());
+                assert_eq!(result, "");
+                assert_eq!(num_turns, Some(10));
+            }
+            other => panic!("Expected Result, got {:?}", other),
+        }
+
+        assert_eq!(ResultSubtype::parse("success"), ResultSubtype::Success);
+        assert!(!ResultSubtype::parse("success").is_error());
+        assert!(ResultSubtype::parse("error_something_new").is_error());
+    }
+
+    #[test]
+    fn test_parse_unknown_event() {
+        let parsed = parse_message(r#"{"type":"stream_event","session_id":"s1","event":{"x":1}}"#);
+
+        assert_eq!(parsed.conversation_id, Some("s1".to_string()));
+        match &parsed.message_type {
+            MessageType::Unknown {
+                event_type, raw, ..
+            } => {
+                assert_eq!(event_type.as_deref(), Some("stream_event"));
+                assert_eq!(raw["event"]["x"], 1);
+            }
+            other => panic!("Expected Unknown message type, got {:?}", other),
+        }
+        match parsed.message {
+            ClaudeMessage::Unknown(raw) => assert_eq!(raw["event"]["x"], 1),
+            other => panic!("Expected Unknown message, got {:?}", other),
+        }
+
+        let parsed = parse_message(
+            r#"{"type":"assistant","message":{"content":[{"type":"server_tool_use","id":"x"},{"type":"text","text":"Hi"}]},"session_id":"s1"}"#,
+        );
+        assert_eq!(parsed.get_text(), Some(&"Hi".to_string()));
+    }
 }
@@ -8,21 +8,28 @@ pub mod container;
 pub mod container_cred_storage;
 pub mod container_utils;
 pub mod executor;
+pub mod interactive;
 pub mod message_parser;
 pub mod response_processor;
 pub mod streaming;
 
 pub use auth::{AuthState, AuthenticationHandle};
 pub use claude_command::{ClaudeCommandExecutor, ClaudeExecutionResult};
-pub use config::ClaudeCodeConfig;
+pub use config::{ClaudeCodeConfig, McpConfig};
 pub use container_cred_storage::ContainerCredStorage;
-pub use executor::CommandExecutor;
-pub use message_parser::{ClaudeMessageParser, MessageType, ParseResult, ParsedClaudeMessage};
+pub use executor::{CommandExecutor, InteractiveExec};
+pub use interactive::InteractiveClaudeProcess;
+pub use message_parser::{
+    ClaudeMessageParser, MessageType, ParseResult, ParsedClaudeMessage, ResultSubtype,
+};
 pub use response_processor::{
     ErrorInfo, LiveMessage, ProcessedResponse, ResponseItem, ResponseProcessor, SessionInfo,
-    ToolResultItem,
+    ToolCall, ToolCallStatus, ToolCallTracker, ToolResultItem,
+};
+pub use streaming::{
+    AssistantMessage, ClaudeMessage, ContentBlock, McpServerStatus, MessageContent, ToolResult,
+    UserMessage,
 };
-pub use streaming::{AssistantMessage, ClaudeMessage, ContentBlock, ToolResult, UserMessage};
 
 // Re-export OAuth types from oauth module
 pub use crate::oauth::{ClaudeAuth, Config as OAuthConfig, Credentials, OAuthError};
@@ -248,6 +255,38 @@ impl ClaudeCodeClient {
             .await
     }
 
+    /// Start a long-lived Claude process in `conversation_id`, or in a new conversation
+    pub async fn start_interactive_claude(
+        &self,
+        conversation_id: Option<&str>,
+    ) -> Result<InteractiveClaudeProcess, Box<dyn std::error::Error + Send + Sync>> {
+        self.claude_executor.start_interactive(conversation_id).await
+    }
+
+    /// Whether `process` was started like this client would start one and is in
+    /// `conversation_id`, so it can take the next prompt
+    pub fn can_continue_interactive(
+        &self,
+        process: &InteractiveClaudeProcess,
+        conversation_id: Option<&str>,
+    ) -> bool {
+        process.can_continue(
+            &self.claude_executor.interactive_launch_key(),
+            conversation_id,
+        )
+    }
+
+    /// Execute a Claude prompt in a new conversation branched from `conversation_id`
+    pub async fn execute_claude_fork(
+        &self,
+        prompt: &str,
+        conversation_id: &str,
+    ) -> Result<ClaudeExecutionResult, Box<dyn std::error::Error + Send + Sync>> {
+        self.claude_executor
+            .execute_claude_fork(prompt, conversation_id)
+            .await
+    }
+
     /// Build Claude command arguments
     pub fn build_claude_command_args(
         &self,
@@ -326,6 +365,33 @@ impl ClaudeCodeClient {
         Ok(Self::new(docker, container_id, config))
     }
 
+    /// Append instructions to Claude's system prompt for the prompts this client runs
+    pub fn with_append_system_prompt(self, system_prompt: Option<String>) -> Self {
+        let config = ClaudeCodeConfig {
+            append_system_prompt: system_prompt,
+            ..self.config
+        };
+        Self::new(self.docker, self.container_id, config)
+    }
+
+    /// Start the given MCP servers for the prompts this client runs
+    pub fn with_mcp_config(self, mcp_config: Option<McpConfig>) -> Self {
+        let config = ClaudeCodeConfig {
+            mcp_config,
+            ..self.config
+        };
+        Self::new(self.docker, self.container_id, config)
+    }
+
+    /// Limit the runs of this client to `max_turns` agentic turns
+    pub fn with_max_turns(self, max_turns: Option<u32>) -> Self {
+        let config = ClaudeCodeConfig {
+            max_turns,
+            ..self.config
+        };
+        Self::new(self.docker, self.container_id, config)
+    }
+
     /// Create a client with custom OAuth configuration
     pub fn with_oauth_config(
         docker: Docker,
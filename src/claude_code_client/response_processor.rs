@@ -51,7 +51,12 @@ impl ResponseProcessor {
                 MessageType::AssistantText { text, .. } => {
                     responses.push(ResponseItem::AssistantText(text.clone()));
                 }
-                MessageType::AssistantToolUse { name, input, .. } => {
+                MessageType::AssistantThinking { thinking, .. } => {
+                    responses.push(ResponseItem::Thinking(thinking.clone()));
+                }
+                MessageType::AssistantToolUse {
+                    id, name, input, ..
+                } => {
                     let input_str = input
                         .as_ref()
                         .map(|v| serde_json::to_string_pretty(v).unwrap_or_default())
@@ -59,13 +64,19 @@ impl ResponseProcessor {
                     responses.push(ResponseItem::ToolUse {
                         name: name.clone(),
                         input: input_str,
-                        id: "".to_string(), // ParsedClaudeMessage doesn't store tool ID
+                        id: id.clone(),
                     });
                 }
-                MessageType::UserToolResult { content, .. } => {
+                MessageType::UserToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                    ..
+                } => {
                     tool_results.push(ToolResultItem {
-                        tool_use_id: None,
+                        tool_use_id: tool_use_id.clone(),
                         content: content.clone(),
+                        is_error: *is_error,
                     });
                 }
                 MessageType::Result {
@@ -94,7 +105,7 @@ impl ResponseProcessor {
                         num_turns: *num_turns,
                     });
                 }
-                MessageType::Other { .. } => {
+                MessageType::Other { .. } | MessageType::Unknown { .. } => {
                     // Skip other message types
                 }
             }
@@ -153,6 +164,8 @@ impl ProcessedResponse {
 pub enum ResponseItem {
     SystemMessage(String),
     AssistantText(String),
+    /// Claude's extended thinking, empty when redacted
+    Thinking(String),
     ToolUse {
         name: String,
         input: String,
@@ -167,6 +180,7 @@ pub enum ResponseItem {
 pub struct ToolResultItem {
     pub tool_use_id: Option<String>,
     pub content: String,
+    pub is_error: bool,
 }
 
 impl ToolResultItem {
@@ -274,9 +288,70 @@ impl LiveMessage {
     }
 }
 
+/// State of a tool call in the activity view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolCallStatus {
+    Running,
+    Done(Duration),
+    Failed(Duration),
+}
+
+/// A tool call made by Claude, paired with its result once that arrives
+#[derive(Debug)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input: String,
+    pub started_at: Instant,
+    pub status: ToolCallStatus,
+}
+
+/// Pairs Claude's tool calls with their results by tool use id
+#[derive(Debug, Default)]
+pub struct ToolCallTracker {
+    calls: Vec<ToolCall>,
+}
+
+impl ToolCallTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a tool call as running
+    pub fn start(&mut self, id: String, name: String, input: String) -> &ToolCall {
+        self.calls.push(ToolCall {
+            id,
+            name,
+            input,
+            started_at: Instant::now(),
+            status: ToolCallStatus::Running,
+        });
+        &self.calls[self.calls.len() - 1]
+    }
+
+    /// Mark the call with `tool_use_id` as done or failed
+    ///
+    /// Returns the call, or `None` for an unknown id or a call that already finished.
+    pub fn finish(&mut self, tool_use_id: &str, is_error: bool) -> Option<&ToolCall> {
+        let call = self
+            .calls
+            .iter_mut()
+            .find(|call| call.id == tool_use_id && call.status == ToolCallStatus::Running)?;
+
+        let duration = call.started_at.elapsed();
+        call.status = if is_error {
+            ToolCallStatus::Failed(duration)
+        } else {
+            ToolCallStatus::Done(duration)
+        };
+        Some(call)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::claude_code_client::{ClaudeMessageParser, ParseResult};
 
     #[test]
     fn test_response_processor_basic() {
@@ -291,10 +366,16 @@ mod tests {
                     cwd: Some("/workspace".to_string()),
                     tools: None,
                     model: None,
+                    permission_mode: None,
+                    mcp_servers: None,
                 },
                 conversation_id: Some("test-session".to_string()),
                 message_type: MessageType::SystemInit {
                     conversation_id: Some("test-session".to_string()),
+                    tools: Vec::new(),
+                    model: None,
+                    permission_mode: None,
+                    mcp_servers: Vec::new(),
                 },
             },
             ParsedClaudeMessage {
@@ -332,6 +413,7 @@ mod tests {
         let tool_result = ToolResultItem {
             tool_use_id: Some("tool1".to_string()),
             content: short_content.to_string(),
+            is_error: false,
         };
 
         assert!(!tool_result.is_large());
@@ -344,6 +426,7 @@ mod tests {
         let large_tool_result = ToolResultItem {
             tool_use_id: Some("tool2".to_string()),
             content: long_content.clone(),
+            is_error: false,
         };
 
         assert!(large_tool_result.is_large());
@@ -377,6 +460,50 @@ mod tests {
         assert_eq!(empty_session.format_summary(), "Session completed");
     }
 
+    #[test]
+    fn test_tool_call_tracker() {
+        let mut tracker = ToolCallTracker::new();
+        let call = tracker.start("t1".to_string(), "Bash".to_string(), "{}".to_string());
+        assert_eq!(call.status, ToolCallStatus::Running);
+        tracker.start("t2".to_string(), "Read".to_string(), "{}".to_string());
+
+        let call = tracker.finish("t2", true).unwrap();
+        assert_eq!(call.name, "Read");
+        assert!(matches!(call.status, ToolCallStatus::Failed(_)));
+
+        assert!(matches!(
+            tracker.finish("t1", false).unwrap().status,
+            ToolCallStatus::Done(_)
+        ));
+
+        // Unknown ids and repeated results are ignored
+        assert!(tracker.finish("t3", false).is_none());
+        assert!(tracker.finish("t1", true).is_none());
+
+        // Parallel calls answered in one message all finish
+        tracker.start("t4".to_string(), "Read".to_string(), "{}".to_string());
+        tracker.start("t5".to_string(), "Read".to_string(), "{}".to_string());
+        let line = r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t4","content":"a"},{"type":"tool_result","tool_use_id":"t5","content":"b","is_error":true}]},"session_id":"s1"}"#;
+        let ParseResult::Messages(messages) = ClaudeMessageParser::parse_line(line) else {
+            panic!("Expected messages");
+        };
+        let statuses: Vec<_> = messages
+            .iter()
+            .filter_map(|parsed| match &parsed.message_type {
+                MessageType::UserToolResult {
+                    tool_use_id: Some(id),
+                    is_error,
+                    ..
+                } => tracker.finish(id, *is_error).map(|call| call.status),
+                _ => None,
+            })
+            .collect();
+        assert!(matches!(
+            statuses[..],
+            [ToolCallStatus::Done(_), ToolCallStatus::Failed(_)]
+        ));
+    }
+
     #[test]
     fn test_live_message() {
         let mut live_msg = LiveMessage::new("Initial content".to_string());
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 /// Claude CLI streaming JSON message types
-#[derive(Debug, Deserialize, Serialize)]
+///
+/// Events of a type this doesn't know yet are kept as `Unknown` with their raw JSON, so a newer
+/// Claude CLI doesn't break parsing.
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum ClaudeMessage {
     #[serde(rename = "system")]
@@ -15,6 +18,10 @@ pub enum ClaudeMessage {
         tools: Option<Vec<String>>,
         #[serde(default)]
         model: Option<String>,
+        #[serde(default, rename = "permissionMode")]
+        permission_mode: Option<String>,
+        #[serde(default)]
+        mcp_servers: Option<Vec<McpServerStatus>>,
     },
     #[serde(rename = "assistant")]
     Assistant {
@@ -31,8 +38,12 @@ pub enum ClaudeMessage {
     #[serde(rename = "result")]
     Result {
         subtype: String,
+        #[serde(default)]
         is_error: bool,
+        /// Missing when the run stopped early, e.g. with `error_max_turns`
+        #[serde(default)]
         result: String,
+        #[serde(default)]
         session_id: String,
         #[serde(default)]
         total_cost_usd: Option<f64>,
@@ -43,9 +54,18 @@ pub enum ClaudeMessage {
         #[serde(default)]
         usage: Option<super::Usage>,
     },
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Connection state of an MCP server, from the init message
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpServerStatus {
+    pub name: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AssistantMessage {
     #[serde(default)]
     pub id: Option<String>,
@@ -53,17 +73,76 @@ pub struct AssistantMessage {
     pub content: Option<Vec<ContentBlock>>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct UserMessage {
     #[serde(default)]
-    pub content: Option<Vec<ToolResult>>,
+    pub content: Option<MessageContent>,
+}
+
+/// A `tool_result` block with its content as plain text
+///
+/// Kept for users of the lib crate; the parser reads tool results as
+/// [`ContentBlock::ToolResult`], which also keeps images and the error flag.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ToolResult {
+    #[serde(default)]
+    pub tool_use_id: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Content of a user message or tool result: plain text or a list of blocks
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Blocks(Vec<ContentBlock>),
+}
+
+impl MessageContent {
+    /// The text of the content, joining text blocks with newlines
+    pub fn text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Blocks(blocks) => blocks
+                .iter()
+                .filter_map(|block| match block {
+                    ContentBlock::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    /// Number of image blocks in the content
+    pub fn image_count(&self) -> usize {
+        match self {
+            Self::Text(_) => 0,
+            Self::Blocks(blocks) => blocks
+                .iter()
+                .filter(|block| matches!(block, ContentBlock::Image { .. }))
+                .count(),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum ContentBlock {
     #[serde(rename = "text")]
     Text { text: String },
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        #[serde(default)]
+        signature: Option<String>,
+    },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking {
+        #[serde(default)]
+        data: Option<String>,
+    },
     #[serde(rename = "tool_use")]
     ToolUse {
         id: String,
@@ -71,12 +150,20 @@ pub enum ContentBlock {
         #[serde(default)]
         input: Option<serde_json::Value>,
     },
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ToolResult {
-    #[serde(default)]
-    pub tool_use_id: Option<String>,
-    #[serde(default)]
-    pub content: Option<String>,
+    #[serde(rename = "tool_result")]
+    ToolResult {
+        #[serde(default)]
+        tool_use_id: Option<String>,
+        #[serde(default)]
+        content: Option<MessageContent>,
+        #[serde(default)]
+        is_error: bool,
+    },
+    #[serde(rename = "image")]
+    Image {
+        #[serde(default)]
+        source: Option<serde_json::Value>,
+    },
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
@@ -0,0 +1,107 @@
+use crate::bot::claude_session::ClaudeSession;
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{prelude::*, types::ParseMode};
+
+use super::commit::{auto_commit, AutoCommitOutcome};
+use super::repo::WORKSPACE_DIRECTORY;
+
+/// Handle the /autocommit command
+///
+/// Toggles the per-chat mode that commits all changes after every successful Claude run,
+/// so each turn shows up as its own commit in the history.
+pub async fn handle_auto_commit(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let requested = match args.trim() {
+        "" => None,
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => {
+            bot.send_message(
+                msg.chat.id,
+                "📝 *Auto\\-commit*\n\nUsage:\n• `/autocommit` \\- Show whether auto\\-commit is \
+                 on\n• `/autocommit on` \\- Commit after every successful Claude run\n• \
+                 `/autocommit off` \\- Only commit with /commit",
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let enabled = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        let session = sessions.entry(chat_id).or_insert_with(ClaudeSession::new);
+        if let Some(enabled) = requested {
+            session.auto_commit = enabled;
+        }
+        session.auto_commit
+    };
+
+    let message = if enabled {
+        "📝 *Auto\\-commit is on*\n\nChanges are committed after every successful Claude run, \
+         using the same generated message as /commit\\."
+    } else {
+        "📝 *Auto\\-commit is off*\n\nUse /commit to commit changes, or `/autocommit on` to \
+         commit after every successful Claude run\\."
+    };
+
+    bot.send_message(msg.chat.id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Commit the changes of a successful Claude run when auto-commit is on for the chat
+pub async fn auto_commit_after_run(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    client: &ClaudeCodeClient,
+) -> ResponseResult<()> {
+    // The workspace root spanning all repositories is not a repository itself
+    let enabled = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .map(|session| {
+                session.auto_commit
+                    && session.get_working_directory().map(String::as_str)
+                        != Some(WORKSPACE_DIRECTORY)
+            })
+            .unwrap_or(false)
+    };
+
+    if !enabled {
+        return Ok(());
+    }
+
+    let message = match auto_commit(bot, chat_id, bot_state, user_id, client).await {
+        Ok(AutoCommitOutcome::NothingToCommit | AutoCommitOutcome::OperationInProgress) => {
+            return Ok(())
+        }
+        Ok(AutoCommitOutcome::Committed(commit_message)) => format!(
+            "📝 *Auto\\-committed*\n```\n{}\n```",
+            escape_markdown_v2(&commit_message)
+        ),
+        Err(e) => format!(
+            "⚠️ *Auto\\-commit failed:*\n```\n{}\n```",
+            escape_markdown_v2(&e.to_string())
+        ),
+    };
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&message);
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
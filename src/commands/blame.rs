@@ -0,0 +1,222 @@
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+use super::checkpoints::{send_error, working_dir_client};
+
+/// The commit responsible for a line, from `git blame --porcelain`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    pub sha: String,
+    pub author: String,
+    /// Author time as a Unix timestamp
+    pub time: Option<i64>,
+    pub summary: String,
+    pub content: String,
+}
+
+impl BlameLine {
+    /// Lines that were changed in the working tree have no commit yet
+    pub fn is_uncommitted(&self) -> bool {
+        self.sha.chars().all(|c| c == '0')
+    }
+}
+
+/// Handle the /blame command
+///
+/// Shows which commit last changed a line of a file, with a button to view that commit.
+pub async fn handle_blame(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let Some((file, line)) = parse_blame_args(&args) else {
+        bot.send_message(
+            msg.chat.id,
+            "❓ *Usage:* `/blame <file> <line>`\n\nExample: `/blame src/main\\.rs 42`",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let Some(client) = working_dir_client(&bot, msg.chat.id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let output = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "blame".to_string(),
+            "--porcelain".to_string(),
+            "-L".to_string(),
+            format!("{},{}", line, line),
+            "--".to_string(),
+            file.clone(),
+        ])
+        .await;
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            send_error(&bot, msg.chat.id, "Failed to run git blame", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    let Some(blame) = parse_blame(&output) else {
+        send_error(&bot, msg.chat.id, "Unexpected git blame output", &output).await?;
+        return Ok(());
+    };
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&format_blame(&file, line, &blame));
+    let request = bot
+        .send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2);
+
+    if blame.is_uncommitted() {
+        request.await?;
+    } else {
+        request
+            .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+                InlineKeyboardButton::callback("📄 View commit", format!("log_show:{}", blame.sha)),
+            ]]))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Accepts `<file> <line>` as well as `<file>:<line>`
+fn parse_blame_args(args: &str) -> Option<(String, u64)> {
+    let args = args.trim();
+    let (file, line) = args
+        .rsplit_once(char::is_whitespace)
+        .or_else(|| args.rsplit_once(':'))?;
+
+    let file = file.trim();
+    let line = line.trim().parse::<u64>().ok().filter(|line| *line > 0)?;
+    (!file.is_empty()).then(|| (file.to_string(), line))
+}
+
+fn parse_blame(output: &str) -> Option<BlameLine> {
+    let mut lines = output.lines();
+    let sha = lines.next()?.split_whitespace().next()?.to_string();
+
+    let mut blame = BlameLine {
+        sha,
+        author: String::new(),
+        time: None,
+        summary: String::new(),
+        content: String::new(),
+    };
+
+    for line in lines {
+        if let Some(content) = line.strip_prefix('\t') {
+            blame.content = content.to_string();
+            break;
+        }
+
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "author" => blame.author = value.to_string(),
+            "author-time" => blame.time = value.parse().ok(),
+            "summary" => blame.summary = value.to_string(),
+            _ => {}
+        }
+    }
+
+    Some(blame)
+}
+
+fn format_blame(file: &str, line: u64, blame: &BlameLine) -> String {
+    let location = escape_markdown_v2(&format!("{}:{}", file, line));
+    let content = escape_markdown_v2(&blame.content);
+
+    if blame.is_uncommitted() {
+        return format!(
+            "🔎 `{}`\n```\n{}\n```\n✏️ Not committed yet",
+            location, content
+        );
+    }
+
+    let date = blame
+        .time
+        .and_then(|time| chrono::DateTime::from_timestamp(time, 0))
+        .map(|time| time.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let short_sha = blame.sha.get(..7).unwrap_or(&blame.sha);
+
+    format!(
+        "🔎 `{}`\n```\n{}\n```\n`{}` {}\n_{}, {}_",
+        location,
+        content,
+        short_sha,
+        escape_markdown_v2(&blame.summary),
+        escape_markdown_v2(&blame.author),
+        escape_markdown_v2(&date)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PORCELAIN: &str = "3f2a9c1d0e8b7a6f5e4d3c2b1a09f8e7d6c5b4a3 40 42 1\n\
+        author Ada Lovelace\n\
+        author-mail <ada@example.com>\n\
+        author-time 1700000000\n\
+        author-tz +0000\n\
+        committer Ada Lovelace\n\
+        summary Handle empty input\n\
+        filename src/parser.rs\n\
+        \tif input.is_empty() {";
+
+    #[test]
+    fn test_parse_blame_args() {
+        assert_eq!(
+            parse_blame_args("src/main.rs 42"),
+            Some(("src/main.rs".to_string(), 42))
+        );
+        assert_eq!(
+            parse_blame_args("src/main.rs:7"),
+            Some(("src/main.rs".to_string(), 7))
+        );
+        assert_eq!(parse_blame_args("src/main.rs"), None);
+        assert_eq!(parse_blame_args("src/main.rs 0"), None);
+        assert_eq!(parse_blame_args(""), None);
+    }
+
+    #[test]
+    fn test_parse_blame() {
+        let blame = parse_blame(PORCELAIN).unwrap();
+
+        assert_eq!(blame.sha, "3f2a9c1d0e8b7a6f5e4d3c2b1a09f8e7d6c5b4a3");
+        assert_eq!(blame.author, "Ada Lovelace");
+        assert_eq!(blame.time, Some(1700000000));
+        assert_eq!(blame.summary, "Handle empty input");
+        assert_eq!(blame.content, "if input.is_empty() {");
+        assert!(!blame.is_uncommitted());
+        assert_eq!(parse_blame(""), None);
+    }
+
+    #[test]
+    fn test_format_blame() {
+        let blame = parse_blame(PORCELAIN).unwrap();
+        assert_eq!(
+            format_blame("src/parser.rs", 42, &blame),
+            "🔎 `src/parser\\.rs:42`\n```\nif input\\.is\\_empty\\(\\) \\{\n```\n`3f2a9c1` Handle \
+             empty input\n_Ada Lovelace, 2023\\-11\\-14_"
+        );
+
+        let uncommitted = BlameLine {
+            sha: "0".repeat(40),
+            ..blame
+        };
+        assert!(uncommitted.is_uncommitted());
+        assert!(format_blame("a.rs", 1, &uncommitted).ends_with("Not committed yet"));
+    }
+}
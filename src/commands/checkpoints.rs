@@ -0,0 +1,545 @@
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+/// Ref namespace holding working tree snapshots taken before each Claude run
+const CHECKPOINT_REF_PREFIX: &str = "refs/claude-checkpoints/";
+
+/// Number of checkpoints kept per repository; older ones are pruned
+const MAX_CHECKPOINTS: usize = 20;
+
+/// Number of checkpoints listed by /checkpoints
+const LISTED_CHECKPOINTS: usize = 10;
+
+/// Maximum characters of the prompt used as checkpoint label
+const LABEL_LENGTH: usize = 60;
+
+/// Maximum characters of the diff shown before restoring a checkpoint
+const PREVIEW_DIFF_LENGTH: usize = 2500;
+
+/// Shell function that commits the whole working tree (including untracked, non-ignored
+/// files) to a dangling commit using a temporary index, leaving HEAD, the real index and the
+/// working tree untouched. Prints the commit id. The checked out branch is recorded in a
+/// trailer, and `same_branch` refuses to go on when a checkpoint was taken on another one.
+const SNAPSHOT_FUNCTION: &str = r#"
+current_branch() {
+    git symbolic-ref -q --short HEAD || echo "(detached)"
+}
+same_branch() {
+    recorded=$(git log -1 --format='%(trailers:key=Checkpoint-Branch,valueonly)' "$1" | head -n 1)
+    current=$(current_branch)
+    if [ "$recorded" != "$current" ]; then
+        echo "Checkpoint was taken on branch ${recorded:-unknown}, but $current is checked out. Check out ${recorded:-that branch} first." >&2
+        exit 1
+    fi
+}
+snapshot() {
+    index="$(git rev-parse --absolute-git-dir)/claude-checkpoint-index"
+    rm -f "$index"
+    parent=$(git rev-parse -q --verify HEAD || true)
+    if [ -n "$parent" ]; then GIT_INDEX_FILE="$index" git read-tree "$parent"; fi
+    GIT_INDEX_FILE="$index" git add -A
+    tree=$(GIT_INDEX_FILE="$index" git write-tree)
+    rm -f "$index"
+    git commit-tree --no-gpg-sign "$tree" ${parent:+-p "$parent"} -m "$1" \
+        -m "Checkpoint-Branch: $(current_branch)"
+}
+"#;
+
+/// Create a checkpoint ref ($2) labelled $1, unless nothing changed since the latest one, and
+/// prune all but the newest $3 checkpoints when $3 is given
+const CREATE_SCRIPT: &str = r#"
+git rev-parse --is-inside-work-tree >/dev/null 2>&1 || exit 0
+commit=$(snapshot "$1")
+latest=$(git for-each-ref --count=1 --sort=-refname --format='%(objectname)' refs/claude-checkpoints/)
+if [ -n "$latest" ] \
+    && [ "$(git rev-parse "$latest^{tree}")" = "$(git rev-parse "$commit^{tree}")" ] \
+    && [ "$(git rev-parse -q --verify "$latest^" || true)" = "$(git rev-parse -q --verify HEAD || true)" ] \
+    && (same_branch "$latest") 2>/dev/null; then
+    exit 0
+fi
+git update-ref "refs/claude-checkpoints/$2" "$commit"
+if [ -n "$3" ]; then
+    git for-each-ref --sort=-refname --format='%(refname)' refs/claude-checkpoints/ \
+        | tail -n +$(($3 + 1)) | xargs -r -n1 git update-ref -d
+fi
+echo "$2"
+"#;
+
+/// Show what restoring checkpoint $1 would discard: dropped commits, changed files and the diff
+const PREVIEW_SCRIPT: &str = r#"
+checkpoint="refs/claude-checkpoints/$1"
+git rev-parse -q --verify "$checkpoint" >/dev/null
+same_branch "$checkpoint"
+current=$(snapshot "Undo preview")
+parent=$(git rev-parse -q --verify "$checkpoint^" || true)
+if [ -n "$parent" ]; then
+    commits=$(git log --oneline "$parent..HEAD")
+    if [ -n "$commits" ]; then printf 'Commits to be dropped:\n%s\n\n' "$commits"; fi
+fi
+git diff --stat "$checkpoint" "$current"
+echo
+git diff "$checkpoint" "$current"
+"#;
+
+/// Restore HEAD, index and working tree to checkpoint $1
+const RESTORE_SCRIPT: &str = r#"
+checkpoint="refs/claude-checkpoints/$1"
+git rev-parse -q --verify "$checkpoint" >/dev/null
+same_branch "$checkpoint"
+parent=$(git rev-parse -q --verify "$checkpoint^" || true)
+if [ -n "$parent" ]; then git reset -q --hard "$parent"; fi
+git clean -fdq
+git read-tree -u --reset "$checkpoint"
+if [ -n "$parent" ]; then git reset -q; else git read-tree --empty; fi
+"#;
+
+/// A checkpoint as listed by `git for-each-ref`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub id: String,
+    pub age: String,
+    pub label: String,
+}
+
+/// Snapshot the working tree before a Claude run so it can be restored with /undo
+///
+/// Returns the checkpoint id, or `None` when the directory is not a git repository or
+/// nothing changed since the previous checkpoint.
+pub async fn create_checkpoint(
+    client: &ClaudeCodeClient,
+    prompt: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    take_checkpoint(client, prompt, Some(MAX_CHECKPOINTS)).await
+}
+
+/// Create a checkpoint, keeping only the newest `keep` checkpoints when given
+async fn take_checkpoint(
+    client: &ClaudeCodeClient,
+    prompt: &str,
+    keep: Option<usize>,
+) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let id = chrono::Utc::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+    let keep = keep.map(|keep| keep.to_string()).unwrap_or_default();
+    let output = run_script(
+        client,
+        CREATE_SCRIPT,
+        vec![checkpoint_label(prompt), id, keep],
+    )
+    .await?;
+
+    let created = output.trim();
+    Ok((!created.is_empty()).then(|| created.to_string()))
+}
+
+/// Handle the /checkpoints command
+pub async fn handle_checkpoints(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+) -> ResponseResult<()> {
+    let Some(client) = working_dir_client(&bot, msg.chat.id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let checkpoints = match list_checkpoints(&client, LISTED_CHECKPOINTS).await {
+        Ok(checkpoints) => checkpoints,
+        Err(e) => {
+            send_error(
+                &bot,
+                msg.chat.id,
+                "Failed to list checkpoints",
+                &e.to_string(),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    if checkpoints.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "🕰️ *No Checkpoints Yet*\n\nA checkpoint is taken automatically before each Claude \
+             prompt\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    let mut text = "🕰️ *Checkpoints* \\(newest first\\)\n".to_string();
+    let mut rows = Vec::new();
+    for (position, checkpoint) in checkpoints.iter().enumerate() {
+        text.push_str(&format!(
+            "\n{}\\. _{}_ · {}",
+            position + 1,
+            escape_markdown_v2(&checkpoint.age),
+            escape_markdown_v2(&checkpoint.label)
+        ));
+        rows.push(vec![InlineKeyboardButton::callback(
+            format!("↩️ {}. {}", position + 1, checkpoint.age),
+            format!("undo:{}", checkpoint.id),
+        )]);
+    }
+    text.push_str("\n\nTap a checkpoint to preview what restoring it would discard\\.");
+
+    log::debug!(
+        "Listed {} checkpoints for chat {}",
+        checkpoints.len(),
+        chat_id
+    );
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&text);
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(InlineKeyboardMarkup::new(rows))
+        .await?;
+
+    Ok(())
+}
+
+/// Handle the /undo command: preview restoring the given (or latest) checkpoint
+pub async fn handle_undo(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let checkpoint_id = args.trim();
+    let checkpoint_id = (!checkpoint_id.is_empty()).then_some(checkpoint_id);
+    handle_undo_preview(bot, msg.chat.id, &bot_state, checkpoint_id).await
+}
+
+/// Show the changes that restoring a checkpoint would discard, with confirm buttons
+pub async fn handle_undo_preview(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    checkpoint_id: Option<&str>,
+) -> ResponseResult<()> {
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    let checkpoint = match checkpoint_id {
+        Some(id) => Some(id.to_string()),
+        None => match list_checkpoints(&client, 1).await {
+            Ok(checkpoints) => checkpoints.into_iter().next().map(|c| c.id),
+            Err(e) => {
+                send_error(&bot, chat_id, "Failed to list checkpoints", &e.to_string()).await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let Some(checkpoint) = checkpoint else {
+        bot.send_message(
+            chat_id,
+            "🕰️ *Nothing to Undo*\n\nNo checkpoints have been taken in this repository yet\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let preview = match run_script(&client, PREVIEW_SCRIPT, vec![checkpoint.clone()]).await {
+        Ok(preview) => preview,
+        Err(e) => {
+            send_error(
+                &bot,
+                chat_id,
+                "Failed to preview checkpoint",
+                &e.to_string(),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let (text, keyboard) = if preview.trim().is_empty() {
+        (
+            format!(
+                "✅ *Already at checkpoint* `{}`\n\nThe working tree has not changed since\\.",
+                escape_markdown_v2(&checkpoint)
+            ),
+            None,
+        )
+    } else {
+        (
+            format!(
+                "↩️ *Restore checkpoint* `{}`?\n\nThe following changes will be discarded:\n```\n{}\n```\n\nA checkpoint of the current state is taken first, so this can be undone too\\.",
+                escape_markdown_v2(&checkpoint),
+                escape_markdown_v2(&clip_preview(preview.trim()))
+            ),
+            Some(InlineKeyboardMarkup::new(vec![vec![
+                InlineKeyboardButton::callback("✅ Restore", format!("undo_confirm:{}", checkpoint)),
+                InlineKeyboardButton::callback("❌ Cancel", "undo_cancel"),
+            ]])),
+        )
+    };
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&text);
+    let request = bot
+        .send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2);
+    match keyboard {
+        Some(keyboard) => request.reply_markup(keyboard).await?,
+        None => request.await?,
+    };
+
+    Ok(())
+}
+
+/// Restore a checkpoint after the user confirmed the preview
+pub async fn handle_undo_confirm(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    checkpoint_id: &str,
+) -> ResponseResult<()> {
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    // Without pruning, which could delete the checkpoint being restored; the next Claude run
+    // prunes again
+    let label = format!("undo to {}", checkpoint_id);
+    let safety_checkpoint = match take_checkpoint(&client, &label, None).await {
+        Ok(id) => id,
+        Err(e) => {
+            send_error(
+                &bot,
+                chat_id,
+                "Failed to checkpoint current state",
+                &e.to_string(),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = run_script(&client, RESTORE_SCRIPT, vec![checkpoint_id.to_string()]).await {
+        send_error(
+            &bot,
+            chat_id,
+            "Failed to restore checkpoint",
+            &e.to_string(),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let redo_hint = match safety_checkpoint {
+        Some(id) => format!(
+            "\n\nChanged your mind? `/undo {}` brings the discarded state back\\.",
+            escape_markdown_v2(&id)
+        ),
+        None => String::new(),
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "✅ *Checkpoint restored*\n\nThe working tree is back at `{}`\\.{}",
+            escape_markdown_v2(checkpoint_id),
+            redo_hint
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    Ok(())
+}
+
+async fn list_checkpoints(
+    client: &ClaudeCodeClient,
+    count: usize,
+) -> Result<Vec<Checkpoint>, Box<dyn std::error::Error + Send + Sync>> {
+    let output = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "for-each-ref".to_string(),
+            format!("--count={}", count),
+            "--sort=-refname".to_string(),
+            "--format=%(refname)%09%(creatordate:relative)%09%(subject)".to_string(),
+            CHECKPOINT_REF_PREFIX.to_string(),
+        ])
+        .await?;
+
+    Ok(parse_checkpoint_list(&output))
+}
+
+/// Parse `refname<TAB>age<TAB>subject` lines from `git for-each-ref`
+fn parse_checkpoint_list(output: &str) -> Vec<Checkpoint> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let id = fields.next()?.strip_prefix(CHECKPOINT_REF_PREFIX)?;
+            Some(Checkpoint {
+                id: id.to_string(),
+                age: fields.next().unwrap_or("").to_string(),
+                label: fields.next().unwrap_or("").to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Shorten the preview to fit in a message, keeping the stat and the start of the diff
+fn clip_preview(preview: &str) -> String {
+    if preview.chars().count() <= PREVIEW_DIFF_LENGTH {
+        return preview.to_string();
+    }
+
+    let clipped: String = preview.chars().take(PREVIEW_DIFF_LENGTH).collect();
+    format!("{}\n… (diff truncated)", clipped.trim_end())
+}
+
+/// First line of the prompt, shortened for use as a commit subject
+fn checkpoint_label(prompt: &str) -> String {
+    let line = prompt.lines().next().unwrap_or("").trim();
+    let label = if line.chars().count() > LABEL_LENGTH {
+        format!("{}…", line.chars().take(LABEL_LENGTH).collect::<String>())
+    } else {
+        line.to_string()
+    };
+
+    if label.is_empty() {
+        "Before Claude run".to_string()
+    } else {
+        format!("Before: {}", label)
+    }
+}
+
+/// Run a checkpoint script with `bash -c`, passing arguments positionally
+async fn run_script(
+    client: &ClaudeCodeClient,
+    script: &str,
+    args: Vec<String>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let mut command = vec![
+        "bash".to_string(),
+        "-c".to_string(),
+        format!("set -e\n{}{}", SNAPSHOT_FUNCTION, script),
+        "checkpoint".to_string(),
+    ];
+    command.extend(args);
+
+    client.exec_basic_command(command).await
+}
+
+/// Claude client rooted at the chat's working directory
+pub async fn working_dir_client(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<Option<ClaudeCodeClient>> {
+    let working_directory = {
+        let claude_sessions = bot_state.claude_sessions.lock().await;
+        claude_sessions
+            .get(&chat_id.0)
+            .and_then(|session| session.get_working_directory().cloned())
+    };
+
+    directory_client(bot, chat_id, bot_state, working_directory).await
+}
+
+/// Claude client rooted at `working_directory`, telling the user when there is no session
+pub async fn directory_client(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    working_directory: Option<String>,
+) -> ResponseResult<Option<ClaudeCodeClient>> {
+    let container_name = format!("coding-session-{}", chat_id.0);
+
+    match ClaudeCodeClient::for_session_with_working_dir(
+        bot_state.docker.clone(),
+        &container_name,
+        working_directory,
+    )
+    .await
+    {
+        Ok(client) => Ok(Some(client)),
+        Err(e) => {
+            let full_message = format!(
+                "❌ No active coding session found: {}\n\nPlease start a coding session first \
+                 using /start",
+                escape_markdown_v2(&e.to_string())
+            );
+            let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+
+            bot.send_message(chat_id, message_to_send)
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+pub async fn send_error(
+    bot: &Bot,
+    chat_id: ChatId,
+    title: &str,
+    error: &str,
+) -> ResponseResult<()> {
+    let full_message = format!(
+        "❌ *{}:*\n```\n{}\n```",
+        escape_markdown_v2(title),
+        escape_markdown_v2(error)
+    );
+    let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_checkpoint_list() {
+        let output = "refs/claude-checkpoints/20261018-120102-123\t5 minutes ago\tBefore: fix the tests\n\
+                      refs/claude-checkpoints/20261018-110000-001\t2 hours ago\tBefore: add a\ttab\n\
+                      refs/heads/main\tnow\tunrelated";
+
+        let checkpoints = parse_checkpoint_list(output);
+
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(checkpoints[0].id, "20261018-120102-123");
+        assert_eq!(checkpoints[0].age, "5 minutes ago");
+        assert_eq!(checkpoints[0].label, "Before: fix the tests");
+        assert_eq!(checkpoints[1].label, "Before: add a\ttab");
+    }
+
+    #[test]
+    fn test_clip_preview() {
+        assert_eq!(clip_preview("1 file changed"), "1 file changed");
+
+        let long = format!("1 file changed\n\n{}", "+line\n".repeat(1000));
+        let clipped = clip_preview(&long);
+        assert!(clipped.starts_with("1 file changed\n\n+line"));
+        assert!(clipped.ends_with("\n… (diff truncated)"));
+        assert!(clipped.chars().count() <= PREVIEW_DIFF_LENGTH + 20);
+    }
+
+    #[test]
+    fn test_checkpoint_label() {
+        assert_eq!(checkpoint_label("fix it\nplease"), "Before: fix it");
+        assert_eq!(checkpoint_label("   "), "Before Claude run");
+        let long = "x".repeat(100);
+        assert_eq!(
+            checkpoint_label(&long).chars().count(),
+            "Before: ".len() + LABEL_LENGTH + 1
+        );
+    }
+}
@@ -0,0 +1,634 @@
+use crate::bot::claude_session::{CiFailures, ClaudeSession};
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::github_client::{GithubClient, PrCheck, WorkflowRun};
+use crate::BotState;
+use std::time::Duration;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+use tokio::time::{self, Instant};
+
+/// How often `gh pr checks` (or `gh run list` without a pull request) is polled while checks
+/// are running
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Give up watching after this long
+const WATCH_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Stop waiting if no check shows up for the pushed commit within this time
+const NO_RUNS_TIMEOUT: Duration = Duration::from_secs(3 * 60);
+
+/// Consecutive polling errors tolerated before giving up
+const MAX_POLL_ERRORS: u32 = 5;
+
+/// Characters kept from the end of each failed job log when prompting Claude
+const LOG_TAIL_LENGTH: usize = 12_000;
+
+/// A check of the watched commit, from `gh pr checks` or `gh run list`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Check {
+    name: String,
+    url: String,
+    completed: bool,
+    success: bool,
+    /// Workflow run whose failed job logs can be fed to Claude
+    run_id: Option<u64>,
+}
+
+impl From<WorkflowRun> for Check {
+    fn from(run: WorkflowRun) -> Self {
+        Self {
+            completed: run.is_completed(),
+            success: run.is_success(),
+            run_id: Some(run.database_id),
+            name: run.name,
+            url: run.url,
+        }
+    }
+}
+
+impl From<PrCheck> for Check {
+    fn from(check: PrCheck) -> Self {
+        Self {
+            completed: check.is_completed(),
+            success: check.is_success(),
+            run_id: check.run_id(),
+            name: check.name,
+            url: check.link,
+        }
+    }
+}
+
+/// Overall state of the checks for a commit
+#[derive(Debug, PartialEq, Eq)]
+enum CiState {
+    Pending,
+    Passed,
+    Failed(Vec<u64>),
+}
+
+/// Handle the /ci command: watch the checks of the current branch's HEAD
+pub async fn handle_ci(bot: Bot, msg: Message, bot_state: BotState) -> ResponseResult<()> {
+    let Some(github_client) =
+        super::pr_review::repository_github_client(&bot, msg.chat.id, &bot_state).await?
+    else {
+        return Ok(());
+    };
+
+    watch_head(&bot, msg.chat.id, &bot_state, github_client).await
+}
+
+/// Start watching CI for the commit currently checked out by the client
+pub async fn watch_head(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    github_client: GithubClient,
+) -> ResponseResult<()> {
+    let (branch, sha) = match github_client.head_commit().await {
+        Ok(head) => head,
+        Err(e) => {
+            log::warn!("Cannot watch CI for chat {}: {}", chat_id.0, e);
+            return Ok(());
+        }
+    };
+
+    watch_commit(bot, chat_id, bot_state, github_client, branch, sha).await
+}
+
+/// Commit the upstream of the checked out branch points at, if it has one
+pub async fn upstream_commit(client: &ClaudeCodeClient) -> Option<String> {
+    client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "rev-parse".to_string(),
+            "-q".to_string(),
+            "--verify".to_string(),
+            "@{upstream}".to_string(),
+        ])
+        .await
+        .ok()
+        .map(|sha| sha.trim().to_string())
+        .filter(|sha| !sha.is_empty())
+}
+
+/// Start watching CI when the upstream moved away from `upstream_before`, i.e. a Claude run
+/// pushed the branch
+pub async fn watch_if_pushed(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    client: &ClaudeCodeClient,
+    upstream_before: Option<String>,
+) -> ResponseResult<()> {
+    let Some(upstream) = upstream_commit(client).await else {
+        return Ok(());
+    };
+    if Some(&upstream) == upstream_before.as_ref() {
+        return Ok(());
+    }
+
+    let Some(github_client) =
+        super::pr_review::repository_github_client(bot, chat_id, bot_state).await?
+    else {
+        return Ok(());
+    };
+    let branch = match github_client.head_commit().await {
+        Ok((branch, _)) => branch,
+        Err(e) => {
+            log::warn!("Cannot watch CI for chat {}: {}", chat_id.0, e);
+            return Ok(());
+        }
+    };
+
+    watch_commit(bot, chat_id, bot_state, github_client, branch, upstream).await
+}
+
+/// Watch CI for a pushed commit, replacing an earlier watch of the same branch
+async fn watch_commit(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    github_client: GithubClient,
+    branch: String,
+    sha: String,
+) -> ResponseResult<()> {
+    let already_watching = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        let session = sessions.entry(chat_id.0).or_insert_with(ClaudeSession::new);
+        let previous = session.ci_watches.insert(branch.clone(), sha.clone());
+        previous.as_ref() == Some(&sha)
+    };
+
+    if already_watching {
+        bot.send_message(
+            chat_id,
+            format!(
+                "👀 *Already watching CI* for `{}` at `{}`",
+                escape_markdown_v2(&branch),
+                escape_markdown_v2(short_sha(&sha))
+            ),
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "👀 *Watching CI* for `{}` at `{}`\n\nI'll post an update when the checks finish\\.",
+            escape_markdown_v2(&branch),
+            escape_markdown_v2(short_sha(&sha))
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    tokio::spawn(watch_ci(
+        bot.clone(),
+        chat_id,
+        bot_state.clone(),
+        github_client,
+        branch,
+        sha,
+    ));
+
+    Ok(())
+}
+
+/// Poll the checks of a pushed commit until they all complete, then report
+///
+/// Stops quietly when a newer commit of the branch is watched instead.
+async fn watch_ci(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: BotState,
+    github_client: GithubClient,
+    branch: String,
+    sha: String,
+) {
+    let started = Instant::now();
+    let mut errors = 0;
+
+    let outcome = loop {
+        time::sleep(POLL_INTERVAL).await;
+
+        if !is_watched(&bot_state, chat_id, &branch, &sha).await {
+            log::info!("Stopped watching CI for {}@{}, superseded", branch, sha);
+            return;
+        }
+
+        let checks = match poll_checks(&github_client, &branch, &sha).await {
+            Ok(checks) => {
+                errors = 0;
+                checks
+            }
+            Err(e) => {
+                errors += 1;
+                log::warn!("Failed to poll CI for {}@{}: {}", branch, sha, e);
+                if errors >= MAX_POLL_ERRORS {
+                    break Err(format!("Could not read CI status: {}", e));
+                }
+                continue;
+            }
+        };
+
+        if checks.is_empty() && started.elapsed() >= NO_RUNS_TIMEOUT {
+            break Err("No CI checks were reported for this commit.".to_string());
+        }
+
+        match ci_state(&checks) {
+            CiState::Pending if started.elapsed() >= WATCH_TIMEOUT => {
+                break Err(format!(
+                    "Checks are still running after {} minutes, stopped watching.",
+                    WATCH_TIMEOUT.as_secs() / 60
+                ));
+            }
+            CiState::Pending => continue,
+            state => break Ok((checks, state)),
+        }
+    };
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            if session.ci_watches.get(&branch) == Some(&sha) {
+                session.ci_watches.remove(&branch);
+            }
+        }
+    }
+
+    let result = match outcome {
+        Ok((checks, state)) => {
+            send_ci_report(&bot, chat_id, &bot_state, &branch, &sha, &checks, state).await
+        }
+        Err(reason) => bot
+            .send_message(
+                chat_id,
+                format!(
+                    "⚠️ *CI for* `{}`\n\n{}",
+                    escape_markdown_v2(&branch),
+                    escape_markdown_v2(&reason)
+                ),
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await
+            .map(|_| ()),
+    };
+
+    if let Err(e) = result {
+        log::error!("Failed to send CI report to chat {}: {}", chat_id.0, e);
+    }
+}
+
+/// Whether the commit is still the one watched for the branch
+async fn is_watched(bot_state: &BotState, chat_id: ChatId, branch: &str, sha: &str) -> bool {
+    let sessions = bot_state.claude_sessions.lock().await;
+    sessions
+        .get(&chat_id.0)
+        .and_then(|session| session.ci_watches.get(branch))
+        .is_some_and(|watched| watched == sha)
+}
+
+/// The checks of the branch's pull request, or the workflow runs of the commit without one
+async fn poll_checks(
+    github_client: &GithubClient,
+    branch: &str,
+    sha: &str,
+) -> Result<Vec<Check>, Box<dyn std::error::Error + Send + Sync>> {
+    let checks = match github_client.pr_checks(branch).await? {
+        Some(checks) => checks.into_iter().map(Check::from).collect(),
+        None => github_client
+            .run_list_for_commit(branch, sha)
+            .await?
+            .into_iter()
+            .map(Check::from)
+            .collect(),
+    };
+
+    Ok(checks)
+}
+
+async fn send_ci_report(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    branch: &str,
+    sha: &str,
+    checks: &[Check],
+    state: CiState,
+) -> ResponseResult<()> {
+    let text = format_ci_report(branch, sha, checks, &state);
+    let (message_to_send, _was_truncated) = truncate_if_needed(&text);
+    let request = bot
+        .send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2);
+
+    match state {
+        // Only GitHub Actions runs have logs to fix from
+        CiState::Failed(run_ids) if !run_ids.is_empty() => {
+            {
+                let mut sessions = bot_state.claude_sessions.lock().await;
+                if let Some(session) = sessions.get_mut(&chat_id.0) {
+                    session.ci_failures = Some(CiFailures {
+                        branch: branch.to_string(),
+                        run_ids,
+                    });
+                }
+            }
+
+            request
+                .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+                    InlineKeyboardButton::callback("🛠️ Fix failures", "ci_fix"),
+                ]]))
+                .await?;
+        }
+        _ => {
+            request.await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Feed the failed job logs to Claude so it can fix the build
+pub async fn handle_ci_fix(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+) -> ResponseResult<()> {
+    let failures = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .and_then(|session| session.ci_failures.clone())
+    };
+
+    let Some(failures) = failures else {
+        bot.send_message(
+            chat_id,
+            "ℹ️ No failed CI runs to fix\\. Use /ci to watch the current branch\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let Some(github_client) =
+        super::pr_review::repository_github_client(&bot, chat_id, bot_state).await?
+    else {
+        return Ok(());
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "📥 *Fetching failed job logs for* `{}`\\.\\.\\.",
+            escape_markdown_v2(&failures.branch)
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    let mut logs = Vec::new();
+    for run_id in &failures.run_ids {
+        match github_client.run_failed_logs(*run_id).await {
+            Ok(log) => logs.push((*run_id, log)),
+            Err(e) => logs.push((*run_id, format!("(failed to fetch logs: {})", e))),
+        }
+    }
+
+    let prompt = build_fix_prompt(&failures.branch, &logs);
+
+    let conversation_id = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions.get_mut(&chat_id.0).and_then(|session| {
+            session.ci_failures = None;
+            // Keep the conversation open so the user can follow up on the fix
+            session.is_active = true;
+            session.conversation_id.clone()
+        })
+    };
+
+    if let Err(e) = super::execute_claude_command(
+        bot.clone(),
+        chat_id,
+        bot_state.clone(),
+        user_id,
+        &prompt,
+        conversation_id,
+    )
+    .await
+    {
+        let full_message = format!(
+            "❌ Claude command failed: {}",
+            escape_markdown_v2(&e.to_string())
+        );
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+        bot.send_message(chat_id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Combine the states of all checks: any failure fails, any unfinished check is pending
+///
+/// A failed state carries the workflow runs of the failed checks, once each.
+fn ci_state(checks: &[Check]) -> CiState {
+    if checks.is_empty() || checks.iter().any(|check| !check.completed) {
+        return CiState::Pending;
+    }
+
+    let failed: Vec<&Check> = checks.iter().filter(|check| !check.success).collect();
+    if failed.is_empty() {
+        return CiState::Passed;
+    }
+
+    let mut run_ids = Vec::new();
+    for run_id in failed.iter().filter_map(|check| check.run_id) {
+        if !run_ids.contains(&run_id) {
+            run_ids.push(run_id);
+        }
+    }
+    CiState::Failed(run_ids)
+}
+
+fn format_ci_report(branch: &str, sha: &str, checks: &[Check], state: &CiState) -> String {
+    let headline = match state {
+        CiState::Passed => "✅ *CI passed*",
+        CiState::Failed(_) => "❌ *CI failed*",
+        CiState::Pending => "⏳ *CI running*",
+    };
+
+    let mut text = format!(
+        "{} for `{}` at `{}`\n",
+        headline,
+        escape_markdown_v2(branch),
+        escape_markdown_v2(short_sha(sha))
+    );
+
+    for check in checks {
+        let icon = if check.success {
+            "✅"
+        } else if check.completed {
+            "❌"
+        } else {
+            "⏳"
+        };
+        let name = escape_markdown_v2(&check.name);
+        if check.url.is_empty() {
+            text.push_str(&format!("\n{} {}", icon, name));
+        } else {
+            text.push_str(&format!(
+                "\n{} [{}]({})",
+                icon,
+                name,
+                check.url.replace('\\', "\\\\").replace(')', "\\)")
+            ));
+        }
+    }
+
+    text
+}
+
+fn build_fix_prompt(branch: &str, logs: &[(u64, String)]) -> String {
+    let mut prompt = format!(
+        "The CI checks for branch `{}` failed. Below are the logs of the failed jobs. Find the \
+         root cause, fix it in the code, and verify the fix locally where possible. Do not \
+         push.\n",
+        branch
+    );
+
+    for (run_id, log) in logs {
+        prompt.push_str(&format!(
+            "\n--- Failed jobs of workflow run {} ---\n{}\n",
+            run_id,
+            log_tail(log, LOG_TAIL_LENGTH)
+        ));
+    }
+
+    prompt
+}
+
+/// Keep the end of a log, where the failure usually is
+fn log_tail(log: &str, max_chars: usize) -> String {
+    let total = log.chars().count();
+    if total <= max_chars {
+        log.to_string()
+    } else {
+        let tail: String = log.chars().skip(total - max_chars).collect();
+        format!(
+            "[... {} earlier characters omitted ...]\n{}",
+            total - max_chars,
+            tail
+        )
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: u64, status: &str, conclusion: Option<&str>) -> Check {
+        WorkflowRun {
+            database_id: id,
+            name: format!("job-{}", id),
+            status: status.to_string(),
+            conclusion: conclusion.map(str::to_string),
+            head_branch: "feature".to_string(),
+            head_sha: "0123456789abcdef".to_string(),
+            url: String::new(),
+        }
+        .into()
+    }
+
+    fn pr_check(name: &str, bucket: &str, link: &str) -> Check {
+        PrCheck {
+            name: name.to_string(),
+            state: String::new(),
+            bucket: bucket.to_string(),
+            link: link.to_string(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_ci_state() {
+        assert_eq!(ci_state(&[]), CiState::Pending);
+        assert_eq!(
+            ci_state(&[
+                run(1, "completed", Some("success")),
+                run(2, "in_progress", None)
+            ]),
+            CiState::Pending
+        );
+        assert_eq!(
+            ci_state(&[
+                run(1, "completed", Some("success")),
+                run(2, "completed", Some("skipped"))
+            ]),
+            CiState::Passed
+        );
+        assert_eq!(
+            ci_state(&[
+                run(1, "completed", Some("failure")),
+                run(2, "completed", Some("success")),
+                run(3, "completed", Some("cancelled"))
+            ]),
+            CiState::Failed(vec![1, 3])
+        );
+    }
+
+    #[test]
+    fn test_ci_state_of_pr_checks() {
+        let runs = "https://github.com/o/r/actions/runs";
+        assert_eq!(
+            ci_state(&[
+                pr_check("build", "pass", &format!("{}/5/job/1", runs)),
+                pr_check("deploy", "pending", "https://ci.example.com/1")
+            ]),
+            CiState::Pending
+        );
+        assert_eq!(
+            ci_state(&[
+                pr_check("test", "fail", &format!("{}/5/job/2", runs)),
+                pr_check("lint", "fail", &format!("{}/5/job/3", runs)),
+                pr_check("external", "fail", "https://ci.example.com/1"),
+                pr_check("docs", "skipping", "")
+            ]),
+            CiState::Failed(vec![5])
+        );
+    }
+
+    #[test]
+    fn test_build_fix_prompt_keeps_log_tail() {
+        let long_log = format!("{}ERROR: test failed", "x".repeat(LOG_TAIL_LENGTH));
+        let prompt = build_fix_prompt("feature", &[(7, long_log)]);
+
+        assert!(prompt.contains("branch `feature`"));
+        assert!(prompt.contains("workflow run 7"));
+        assert!(prompt.contains("ERROR: test failed"));
+        assert!(prompt.contains("earlier characters omitted"));
+        assert!(prompt.len() < LOG_TAIL_LENGTH + 1_000);
+    }
+
+    #[test]
+    fn test_format_ci_report() {
+        let runs = [run(1, "completed", Some("failure"))];
+        let text = format_ci_report("feat/x", "0123456789", &runs, &CiState::Failed(vec![1]));
+
+        assert!(text.starts_with("❌ *CI failed*"));
+        assert!(text.contains("`0123456`"));
+        assert!(text.contains("❌ job\\-1"));
+    }
+}
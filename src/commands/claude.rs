@@ -1,10 +1,12 @@
+use crate::bot::claude_session::PendingFork;
 use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
 use crate::BotState;
 use futures_util::StreamExt;
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use telegram_bot::claude_code_client::{
     ClaudeCodeClient, ClaudeExecutionResult, ClaudeMessageParser, LiveMessage, MessageType,
-    ParseResult, ParsedClaudeMessage,
+    ParseResult, ParsedClaudeMessage, ResultSubtype, ToolCall, ToolCallStatus, ToolCallTracker,
 };
 use teloxide::{
     prelude::*,
@@ -12,6 +14,12 @@ use teloxide::{
 };
 use tokio::time;
 
+/// Characters of Claude's thinking shown in the chat
+const THINKING_PREVIEW_LENGTH: usize = 800;
+
+/// Characters of a failed tool's output shown in the chat
+const TOOL_ERROR_PREVIEW_LENGTH: usize = 1000;
+
 /// Handle the /claude command
 pub async fn handle_claude(
     bot: Bot,
@@ -70,6 +78,7 @@ pub async fn execute_claude_command(
     bot: Bot,
     chat_id: ChatId,
     bot_state: BotState,
+    user_id: i64,
     prompt: &str,
     conversation_id: Option<String>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -79,42 +88,205 @@ pub async fn execute_claude_command(
         prompt
     );
 
+    if !super::usage::check_budget(&bot, chat_id, &bot_state, user_id).await? {
+        return Ok(());
+    }
+
     let container_name = format!("coding-session-{}", chat_id.0);
 
-    // Get working directory from session state
-    let working_directory = {
+    // Get working directory, custom instructions and limits from session state
+    let (working_directory, custom_instructions, max_turns, run_timeout) = {
         let claude_sessions = bot_state.claude_sessions.lock().await;
         claude_sessions
             .get(&chat_id.0)
-            .and_then(|session| session.get_working_directory().cloned())
+            .map(|session| {
+                (
+                    session.get_working_directory().cloned(),
+                    session.custom_instructions.clone(),
+                    session.max_turns,
+                    session.run_timeout,
+                )
+            })
+            .unwrap_or_default()
     };
 
     let client = ClaudeCodeClient::for_session_with_working_dir(
         bot_state.docker.clone(),
         &container_name,
-        working_directory,
+        working_directory.clone(),
     )
-    .await?;
-
-    // Execute Claude prompt with streaming or batch processing
-    match client
-        .execute_claude_prompt(prompt, conversation_id.as_deref())
-        .await?
-    {
-        ClaudeExecutionResult::Streaming(mut stream) => {
-            log::info!("Using streaming execution for Claude command");
-            process_claude_streaming(bot, chat_id, &mut stream, bot_state.clone()).await?;
-        }
-        ClaudeExecutionResult::Batch(output) => {
-            log::info!("Using batch processing for Claude command");
-            process_claude_batch(bot, chat_id, output, bot_state.clone()).await?;
+    .await?
+    .with_append_system_prompt(custom_instructions)
+    .with_max_turns(max_turns);
+
+    // Start the MCP servers from /mcp, if any
+    let mcp_config = super::mcp::load_mcp_config(&client)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load MCP servers for chat {}: {}", chat_id.0, e);
+            None
+        });
+    let client = client.with_mcp_config(mcp_config);
+
+    // Snapshot the working tree first so the run can be reverted with /undo
+    match super::checkpoints::create_checkpoint(&client, prompt).await {
+        Ok(Some(checkpoint)) => {
+            log::info!("Created checkpoint {} for chat {}", checkpoint, chat_id.0)
         }
+        Ok(None) => log::debug!("No checkpoint needed for chat {}", chat_id.0),
+        Err(e) => log::warn!("Failed to create checkpoint for chat {}: {}", chat_id.0, e),
+    }
+
+    // Remember where the branch's upstream is, to notice when Claude pushes
+    let upstream_before = super::ci::upstream_commit(&client).await;
+
+    // Forget the previous run's cost so a run without a result isn't charged for it, and pick
+    // up a /fork of the conversation being resumed
+    let fork = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions.get_mut(&chat_id.0).and_then(|session| {
+            session.last_run_cost = None;
+            session
+                .pending_fork
+                .take()
+                .filter(|fork| Some(&fork.conversation_id) == conversation_id.as_ref())
+        })
+    };
+
+    // Execute Claude prompt with streaming or batch processing, within the chat's time limit
+    let deadline = run_timeout.map(|timeout| time::Instant::now() + timeout);
+    let execution = until_deadline(deadline, async {
+        let execution = match &fork {
+            Some(fork) => {
+                client
+                    .execute_claude_fork(prompt, &fork.conversation_id)
+                    .await?
+            }
+            None => {
+                let interactive = super::interactive::execute_interactive_turn(
+                    &bot_state,
+                    chat_id.0,
+                    &client,
+                    prompt,
+                    conversation_id.as_deref(),
+                )
+                .await;
+                match interactive {
+                    Some(execution) => execution,
+                    None => {
+                        client
+                            .execute_claude_prompt(prompt, conversation_id.as_deref())
+                            .await?
+                    }
+                }
+            }
+        };
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(execution)
+    })
+    .await;
+    let outcome = async {
+        let outcome = match execution.transpose()? {
+            Some(ClaudeExecutionResult::Streaming(mut stream)) => {
+                log::info!("Using streaming execution for Claude command");
+                process_claude_streaming(
+                    bot.clone(),
+                    chat_id,
+                    &mut stream,
+                    bot_state.clone(),
+                    deadline,
+                )
+                .await?
+            }
+            Some(ClaudeExecutionResult::Batch(output)) => {
+                log::info!("Using batch processing for Claude command");
+                process_claude_batch(bot.clone(), chat_id, output, bot_state.clone()).await?
+            }
+            None => RunOutcome::TimedOut,
+        };
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(outcome)
+    }
+    .await;
+
+    // Also when the run failed, so its cost is still charged and a /fork that never branched off
+    // is kept for the next prompt
+    let cost = record_conversation(
+        &bot_state,
+        chat_id.0,
+        conversation_id.as_deref(),
+        fork,
+        prompt,
+    )
+    .await;
+    if let Some(cost) = cost.filter(|cost| *cost > 0.0) {
+        super::usage::record_run_cost(
+            &bot,
+            chat_id,
+            &bot_state,
+            user_id,
+            working_directory.clone(),
+            cost,
+        )
+        .await?;
+    }
+    let outcome = outcome?;
+
+    if let (RunOutcome::TimedOut, Some(timeout)) = (outcome, run_timeout) {
+        super::limits::handle_run_timeout(
+            &bot,
+            chat_id,
+            &bot_state,
+            &client,
+            working_directory.as_deref(),
+            timeout,
+        )
+        .await?;
     }
 
+    if outcome == RunOutcome::Succeeded {
+        super::auto_commit::auto_commit_after_run(&bot, chat_id, &bot_state, user_id, &client)
+            .await?;
+    }
+
+    super::ci::watch_if_pushed(&bot, chat_id, &bot_state, &client, upstream_before).await?;
+
     Ok(())
 }
 
-/// Process Claude streaming output
+/// How a Claude run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunOutcome {
+    /// Finished with a successful result message
+    Succeeded,
+    /// Failed, or ended without a result message
+    Failed,
+    /// Stopped at the chat's turn limit
+    TurnLimit,
+    /// Still running at the chat's time limit
+    TimedOut,
+}
+
+impl RunOutcome {
+    fn from_result(subtype: &ResultSubtype, is_error: bool) -> Self {
+        match subtype {
+            ResultSubtype::ErrorMaxTurns => Self::TurnLimit,
+            _ if is_error || subtype.is_error() => Self::Failed,
+            _ => Self::Succeeded,
+        }
+    }
+}
+
+/// Run `future` to completion, or until `deadline` if there is one; `None` when it passed
+async fn until_deadline<F: std::future::Future>(
+    deadline: Option<time::Instant>,
+    future: F,
+) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+/// Process Claude streaming output, until `deadline` if there is one
 async fn process_claude_streaming(
     bot: Bot,
     chat_id: ChatId,
@@ -122,8 +294,12 @@ async fn process_claude_streaming(
         Box<dyn futures_util::Stream<Item = Result<ParsedClaudeMessage, String>> + Send>,
     >,
     bot_state: BotState,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    deadline: Option<time::Instant>,
+) -> Result<RunOutcome, Box<dyn std::error::Error + Send + Sync>> {
     let mut current_live_message: Option<(MessageId, LiveMessage)> = None;
+    let mut outcome = RunOutcome::Failed;
+    let mut tool_calls = ToolCallTracker::new();
+    let mut tool_messages: HashMap<String, MessageId> = HashMap::new();
 
     // Send typing indicator
     bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing)
@@ -147,7 +323,14 @@ async fn process_claude_streaming(
     });
 
     // Process streaming events (now already parsed)
-    while let Some(message_result) = stream.next().await {
+    loop {
+        let Some(next) = until_deadline(deadline, stream.next()).await else {
+            outcome = RunOutcome::TimedOut;
+            break;
+        };
+        let Some(message_result) = next else {
+            break;
+        };
         match message_result {
             Ok(parsed) => {
                 // Update conversation ID if available
@@ -157,7 +340,8 @@ async fn process_claude_streaming(
 
                 // Handle real-time events that need immediate processing
                 match &parsed.message_type {
-                    MessageType::SystemInit { .. } => {
+                    MessageType::SystemInit { tools, .. } => {
+                        update_mcp_tools(&bot_state, chat_id.0, tools.clone()).await;
                         bot.send_message(chat_id, "🤖 *Claude session initialized*")
                             .parse_mode(ParseMode::MarkdownV2)
                             .await?;
@@ -171,23 +355,32 @@ async fn process_claude_streaming(
                         )
                         .await?;
                     }
-                    MessageType::AssistantToolUse { name, input, .. } => {
+                    MessageType::AssistantThinking { thinking, .. } => {
+                        bot.send_message(chat_id, format_thinking(thinking))
+                            .parse_mode(ParseMode::MarkdownV2)
+                            .await?;
+                    }
+                    MessageType::AssistantToolUse {
+                        id, name, input, ..
+                    } => {
                         let input_str = input
                             .as_ref()
                             .map(|v| serde_json::to_string_pretty(v).unwrap_or_default())
                             .unwrap_or_default();
-                        let tool_message = format!(
-                            "🔧 *Using tool: {}*\n```json\n{}\n```",
-                            escape_markdown_v2(name),
-                            escape_markdown_v2(&input_str)
-                        );
-                        let (message_to_send, _was_truncated) = truncate_if_needed(&tool_message);
+                        let call = tool_calls.start(id.clone(), name.clone(), input_str);
+                        let (message_to_send, _was_truncated) =
+                            truncate_if_needed(&format_tool_call(call));
 
-                        bot.send_message(chat_id, message_to_send)
+                        let sent = bot
+                            .send_message(chat_id, message_to_send)
                             .parse_mode(ParseMode::MarkdownV2)
                             .await?;
+                        tool_messages.insert(id.clone(), sent.id);
                     }
                     MessageType::Result {
+                        result,
+                        subtype,
+                        conversation_id,
                         is_error,
                         cost,
                         duration_ms,
@@ -195,6 +388,9 @@ async fn process_claude_streaming(
                         usage,
                         ..
                     } => {
+                        update_last_run_result(&bot_state, chat_id.0, result.clone(), *cost).await;
+                        outcome = RunOutcome::from_result(subtype, *is_error);
+
                         let mut summary_parts = Vec::new();
 
                         // Cost summary
@@ -233,22 +429,58 @@ async fn process_claude_streaming(
                             summary_parts.join(" • ")
                         };
 
-                        let status_emoji = if *is_error { "❌" } else { "✅" };
-
-                        let summary_message = format!(
-                            "{} *Claude Run Summary*\n{}",
-                            status_emoji,
-                            escape_markdown_v2(&summary_body)
-                        );
+                        let summary_message = format_run_summary(subtype, *is_error, &summary_body);
 
                         let (message_to_send, _was_truncated) = truncate_if_needed(&summary_message);
 
-                        bot.send_message(chat_id, message_to_send)
-                            .parse_mode(ParseMode::MarkdownV2)
-                            .await?;
+                        let mut request = bot
+                            .send_message(chat_id, message_to_send)
+                            .parse_mode(ParseMode::MarkdownV2);
+                        if outcome == RunOutcome::TurnLimit {
+                            request = request
+                                .reply_markup(super::limits::continue_keyboard(conversation_id));
+                        }
+                        request.await?;
+                    }
+                    MessageType::UserToolResult {
+                        tool_use_id,
+                        content,
+                        is_error,
+                        ..
+                    } => {
+                        // Show the outcome on the tool's message; only failures get their output
+                        // sent to chat
+                        let finished = tool_use_id.as_deref().and_then(|id| {
+                            Some((tool_messages.get(id)?, tool_calls.finish(id, *is_error)?))
+                        });
+                        if let Some((message_id, call)) = finished {
+                            let (message_to_send, _was_truncated) =
+                                truncate_if_needed(&format_tool_call(call));
+                            if let Err(e) = bot
+                                .edit_message_text(chat_id, *message_id, message_to_send)
+                                .parse_mode(ParseMode::MarkdownV2)
+                                .await
+                            {
+                                log::warn!("Failed to update tool message: {}", e);
+                            }
+                        }
+
+                        if *is_error {
+                            let (message_to_send, _was_truncated) =
+                                truncate_if_needed(&format_tool_error(content));
+                            bot.send_message(chat_id, message_to_send)
+                                .parse_mode(ParseMode::MarkdownV2)
+                                .await?;
+                        }
                     }
-                    MessageType::UserToolResult { .. } => {
-                        // Tool results are no longer sent to chat
+                    MessageType::Unknown {
+                        event_type, raw, ..
+                    } => {
+                        log::debug!(
+                            "Ignoring unknown Claude event type {:?}: {}",
+                            event_type,
+                            raw
+                        );
                     }
                     _ => {}
                 }
@@ -287,7 +519,7 @@ async fn process_claude_streaming(
 
     // Final processing complete
 
-    Ok(())
+    Ok(outcome)
 }
 
 /// Process Claude batch output
@@ -296,77 +528,154 @@ async fn process_claude_batch(
     chat_id: ChatId,
     output: String,
     bot_state: BotState,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<RunOutcome, Box<dyn std::error::Error + Send + Sync>> {
     // Process batch output directly
+    let mut outcome = RunOutcome::Failed;
+
+    // Parse all lines using the message parser, one message per content block
+    let messages = ClaudeMessageParser::parse_lines(&output)
+        .into_iter()
+        .flat_map(|parse_result| match parse_result {
+            ParseResult::Messages(messages) => messages,
+            ParseResult::PlainText(_) | ParseResult::Empty => Vec::new(),
+        });
+
+    for parsed in messages {
+        // Update conversation ID if available
+        if let Some(conversation_id) = &parsed.conversation_id {
+            update_conversation_id(&bot_state, chat_id.0, conversation_id.clone()).await;
+        }
 
-    // Parse all lines using the message parser
-    let parse_results = ClaudeMessageParser::parse_lines(&output);
-
-    for parse_result in parse_results {
-        match parse_result {
-            ParseResult::Message(parsed) => {
-                // Update conversation ID if available
-                if let Some(conversation_id) = &parsed.conversation_id {
-                    update_conversation_id(&bot_state, chat_id.0, conversation_id.clone()).await;
-                }
-
-                // If this is a result message, send summary
-                if let MessageType::Result {
-                    is_error,
-                    cost,
-                    duration_ms,
-                    num_turns,
-                    usage,
-                    ..
-                } = parsed.message_type
-                {
-                    let mut summary_parts = Vec::new();
-
-                    if let Some(c) = cost {
-                        summary_parts.push(format!("Cost: ${:.4}", c));
-                    }
+        if let MessageType::SystemInit { tools, .. } = &parsed.message_type {
+            update_mcp_tools(&bot_state, chat_id.0, tools.clone()).await;
+        }
 
-                    if let Some(d) = duration_ms {
-                        summary_parts.push(format!("Duration: {}ms", d));
-                    }
+        // If this is a result message, send summary
+        if let MessageType::Result {
+            result,
+            subtype,
+            conversation_id,
+            is_error,
+            cost,
+            duration_ms,
+            num_turns,
+            usage,
+            ..
+        } = parsed.message_type
+        {
+            update_last_run_result(&bot_state, chat_id.0, result, cost).await;
+            outcome = RunOutcome::from_result(&subtype, is_error);
+
+            let mut summary_parts = Vec::new();
+
+            if let Some(c) = cost {
+                summary_parts.push(format!("Cost: ${:.4}", c));
+            }
 
-                    if let Some(t) = num_turns {
-                        summary_parts.push(format!("Turns: {}", t));
-                    }
+            if let Some(d) = duration_ms {
+                summary_parts.push(format!("Duration: {}ms", d));
+            }
 
-                    if let Some(u) = usage {
-                        summary_parts.push(format!("Tokens: {} in / {} out", u.input_tokens, u.output_tokens));
-                    }
+            if let Some(t) = num_turns {
+                summary_parts.push(format!("Turns: {}", t));
+            }
 
-                    let summary_body = if summary_parts.is_empty() {
-                        "Run completed".to_string()
-                    } else {
-                        summary_parts.join(" • ")
-                    };
+            if let Some(u) = usage {
+                summary_parts.push(format!(
+                    "Tokens: {} in / {} out",
+                    u.input_tokens, u.output_tokens
+                ));
+            }
 
-                    let status_emoji = if is_error { "❌" } else { "✅" };
+            let summary_body = if summary_parts.is_empty() {
+                "Run completed".to_string()
+            } else {
+                summary_parts.join(" • ")
+            };
 
-                    let summary_message = format!(
-                        "{} *Claude Run Summary*\n{}",
-                        status_emoji,
-                        escape_markdown_v2(&summary_body)
-                    );
+            let summary_message = format_run_summary(&subtype, is_error, &summary_body);
 
-                    let (message_to_send, _was_truncated) = truncate_if_needed(&summary_message);
+            let (message_to_send, _was_truncated) = truncate_if_needed(&summary_message);
 
-                    bot.send_message(chat_id, message_to_send)
-                        .parse_mode(ParseMode::MarkdownV2)
-                        .await?;
-                }
+            let mut request = bot
+                .send_message(chat_id, message_to_send)
+                .parse_mode(ParseMode::MarkdownV2);
+            if outcome == RunOutcome::TurnLimit {
+                request = request.reply_markup(super::limits::continue_keyboard(&conversation_id));
             }
-            ParseResult::PlainText(_) => {}
-            ParseResult::Empty => {}
+            request.await?;
         }
     }
 
     log::info!("Batch processing completed for chat {}", chat_id.0);
 
-    Ok(())
+    Ok(outcome)
+}
+
+/// Claude's thinking, as a short italic preview
+fn format_thinking(thinking: &str) -> String {
+    if thinking.trim().is_empty() {
+        return "💭 _Thinking \\(redacted\\)_".to_string();
+    }
+    format!(
+        "💭 *Thinking*\n_{}_",
+        escape_markdown_v2(&clip(thinking.trim(), THINKING_PREVIEW_LENGTH))
+    )
+}
+
+/// A tool call with its input and whether it's still running, finished or failed
+fn format_tool_call(call: &ToolCall) -> String {
+    let status = match call.status {
+        ToolCallStatus::Running => "⏳ running".to_string(),
+        ToolCallStatus::Done(duration) => {
+            format!("✅ done ({:.1}s)", duration.as_secs_f64())
+        }
+        ToolCallStatus::Failed(duration) => {
+            format!("❌ failed ({:.1}s)", duration.as_secs_f64())
+        }
+    };
+    format!(
+        "🔧 *{}* \\- {}\n```json\n{}\n```",
+        escape_markdown_v2(&call.name),
+        escape_markdown_v2(&status),
+        escape_markdown_v2(&call.input)
+    )
+}
+
+/// Output of a tool call that failed
+fn format_tool_error(content: &str) -> String {
+    format!(
+        "⚠️ *Tool error*\n```\n{}\n```",
+        escape_markdown_v2(&clip(content.trim(), TOOL_ERROR_PREVIEW_LENGTH))
+    )
+}
+
+/// Summary of a finished run, calling out runs stopped by the turn limit
+fn format_run_summary(subtype: &ResultSubtype, is_error: bool, summary_body: &str) -> String {
+    let heading = match subtype {
+        ResultSubtype::ErrorMaxTurns => "⏸️ *Turn limit reached*",
+        _ if is_error || subtype.is_error() => "❌ *Claude Run Summary*",
+        _ => "✅ *Claude Run Summary*",
+    };
+    let mut message = format!("{}\n{}", heading, escape_markdown_v2(summary_body));
+
+    if *subtype == ResultSubtype::ErrorMaxTurns {
+        message.push_str(
+            "\n\nClaude stopped at the turn limit before finishing\\. Press Continue to resume \
+             it, or change the limit with /limits\\.",
+        );
+    }
+    message
+}
+
+/// Cut text to at most `max_chars` characters, marking the cut with an ellipsis
+fn clip(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut clipped: String = text.chars().take(max_chars - 1).collect();
+    clipped.push('…');
+    clipped
 }
 
 /// Update live message for streaming
@@ -444,3 +753,131 @@ async fn update_conversation_id(bot_state: &BotState, chat_id: i64, conversation
         );
     }
 }
+
+/// Remember the tools Claude reported at startup, so /mcp can tell which servers connected
+async fn update_mcp_tools(bot_state: &BotState, chat_id: i64, tools: Vec<String>) {
+    let mut sessions = bot_state.claude_sessions.lock().await;
+    if let Some(session) = sessions.get_mut(&chat_id) {
+        session.mcp_tools = Some(tools);
+    }
+}
+
+/// Add the run to the session's conversation history for /history and return its cost
+async fn record_conversation(
+    bot_state: &BotState,
+    chat_id: i64,
+    resumed_id: Option<&str>,
+    fork: Option<PendingFork>,
+    prompt: &str,
+) -> Option<f64> {
+    let mut sessions = bot_state.claude_sessions.lock().await;
+    let session = sessions.get_mut(&chat_id)?;
+    let cost = session.last_run_cost;
+    let Some(conversation_id) = session.conversation_id.clone() else {
+        return cost;
+    };
+
+    match fork {
+        Some(fork) if conversation_id != fork.conversation_id => {
+            session.record_fork(fork, &conversation_id, prompt, cost);
+        }
+        Some(fork) => {
+            // The run never got a session id of its own, so try forking again next time
+            session.record_conversation(resumed_id, &conversation_id, prompt, cost);
+            session.pending_fork = Some(fork);
+        }
+        None => session.record_conversation(resumed_id, &conversation_id, prompt, cost),
+    }
+
+    cost
+}
+
+/// Remember the final result text and cost of the latest Claude run
+async fn update_last_run_result(
+    bot_state: &BotState,
+    chat_id: i64,
+    result: String,
+    cost: Option<f64>,
+) {
+    let mut sessions = bot_state.claude_sessions.lock().await;
+    if let Some(session) = sessions.get_mut(&chat_id) {
+        session.last_run_result = Some(result);
+        session.last_run_cost = cost;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_thinking() {
+        assert_eq!(
+            format_thinking("Check the tests first."),
+            "💭 *Thinking*\n_Check the tests first\\._"
+        );
+        assert_eq!(format_thinking(""), "💭 _Thinking \\(redacted\\)_");
+        assert!(format_thinking(&"x".repeat(2000)).ends_with("…_"));
+    }
+
+    #[test]
+    fn test_format_tool_call() {
+        let mut tracker = ToolCallTracker::new();
+        let call = tracker.start(
+            "t1".to_string(),
+            "Bash".to_string(),
+            "{\"command\": \"ls\"}".to_string(),
+        );
+        assert_eq!(
+            format_tool_call(call),
+            "🔧 *Bash* \\- ⏳ running\n```json\n\\{\"command\": \"ls\"\\}\n```"
+        );
+
+        let call = |status| ToolCall {
+            id: "t1".to_string(),
+            name: "Read".to_string(),
+            input: String::new(),
+            started_at: Instant::now(),
+            status,
+        };
+        assert!(
+            format_tool_call(&call(ToolCallStatus::Done(Duration::from_millis(1234))))
+                .starts_with("🔧 *Read* \\- ✅ done \\(1\\.2s\\)")
+        );
+        assert!(
+            format_tool_call(&call(ToolCallStatus::Failed(Duration::from_secs(3))))
+                .starts_with("🔧 *Read* \\- ❌ failed \\(3\\.0s\\)")
+        );
+    }
+
+    #[test]
+    fn test_format_tool_error() {
+        assert_eq!(
+            format_tool_error("No such file: a.rs\n"),
+            "⚠️ *Tool error*\n```\nNo such file: a\\.rs\n```"
+        );
+    }
+
+    #[test]
+    fn test_format_run_summary() {
+        assert_eq!(
+            format_run_summary(&ResultSubtype::Success, false, "Turns: 2"),
+            "✅ *Claude Run Summary*\nTurns: 2"
+        );
+        assert_eq!(
+            format_run_summary(&ResultSubtype::ErrorDuringExecution, false, "Turns: 2"),
+            "❌ *Claude Run Summary*\nTurns: 2"
+        );
+        assert_eq!(
+            format_run_summary(&ResultSubtype::ErrorMaxTurns, true, "Turns: 10"),
+            "⏸️ *Turn limit reached*\nTurns: 10\n\nClaude stopped at the turn limit before \
+             finishing\\. Press Continue to resume it, or change the limit with /limits\\."
+        );
+    }
+
+    #[test]
+    fn test_clip() {
+        assert_eq!(clip("short", 10), "short");
+        assert_eq!(clip("a longer text", 5), "a lo…");
+    }
+}
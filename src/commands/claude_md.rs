@@ -0,0 +1,260 @@
+use crate::bot::markdown::{capitalize, escape_markdown_v2};
+use crate::BotState;
+use teloxide::{prelude::*, types::ParseMode};
+
+use super::checkpoints::{send_error, working_dir_client};
+use super::git_log::send_code_blocks;
+
+/// User-level CLAUDE.md, on the persistent volume so it applies to every repository
+const USER_CLAUDE_MD_PATH: &str = "/root/.claude/CLAUDE.md";
+
+/// Print "exists" and the contents of file $1, if it exists
+///
+/// The marker line keeps a missing file apart from an empty one.
+const READ_SCRIPT: &str = r#"
+cd "$(git rev-parse --show-toplevel 2>/dev/null || pwd)"
+if [ -f "$1" ]; then
+    echo exists
+    cat -- "$1"
+fi
+"#;
+
+/// Replace ($2 = set) or extend ($2 = append) file $1 with $3, or delete it ($2 = clear)
+const WRITE_SCRIPT: &str = r#"
+set -e
+cd "$(git rev-parse --show-toplevel 2>/dev/null || pwd)"
+mkdir -p "$(dirname -- "$1")"
+case "$2" in
+    set) printf '%s\n' "$3" > "$1" ;;
+    append)
+        if [ -s "$1" ]; then
+            if [ -n "$(tail -c1 -- "$1")" ]; then echo >> "$1"; fi
+            echo >> "$1"
+        fi
+        printf '%s\n' "$3" >> "$1"
+        ;;
+    clear) rm -f -- "$1" ;;
+esac
+"#;
+
+/// Which CLAUDE.md a command works on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaudeMdScope {
+    /// CLAUDE.md at the root of the current repository
+    Repository,
+    /// ~/.claude/CLAUDE.md, read by Claude in every repository
+    User,
+}
+
+impl ClaudeMdScope {
+    fn path(self) -> &'static str {
+        match self {
+            Self::Repository => "CLAUDE.md",
+            Self::User => USER_CLAUDE_MD_PATH,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Repository => "the repository's CLAUDE.md",
+            Self::User => "your personal CLAUDE.md",
+        }
+    }
+
+    fn argument(self) -> &'static str {
+        match self {
+            Self::Repository => "",
+            Self::User => " user",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaudeMdAction {
+    Show,
+    Set(String),
+    Append(String),
+    Clear,
+}
+
+/// Handle the /claudemd command
+///
+/// Views and edits the repository's CLAUDE.md, or with `user` the personal one in
+/// `~/.claude` that applies to every repository.
+pub async fn handle_claude_md(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let Some((scope, action)) = parse_claude_md_args(&args) else {
+        bot.send_message(
+            chat_id,
+            "❓ *Usage:* `/claudemd [user] [set|append <text> | clear]`\n\n`/claudemd` shows \
+             the repository's CLAUDE\\.md, `/claudemd user` your personal one in \
+             `~/\\.claude`\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let Some(client) = working_dir_client(&bot, chat_id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let (mode, text) = match &action {
+        ClaudeMdAction::Show => {
+            let output = client
+                .exec_basic_command(script_command(READ_SCRIPT, &[scope.path()]))
+                .await;
+            return match output {
+                Ok(output) => send_claude_md(&bot, chat_id, scope, &output).await,
+                Err(e) => {
+                    let title = format!("Failed to read {}", scope.title());
+                    send_error(&bot, chat_id, &title, &e.to_string()).await
+                }
+            };
+        }
+        ClaudeMdAction::Set(text) => ("set", text.as_str()),
+        ClaudeMdAction::Append(text) => ("append", text.as_str()),
+        ClaudeMdAction::Clear => ("clear", ""),
+    };
+
+    let result = client
+        .exec_basic_command(script_command(WRITE_SCRIPT, &[scope.path(), mode, text]))
+        .await;
+    if let Err(e) = result {
+        let title = format!("Failed to update {}", scope.title());
+        send_error(&bot, chat_id, &title, &e.to_string()).await?;
+        return Ok(());
+    }
+
+    let message = match action {
+        ClaudeMdAction::Clear => format!("🗑️ *Deleted {}*", escape_markdown_v2(scope.title())),
+        _ => format!(
+            "✅ *Updated {}*\n\nClaude reads it at the start of every conversation\\. View it \
+             with `/claudemd{}`\\.",
+            escape_markdown_v2(scope.title()),
+            scope.argument()
+        ),
+    };
+    bot.send_message(chat_id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+fn script_command(script: &str, args: &[&str]) -> Vec<String> {
+    let mut command = vec![
+        "bash".to_string(),
+        "-c".to_string(),
+        script.to_string(),
+        "claude-md".to_string(),
+    ];
+    command.extend(args.iter().map(|arg| arg.to_string()));
+    command
+}
+
+async fn send_claude_md(
+    bot: &Bot,
+    chat_id: ChatId,
+    scope: ClaudeMdScope,
+    output: &str,
+) -> ResponseResult<()> {
+    let Some(contents) = output.strip_prefix("exists") else {
+        bot.send_message(
+            chat_id,
+            format!(
+                "📘 *{} doesn't exist yet*\n\nCreate it with `/claudemd{} set <text>`\\.",
+                escape_markdown_v2(&capitalize(scope.title())),
+                scope.argument()
+            ),
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let contents = contents.trim_start_matches('\n');
+    bot.send_message(
+        chat_id,
+        format!("📘 *{}*", escape_markdown_v2(&capitalize(scope.title()))),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    if contents.trim().is_empty() {
+        bot.send_message(chat_id, "_The file is empty\\._")
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    }
+    send_code_blocks(bot, chat_id, contents, "markdown").await
+}
+
+/// Parse `[user|repo] [show | set <text> | append <text> | clear]`
+fn parse_claude_md_args(args: &str) -> Option<(ClaudeMdScope, ClaudeMdAction)> {
+    let split = |text: &str| -> (String, String) {
+        let text = text.trim_start();
+        match text.split_once(char::is_whitespace) {
+            Some((word, rest)) => (word.to_lowercase(), rest.trim().to_string()),
+            None => (text.to_lowercase(), String::new()),
+        }
+    };
+
+    let (word, rest) = split(args);
+    let (scope, (action, text)) = match word.as_str() {
+        "user" => (ClaudeMdScope::User, split(&rest)),
+        "repo" | "repository" => (ClaudeMdScope::Repository, split(&rest)),
+        _ => (ClaudeMdScope::Repository, (word, rest)),
+    };
+
+    let action = match action.as_str() {
+        "" | "show" if text.is_empty() => ClaudeMdAction::Show,
+        "set" if !text.is_empty() => ClaudeMdAction::Set(text),
+        "append" | "add" if !text.is_empty() => ClaudeMdAction::Append(text),
+        "clear" | "delete" if text.is_empty() => ClaudeMdAction::Clear,
+        _ => return None,
+    };
+    Some((scope, action))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_claude_md_args() {
+        assert_eq!(
+            parse_claude_md_args(""),
+            Some((ClaudeMdScope::Repository, ClaudeMdAction::Show))
+        );
+        assert_eq!(
+            parse_claude_md_args("user"),
+            Some((ClaudeMdScope::User, ClaudeMdAction::Show))
+        );
+        assert_eq!(
+            parse_claude_md_args("append Run `cargo fmt`\n- before committing"),
+            Some((
+                ClaudeMdScope::Repository,
+                ClaudeMdAction::Append("Run `cargo fmt`\n- before committing".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_claude_md_args("user set Prefer small commits"),
+            Some((
+                ClaudeMdScope::User,
+                ClaudeMdAction::Set("Prefer small commits".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_claude_md_args("repo clear"),
+            Some((ClaudeMdScope::Repository, ClaudeMdAction::Clear))
+        );
+        assert_eq!(parse_claude_md_args("set"), None);
+        assert_eq!(parse_claude_md_args("frobnicate"), None);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::github_client::{GithubClient, GithubClientConfig};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{prelude::*, types::ParseMode};
+
+/// Handle the /clone command
+///
+/// Accepts GitHub shorthand or any HTTPS/SSH git URL, optionally followed by
+/// `--branch <name>` and `--depth <n>`.
+pub async fn handle_clone(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    if args.trim().is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "📦 *Clone a Repository*\n\nUsage: `/clone <owner/repo | git URL> [--branch <name>] \
+             [--depth <n>]`\n\nExamples:\n• `/clone octocat/Hello-World`\n• `/clone \
+             https://gitlab.com/group/project.git --branch develop`\n• `/clone \
+             git@git.example.com:team/tool.git --depth 1`\n\n🔑 SSH URLs use your deploy key, \
+             see /deploykey",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    let container_name = format!("coding-session-{}", chat_id);
+
+    match ClaudeCodeClient::for_session(bot_state.docker.clone(), &container_name).await {
+        Ok(client) => {
+            let github_client = GithubClient::new(
+                bot_state.docker.clone(),
+                client.container_id().to_string(),
+                GithubClientConfig::default(),
+            );
+
+            super::start::perform_github_clone(
+                &bot,
+                msg.chat.id,
+                &github_client,
+                args.trim(),
+                &bot_state,
+            )
+            .await?;
+        }
+        Err(e) => {
+            let full_message = format!(
+                "❌ No active coding session found: {}\n\nPlease start a coding session first \
+                 using /start",
+                escape_markdown_v2(&e.to_string())
+            );
+            let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+
+            bot.send_message(msg.chat.id, message_to_send)
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
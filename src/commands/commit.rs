@@ -3,6 +3,14 @@ use crate::BotState;
 use telegram_bot::claude_code_client::ClaudeCodeClient;
 use teloxide::{prelude::*, types::ParseMode};
 
+use super::signing_key::{explain_signing_failure, signing_key_problem};
+
+/// Git config key (in the per-user config) enabling the Claude co-author trailer
+pub const CO_AUTHOR_CONFIG_KEY: &str = "telegram.coauthor";
+
+/// Trailer crediting Claude on commits made through /commit
+const CO_AUTHOR_TRAILER: &str = "Co-authored-by: Claude <noreply@anthropic.com>";
+
 /// Handle the /commit command
 pub async fn handle_commit(
     bot: Bot,
@@ -11,6 +19,11 @@ pub async fn handle_commit(
     chat_id: i64,
 ) -> ResponseResult<()> {
     let container_name = format!("coding-session-{}", chat_id);
+    let user_id = msg
+        .from
+        .as_ref()
+        .map(|user| user.id.0 as i64)
+        .unwrap_or(chat_id);
 
     // Check if a coding session exists by trying to create a basic client
     if ClaudeCodeClient::for_session(bot_state.docker.clone(), &container_name).await.is_err() {
@@ -122,37 +135,27 @@ pub async fn handle_commit(
         }
     };
 
-    // Build prompt for Claude
-    let prompt = format!(
-        "generate a commit message for the following working state diff:\n\n{}",
-        git_diff
-    );
+    // Fail early with a clear message instead of git's signing error, and before paying for
+    // a commit message
+    if let Some(problem) = signing_key_problem(&client_with_dir).await {
+        let full_message = format!("❌ *Cannot sign commit:*\n{}", escape_markdown_v2(&problem));
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
 
-    // Generate commit message using Claude
-    let claude_result = client_with_dir
-        .exec_basic_command(vec![
-            "claude".to_string(),
-            "--print".to_string(),
-            "--model".to_string(),
-            "claude-3-5-haiku-20241022".to_string(),
-            prompt,
-        ])
-        .await;
+        bot.send_message(msg.chat.id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    }
 
-    let commit_message = match claude_result {
-        Ok(output) => {
-            let generated_message = output.trim();
-            if generated_message.is_empty() {
-                "Add changes".to_string()
-            } else {
-                format!("Claude Code Checkpoint: {}", generated_message)
-            }
-        }
-        Err(e) => {
-            log::warn!("Failed to generate commit message with Claude: {}", e);
-            "Claude Code Checkpoint: Add changes".to_string()
-        }
-    };
+    let commit_message = generate_commit_message(
+        &bot,
+        msg.chat.id,
+        &bot_state,
+        user_id,
+        &client_with_dir,
+        &git_diff,
+    )
+    .await;
 
     // Stage all changes
     let stage_result = client_with_dir.exec_basic_command(vec!["git".to_string(), "add".to_string(), "-A".to_string()]).await;
@@ -194,9 +197,14 @@ pub async fn handle_commit(
                 .await?;
         }
         Err(e) => {
+            let error = e.to_string();
+            let hint = explain_signing_failure(&error)
+                .map(|hint| format!("\n\n💡 {}", escape_markdown_v2(hint)))
+                .unwrap_or_default();
             let full_message = format!(
-                "❌ *Commit failed:*\n```\n{}\n```\n\n*Attempted message:*\n```\n{}\n```",
-                escape_markdown_v2(&e.to_string()),
+                "❌ *Commit failed:*\n```\n{}\n```{}\n\n*Attempted message:*\n```\n{}\n```",
+                escape_markdown_v2(&error),
+                hint,
                 escape_markdown_v2(&commit_message)
             );
             let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
@@ -271,4 +279,160 @@ async fn get_git_diff(
 
     // Nothing to commit
     Ok(None)
-}
\ No newline at end of file
+}
+
+/// Outcome of an automatic commit after a Claude run
+#[derive(Debug)]
+pub enum AutoCommitOutcome {
+    NothingToCommit,
+    /// A merge or rebase is stopped on conflicts, which /sync concludes instead
+    OperationInProgress,
+    Committed(String),
+}
+
+/// Stage and commit all changes with a Claude-generated message, as /commit does
+///
+/// Used by auto-commit mode after successful Claude runs, so it reports through the result
+/// instead of messaging the chat at every step.
+pub async fn auto_commit(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    client: &ClaudeCodeClient,
+) -> Result<AutoCommitOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let git_status_raw = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "status".to_string(),
+            "--porcelain".to_string(),
+        ])
+        .await?;
+
+    if git_status_raw.trim().is_empty() {
+        return Ok(AutoCommitOutcome::NothingToCommit);
+    }
+
+    if super::sync::operation_in_progress(client).await.is_some() {
+        return Ok(AutoCommitOutcome::OperationInProgress);
+    }
+
+    let Some(git_diff) = get_git_diff(client, &git_status_raw).await? else {
+        return Ok(AutoCommitOutcome::NothingToCommit);
+    };
+
+    if let Some(problem) = signing_key_problem(client).await {
+        return Err(problem.into());
+    }
+
+    let commit_message =
+        generate_commit_message(bot, chat_id, bot_state, user_id, client, &git_diff).await;
+
+    client
+        .exec_basic_command(vec!["git".to_string(), "add".to_string(), "-A".to_string()])
+        .await?;
+
+    client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "commit".to_string(),
+            "-m".to_string(),
+            commit_message.clone(),
+        ])
+        .await
+        .map_err(|e| match explain_signing_failure(&e.to_string()) {
+            Some(hint) => format!("{}\n\n{}", e, hint).into(),
+            None => e,
+        })?;
+
+    Ok(AutoCommitOutcome::Committed(commit_message))
+}
+
+/// Ask Claude for a commit message describing the diff, with a generic fallback
+///
+/// The fallback is also used when the user's or chat's budget is used up.
+async fn generate_commit_message(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    client: &ClaudeCodeClient,
+    git_diff: &str,
+) -> String {
+    // Build prompt for Claude
+    let prompt = format!(
+        "generate a commit message for the following working state diff:\n\n{}",
+        git_diff
+    );
+
+    // Generate commit message using Claude
+    let args = vec![
+        "--model".to_string(),
+        "claude-3-5-haiku-20241022".to_string(),
+        prompt,
+    ];
+    let claude_result =
+        super::usage::run_claude_print(bot, chat_id, bot_state, user_id, client, args).await;
+
+    let commit_message = match claude_result {
+        Ok(Some(output)) => {
+            let generated_message = output.trim();
+            if generated_message.is_empty() {
+                "Add changes".to_string()
+            } else {
+                format!("Claude Code Checkpoint: {}", generated_message)
+            }
+        }
+        Ok(None) => "Claude Code Checkpoint: Add changes".to_string(),
+        Err(e) => {
+            log::warn!("Failed to generate commit message with Claude: {}", e);
+            "Claude Code Checkpoint: Add changes".to_string()
+        }
+    };
+
+    // Credit Claude when the user opted in via /identity coauthor on
+    if co_author_enabled(client).await {
+        append_co_author_trailer(&commit_message)
+    } else {
+        commit_message
+    }
+}
+
+/// Whether the co-author trailer is enabled in the user's git configuration
+pub async fn co_author_enabled(client: &ClaudeCodeClient) -> bool {
+    client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "config".to_string(),
+            "--type=bool".to_string(),
+            "--get".to_string(),
+            CO_AUTHOR_CONFIG_KEY.to_string(),
+        ])
+        .await
+        .map(|value| value.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// Append the Claude co-author trailer unless the message already carries it
+fn append_co_author_trailer(message: &str) -> String {
+    if message.contains(CO_AUTHOR_TRAILER) {
+        message.to_string()
+    } else {
+        format!("{}\n\n{}", message.trim_end(), CO_AUTHOR_TRAILER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_co_author_trailer() {
+        let message = append_co_author_trailer("Fix parser\n");
+        assert_eq!(
+            message,
+            "Fix parser\n\nCo-authored-by: Claude <noreply@anthropic.com>"
+        );
+        assert_eq!(append_co_author_trailer(&message), message);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{prelude::*, types::ParseMode};
+
+/// Private key path inside the container; `/root/.ssh` is linked to the persistent volume
+const DEPLOY_KEY_PATH: &str = "/root/.ssh/id_ed25519";
+
+/// Handle the /deploykey command
+///
+/// Manages the per-user SSH key used to clone and push over SSH. The key lives on the
+/// user's persistent volume, so it survives session restarts.
+pub async fn handle_deploy_key(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let container_name = format!("coding-session-{}", chat_id);
+
+    let client =
+        match ClaudeCodeClient::for_session(bot_state.docker.clone(), &container_name).await {
+            Ok(client) => client,
+            Err(e) => {
+                let full_message = format!(
+                    "❌ No active coding session found: {}\n\nPlease start a coding session first \
+                 using /start",
+                    escape_markdown_v2(&e.to_string())
+                );
+                let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+
+                bot.send_message(msg.chat.id, message_to_send)
+                    .parse_mode(ParseMode::MarkdownV2)
+                    .await?;
+                return Ok(());
+            }
+        };
+
+    let key_exists = client
+        .exec_basic_command(vec![
+            "test".to_string(),
+            "-f".to_string(),
+            DEPLOY_KEY_PATH.to_string(),
+        ])
+        .await
+        .is_ok();
+
+    let message = match args.trim() {
+        "" | "show" => {
+            if key_exists {
+                match read_public_key(&client).await {
+                    Ok(public_key) => {
+                        format_public_key_message("🔑 *Your Deploy Key*", &public_key)
+                    }
+                    Err(e) => format!(
+                        "❌ Failed to read deploy key: {}",
+                        escape_markdown_v2(&e.to_string())
+                    ),
+                }
+            } else {
+                "🔑 *No Deploy Key*\n\nYou don't have an SSH deploy key yet\\.\n\nUse \
+                 `/deploykey generate` to create one\\."
+                    .to_string()
+            }
+        }
+        "generate" => {
+            if key_exists {
+                "⚠️ *Deploy Key Already Exists*\n\nUse `/deploykey show` to view it, or \
+                 `/deploykey remove` first to replace it\\."
+                    .to_string()
+            } else {
+                match generate_key(&client, chat_id).await {
+                    Ok(public_key) => {
+                        format_public_key_message("✅ *Deploy Key Generated*", &public_key)
+                    }
+                    Err(e) => format!(
+                        "❌ Failed to generate deploy key: {}",
+                        escape_markdown_v2(&e.to_string())
+                    ),
+                }
+            }
+        }
+        "remove" => {
+            match client
+                .exec_basic_command(vec![
+                    "rm".to_string(),
+                    "-f".to_string(),
+                    DEPLOY_KEY_PATH.to_string(),
+                    format!("{}.pub", DEPLOY_KEY_PATH),
+                ])
+                .await
+            {
+                Ok(_) => "🗑️ *Deploy Key Removed*\n\nRemember to also remove it from any \
+                          repositories it was added to\\."
+                    .to_string(),
+                Err(e) => format!(
+                    "❌ Failed to remove deploy key: {}",
+                    escape_markdown_v2(&e.to_string())
+                ),
+            }
+        }
+        _ => "🔑 *Deploy Key Management*\n\nUsage:\n• `/deploykey` or `/deploykey show` \\- Show \
+              your public key\n• `/deploykey generate` \\- Create a new key\n• `/deploykey \
+              remove` \\- Delete your key"
+            .to_string(),
+    };
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&message);
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Generate a new ed25519 key pair without a passphrase and return the public key
+async fn generate_key(
+    client: &ClaudeCodeClient,
+    chat_id: i64,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    client
+        .exec_basic_command(vec![
+            "ssh-keygen".to_string(),
+            "-q".to_string(),
+            "-t".to_string(),
+            "ed25519".to_string(),
+            "-N".to_string(),
+            "".to_string(),
+            "-C".to_string(),
+            format!("telegram-claude-code-{}", chat_id),
+            "-f".to_string(),
+            DEPLOY_KEY_PATH.to_string(),
+        ])
+        .await?;
+
+    read_public_key(client).await
+}
+
+async fn read_public_key(
+    client: &ClaudeCodeClient,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let output = client
+        .exec_basic_command(vec!["cat".to_string(), format!("{}.pub", DEPLOY_KEY_PATH)])
+        .await?;
+    Ok(output.trim().to_string())
+}
+
+fn format_public_key_message(title: &str, public_key: &str) -> String {
+    format!(
+        "{}\n\n```\n{}\n```\n\nAdd this key as a deploy key \\(or to your account's SSH keys\\) on \
+         GitLab, Gitea or any other git host, then clone with an SSH URL:\n`/clone \
+         git@host:owner/repo.git`",
+        title, public_key
+    )
+}
@@ -0,0 +1,546 @@
+use crate::bot::claude_session::{ChangedFile, ClaudeSession, DiscardState};
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ParseMode},
+};
+
+use super::checkpoints::{create_checkpoint, send_error, working_dir_client};
+use super::sync::{operation_in_progress, run_git_script};
+
+/// Lists changed files NUL-separated. Command output is trimmed, which would eat the leading
+/// space of the first status code, so a marker line goes first.
+const STATUS_SCRIPT: &str = "echo status\ngit status --porcelain -z";
+
+/// Discards changes of files given as (mode, path) argument pairs
+const DISCARD_SCRIPT: &str = r#"
+while [ $# -gt 1 ]; do
+    case "$1" in
+        untracked) git clean -f -d -q -- "$2" ;;
+        added) git rm -r -f -q -- "$2" ;;
+        tracked) git restore --source=HEAD --staged --worktree -- "$2" ;;
+    esac
+    shift 2
+done
+"#;
+
+/// Files beyond this many are not listed as buttons, but can be discarded with `/discard all`
+const MAX_LISTED_FILES: usize = 40;
+
+/// Handle the /discard command
+///
+/// Lists the changed files so the user can pick which ones to throw away. `/discard all`
+/// selects everything and skips straight to the confirmation.
+pub async fn handle_discard(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let Some(client) = working_dir_client(&bot, msg.chat.id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    if operation_in_progress(&client).await.is_some() {
+        bot.send_message(
+            msg.chat.id,
+            "⚠️ *Merge in progress*\n\nResolve or abort it with /sync before discarding \
+             changes\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    let files = match changed_files(&client).await {
+        Ok(files) => files,
+        Err(e) => {
+            send_error(
+                &bot,
+                msg.chat.id,
+                "Failed to check git status",
+                &e.to_string(),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    if files.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "ℹ️ *Nothing to discard*\n\nThe working directory is clean\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    let mut state = DiscardState::new(files);
+    let select_all = args.trim() == "all";
+    if select_all {
+        state.toggle_all();
+    }
+
+    let message = format_file_list(&state);
+    let keyboard = file_list_keyboard(&state);
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .entry(chat_id)
+            .or_insert_with(ClaudeSession::new)
+            .discard = Some(state);
+    }
+
+    if select_all {
+        return handle_discard_review(bot, msg.chat.id, &bot_state).await;
+    }
+
+    bot.send_message(msg.chat.id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Toggle a file (or all files) in the selection and refresh the keyboard
+pub async fn handle_discard_toggle(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    bot_state: &BotState,
+    index: Option<usize>,
+) -> ResponseResult<()> {
+    let keyboard = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get_mut(&chat_id.0)
+            .and_then(|s| s.discard.as_mut())
+            .map(|state| {
+                match index {
+                    Some(index) => state.toggle(index),
+                    None => state.toggle_all(),
+                }
+                file_list_keyboard(state)
+            })
+    };
+
+    let Some(keyboard) = keyboard else {
+        return send_no_discard_in_progress(bot, chat_id).await;
+    };
+
+    bot.edit_message_reply_markup(chat_id, message_id)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Show the current `git status` of the selected files and ask for confirmation
+pub async fn handle_discard_review(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    let selected = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .and_then(|s| s.discard.as_ref())
+            .map(|state| {
+                state
+                    .selected_files()
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+    };
+
+    let Some(selected) = selected else {
+        return send_no_discard_in_progress(bot, chat_id).await;
+    };
+
+    if selected.is_empty() {
+        bot.send_message(chat_id, "☝️ Select at least one file to discard\\.")
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    }
+
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    // The tree may have changed since the list was sent, so confirm against a fresh status
+    let files = match selected_changes(&client, &selected).await {
+        Ok(files) => files,
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to check git status", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    if files.is_empty() {
+        return send_nothing_to_discard(bot, chat_id).await;
+    }
+
+    // Only the files shown here are discarded, whatever is toggled afterwards
+    let confirmed = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get_mut(&chat_id.0)
+            .and_then(|s| s.discard.as_mut())
+            .map(|state| state.confirmed = Some(files.clone()))
+            .is_some()
+    };
+    if !confirmed {
+        return send_no_discard_in_progress(bot, chat_id).await;
+    }
+
+    let full_message = format!(
+        "⚠️ *Discard changes to {} file{}?*\n```\n{}\n```\nA checkpoint is taken first, so \
+         /undo can bring them back\\.",
+        files.len(),
+        if files.len() == 1 { "" } else { "s" },
+        escape_markdown_v2(&format_status(&files))
+    );
+    let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("🗑️ Discard", "discard_apply"),
+            InlineKeyboardButton::callback("❌ Cancel", "discard_cancel"),
+        ]]))
+        .await?;
+
+    Ok(())
+}
+
+/// Discard the files shown for confirmation
+pub async fn handle_discard_apply(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    let confirmed = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get_mut(&chat_id.0)
+            .and_then(|s| s.discard.as_mut())
+            .map(|state| state.confirmed.take())
+    };
+
+    let confirmed = match confirmed {
+        Some(Some(confirmed)) => confirmed,
+        Some(None) => {
+            bot.send_message(
+                chat_id,
+                "☝️ The selection changed after the confirmation was sent\\. Tap *Discard \
+                 Selected* again to confirm the files to discard\\.",
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            return Ok(());
+        }
+        None => return send_no_discard_in_progress(bot, chat_id).await,
+    };
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.discard = None;
+        }
+    }
+
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    // Files that lost their changes since the review are skipped, but none are added
+    let files = match selected_changes(&client, &confirmed).await {
+        Ok(files) => files,
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to check git status", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    if files.is_empty() {
+        return send_nothing_to_discard(bot, chat_id).await;
+    }
+
+    match create_checkpoint(&client, "/discard").await {
+        Ok(checkpoint) => log::info!("Checkpoint before discard: {:?}", checkpoint),
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to take a checkpoint", &e.to_string()).await?;
+            return Ok(());
+        }
+    }
+
+    if let Err(e) = run_git_script(&client, DISCARD_SCRIPT, discard_args(&files)).await {
+        send_error(&bot, chat_id, "Failed to discard changes", &e.to_string()).await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "🗑️ *Discarded changes to {} file{}*\n\nUse /undo to restore them\\.",
+            files.len(),
+            if files.len() == 1 { "" } else { "s" }
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn handle_discard_cancel(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.discard = None;
+        }
+    }
+
+    bot.send_message(chat_id, "👍 Discard cancelled\\. Nothing was changed\\.")
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+async fn send_no_discard_in_progress(bot: Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        "ℹ️ No discard in progress\\. Use /discard to pick files\\.",
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+    Ok(())
+}
+
+async fn send_nothing_to_discard(bot: Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        "ℹ️ *Nothing to discard*\n\nThe selected files no longer have changes\\.",
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+    Ok(())
+}
+
+/// Current status of the selected files that still have changes
+async fn selected_changes(
+    client: &ClaudeCodeClient,
+    selected: &[ChangedFile],
+) -> Result<Vec<ChangedFile>, Box<dyn std::error::Error + Send + Sync>> {
+    let files = changed_files(client).await?;
+    Ok(files
+        .into_iter()
+        .filter(|file| selected.iter().any(|s| s.path == file.path))
+        .collect())
+}
+
+async fn changed_files(
+    client: &ClaudeCodeClient,
+) -> Result<Vec<ChangedFile>, Box<dyn std::error::Error + Send + Sync>> {
+    let output = run_git_script(client, STATUS_SCRIPT, vec![]).await?;
+    let porcelain = output
+        .strip_prefix("status")
+        .ok_or_else(|| format!("Unexpected git status output: {}", output))?;
+
+    Ok(parse_porcelain(porcelain.trim_start_matches('\n')))
+}
+
+/// Parse `git status --porcelain -z`, where renames and copies are followed by their source
+fn parse_porcelain(output: &str) -> Vec<ChangedFile> {
+    let mut files = Vec::new();
+    let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
+
+    while let Some(entry) = entries.next() {
+        let (Some(status), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+            continue;
+        };
+
+        let original_path = if status.starts_with(['R', 'C']) {
+            entries.next().map(str::to_string)
+        } else {
+            None
+        };
+
+        files.push(ChangedFile {
+            status: status.to_string(),
+            path: path.to_string(),
+            original_path,
+        });
+    }
+
+    files
+}
+
+/// Arguments for `DISCARD_SCRIPT`: how to discard each file
+fn discard_args(files: &[ChangedFile]) -> Vec<String> {
+    let mut args = Vec::new();
+
+    for file in files {
+        let mode = match file.status.as_str() {
+            "??" => "untracked",
+            status if status.starts_with(['A', 'R', 'C']) => "added",
+            _ => "tracked",
+        };
+        args.push(mode.to_string());
+        args.push(file.path.clone());
+
+        // A rename also removed the source, which has to come back
+        if let Some(original_path) = file
+            .original_path
+            .as_ref()
+            .filter(|_| file.status.starts_with('R'))
+        {
+            args.push("tracked".to_string());
+            args.push(original_path.clone());
+        }
+    }
+
+    args
+}
+
+/// Render files the way `git status --short` does
+fn format_status(files: &[ChangedFile]) -> String {
+    files
+        .iter()
+        .map(|file| match &file.original_path {
+            Some(original_path) => format!("{} {} -> {}", file.status, original_path, file.path),
+            None => format!("{} {}", file.status, file.path),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_file_list(state: &DiscardState) -> String {
+    let mut message = format!(
+        "🗑️ *Discard changes*\n\n{} changed file{}\\. Select the files to discard:",
+        state.files.len(),
+        if state.files.len() == 1 { "" } else { "s" }
+    );
+
+    if state.files.len() > MAX_LISTED_FILES {
+        message.push_str(&format!(
+            "\n\n_Only the first {} are listed; use_ `/discard all` _to discard everything\\._",
+            MAX_LISTED_FILES
+        ));
+    }
+
+    message
+}
+
+fn file_list_keyboard(state: &DiscardState) -> InlineKeyboardMarkup {
+    let mut keyboard_rows: Vec<Vec<InlineKeyboardButton>> = state
+        .files
+        .iter()
+        .take(MAX_LISTED_FILES)
+        .enumerate()
+        .map(|(index, file)| {
+            let marker = if state.selected.contains(&index) {
+                "☑️"
+            } else {
+                "⬜"
+            };
+            vec![InlineKeyboardButton::callback(
+                format!("{} {} {}", marker, file.status.trim(), file.path),
+                format!("discard_toggle:{}", index),
+            )]
+        })
+        .collect();
+
+    keyboard_rows.push(vec![InlineKeyboardButton::callback(
+        "☑️ Select All",
+        "discard_all",
+    )]);
+    keyboard_rows.push(vec![InlineKeyboardButton::callback(
+        format!("🗑️ Discard Selected ({})", state.selected.len()),
+        "discard_review",
+    )]);
+    keyboard_rows.push(vec![InlineKeyboardButton::callback(
+        "❌ Cancel",
+        "discard_cancel",
+    )]);
+
+    InlineKeyboardMarkup::new(keyboard_rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(status: &str, path: &str, original_path: Option<&str>) -> ChangedFile {
+        ChangedFile {
+            status: status.to_string(),
+            path: path.to_string(),
+            original_path: original_path.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_porcelain() {
+        let output = " M src/main.rs\0R  new.rs\0old.rs\0?? notes/\0A  added file.txt\0";
+
+        assert_eq!(
+            parse_porcelain(output),
+            vec![
+                file(" M", "src/main.rs", None),
+                file("R ", "new.rs", Some("old.rs")),
+                file("??", "notes/", None),
+                file("A ", "added file.txt", None),
+            ]
+        );
+        assert!(parse_porcelain("").is_empty());
+    }
+
+    #[test]
+    fn test_discard_args() {
+        let files = vec![
+            file(" M", "a.rs", None),
+            file("??", "tmp/", None),
+            file("AM", "b.rs", None),
+            file("R ", "new.rs", Some("old.rs")),
+        ];
+
+        assert_eq!(
+            discard_args(&files),
+            vec![
+                "tracked",
+                "a.rs",
+                "untracked",
+                "tmp/",
+                "added",
+                "b.rs",
+                "added",
+                "new.rs",
+                "tracked",
+                "old.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_status() {
+        let files = vec![
+            file(" M", "a.rs", None),
+            file("R ", "new.rs", Some("old.rs")),
+        ];
+
+        assert_eq!(format_status(&files), " M a.rs\nR  old.rs -> new.rs");
+    }
+}
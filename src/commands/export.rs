@@ -0,0 +1,692 @@
+use crate::bot::claude_session::RunCost;
+use crate::bot::markdown::escape_markdown_v2;
+use crate::BotState;
+use serde_json::Value;
+use telegram_bot::claude_code_client::container_utils::container_get_file;
+use teloxide::{
+    prelude::*,
+    types::{InputFile, ParseMode},
+};
+
+use super::checkpoints::{send_error, working_dir_client};
+
+/// Directory Claude keeps its conversation transcripts in, one subdirectory per project
+const PROJECTS_DIRECTORY: &str = "/root/.claude/projects";
+
+/// Tool inputs and results longer than this are cut in rendered transcripts
+const MAX_TOOL_TEXT_LENGTH: usize = 4000;
+
+/// Format of an exported transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+impl ExportFormat {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "" | "md" | "markdown" => Some(Self::Markdown),
+            "html" => Some(Self::Html),
+            "jsonl" | "json" | "raw" => Some(Self::Jsonl),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// One step of a conversation, as read from Claude's transcript file
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptItem {
+    Prompt {
+        text: String,
+        timestamp: Option<String>,
+    },
+    Reply {
+        text: String,
+    },
+    ToolCall {
+        id: String,
+        name: String,
+        input: Value,
+        result: Option<String>,
+        is_error: bool,
+    },
+    /// Cost in USD of the turn that precedes it
+    TurnCost(f64),
+}
+
+/// Conversation details shown at the top of an export
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportMetadata {
+    pub conversation_id: String,
+    pub repository: Option<String>,
+    pub total_cost: Option<f64>,
+    /// Runs the bot recorded for the conversation, to annotate each turn with its cost
+    pub run_costs: Vec<RunCost>,
+}
+
+/// Handle the /export command
+///
+/// Sends the current conversation's transcript as a document: Markdown (the default) or HTML
+/// with tool calls collapsed and costs annotated, or Claude's raw JSONL.
+pub async fn handle_export(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let Some(format) = ExportFormat::parse(args.trim()) else {
+        bot.send_message(
+            chat_id,
+            "❓ *Usage:* `/export [md|html|jsonl]`\n\nExports the current conversation, as \
+             Markdown by default\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    // The current conversation, or else the most recent one
+    let metadata = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions.get(&chat_id.0).and_then(|session| {
+            let conversation_id = session
+                .conversation_id
+                .clone()
+                .or_else(|| session.conversations.last().map(|record| record.id.clone()))?;
+            let record = session.conversation(&conversation_id);
+            Some(ExportMetadata {
+                repository: record.and_then(|record| record.repository.clone()),
+                total_cost: record.map(|record| record.total_cost),
+                run_costs: record
+                    .map(|record| record.run_costs.clone())
+                    .unwrap_or_default(),
+                conversation_id,
+            })
+        })
+    };
+    let Some(metadata) = metadata.filter(|metadata| is_session_id(&metadata.conversation_id))
+    else {
+        bot.send_message(
+            chat_id,
+            "ℹ️ *No conversation to export*\n\nStart one with /claude, or pick one from \
+             /history\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let Some(client) = working_dir_client(&bot, chat_id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let path = client
+        .exec_basic_command(vec![
+            "find".to_string(),
+            PROJECTS_DIRECTORY.to_string(),
+            "-name".to_string(),
+            format!("{}.jsonl", metadata.conversation_id),
+            "-print".to_string(),
+            "-quit".to_string(),
+        ])
+        .await;
+    let path = match path {
+        Ok(path) if !path.is_empty() => path,
+        Ok(_) => {
+            bot.send_message(
+                chat_id,
+                "ℹ️ *Transcript not found*\n\nClaude hasn't saved this conversation in the \
+                 container\\.",
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            send_error(
+                &bot,
+                chat_id,
+                "Failed to find the transcript",
+                &e.to_string(),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let jsonl = match container_get_file(&bot_state.docker, client.container_id(), &path).await {
+        Ok(jsonl) => jsonl,
+        Err(e) => {
+            send_error(
+                &bot,
+                chat_id,
+                "Failed to read the transcript",
+                &e.to_string(),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let contents = match format {
+        ExportFormat::Jsonl => jsonl,
+        ExportFormat::Markdown | ExportFormat::Html => {
+            let items = annotate_turn_costs(
+                parse_transcript(&String::from_utf8_lossy(&jsonl)),
+                &metadata.run_costs,
+            );
+            let rendered = if format == ExportFormat::Markdown {
+                render_markdown(&metadata, &items)
+            } else {
+                render_html(&metadata, &items)
+            };
+            rendered.into_bytes()
+        }
+    };
+
+    let short_id = metadata.conversation_id.get(..8).unwrap_or("session");
+    let file_name = format!("conversation-{}.{}", short_id, format.extension());
+    bot.send_document(chat_id, InputFile::memory(contents).file_name(file_name))
+        .caption(format!(
+            "📜 Conversation `{}`",
+            escape_markdown_v2(&metadata.conversation_id)
+        ))
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Session ids are used in a file name, so only accept what Claude hands out
+fn is_session_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Read the prompts, replies and tool calls from Claude's JSONL transcript
+fn parse_transcript(jsonl: &str) -> Vec<TranscriptItem> {
+    let mut items = Vec::new();
+
+    for entry in jsonl
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        if entry["isMeta"].as_bool() == Some(true) {
+            continue;
+        }
+        let content = &entry["message"]["content"];
+
+        match entry["type"].as_str() {
+            Some("user") => {
+                let timestamp = entry["timestamp"].as_str().map(str::to_string);
+                let mut prompts = Vec::new();
+
+                if let Some(text) = content.as_str() {
+                    prompts.push(text.to_string());
+                }
+                for block in content.as_array().into_iter().flatten() {
+                    match block["type"].as_str() {
+                        Some("text") => {
+                            prompts.extend(block["text"].as_str().map(str::to_string));
+                        }
+                        Some("tool_result") => attach_tool_result(&mut items, block),
+                        _ => {}
+                    }
+                }
+
+                items.extend(prompts.into_iter().map(|text| TranscriptItem::Prompt {
+                    text,
+                    timestamp: timestamp.clone(),
+                }));
+            }
+            Some("assistant") => {
+                for block in content.as_array().into_iter().flatten() {
+                    match block["type"].as_str() {
+                        Some("text") => {
+                            items.extend(block["text"].as_str().map(|text| TranscriptItem::Reply {
+                                text: text.to_string(),
+                            }))
+                        }
+                        Some("tool_use") => items.push(TranscriptItem::ToolCall {
+                            id: block["id"].as_str().unwrap_or_default().to_string(),
+                            name: block["name"].as_str().unwrap_or("tool").to_string(),
+                            input: block["input"].clone(),
+                            result: None,
+                            is_error: false,
+                        }),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    items
+}
+
+/// Add the cost of each run the bot recorded after the turn it started
+///
+/// Claude's transcripts don't include costs, so runs are matched to prompts by their text, in
+/// order. Turns the bot didn't run, like those before a /fork, stay without a cost.
+fn annotate_turn_costs(items: Vec<TranscriptItem>, run_costs: &[RunCost]) -> Vec<TranscriptItem> {
+    let mut runs = run_costs.iter().peekable();
+    let mut annotated = Vec::with_capacity(items.len() + run_costs.len());
+    let mut turn_cost = None;
+
+    for item in items {
+        if let TranscriptItem::Prompt { text, .. } = &item {
+            annotated.extend(turn_cost.take().map(TranscriptItem::TurnCost));
+            if runs
+                .peek()
+                .is_some_and(|run| run.prompt.trim() == text.trim())
+            {
+                turn_cost = runs.next().map(|run| run.cost);
+            }
+        }
+        annotated.push(item);
+    }
+    annotated.extend(turn_cost.map(TranscriptItem::TurnCost));
+
+    annotated
+}
+
+fn attach_tool_result(items: &mut [TranscriptItem], block: &Value) {
+    let tool_use_id = block["tool_use_id"].as_str().unwrap_or_default();
+    let text = match &block["content"] {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+
+    let call = items
+        .iter_mut()
+        .rev()
+        .find(|item| matches!(item, TranscriptItem::ToolCall { id, .. } if id == tool_use_id));
+    if let Some(TranscriptItem::ToolCall {
+        result, is_error, ..
+    }) = call
+    {
+        *result = Some(text);
+        *is_error = block["is_error"].as_bool().unwrap_or(false);
+    }
+}
+
+/// The most telling input of a tool call, like the command of Bash, for its collapsed title
+fn tool_summary(name: &str, input: &Value) -> String {
+    let detail = [
+        "command",
+        "file_path",
+        "path",
+        "pattern",
+        "url",
+        "description",
+    ]
+    .iter()
+    .find_map(|key| input[*key].as_str())
+    .and_then(|detail| detail.lines().next());
+
+    match detail {
+        Some(detail) => format!("{}: {}", name, detail),
+        None => name.to_string(),
+    }
+}
+
+fn tool_input_text(input: &Value) -> String {
+    if input.is_null() {
+        return String::new();
+    }
+    serde_json::to_string_pretty(input).unwrap_or_default()
+}
+
+fn cut(text: &str) -> String {
+    if text.chars().count() <= MAX_TOOL_TEXT_LENGTH {
+        return text.to_string();
+    }
+
+    let kept: String = text.chars().take(MAX_TOOL_TEXT_LENGTH).collect();
+    format!(
+        "{}\n… ({} more characters)",
+        kept,
+        text.chars().count() - MAX_TOOL_TEXT_LENGTH
+    )
+}
+
+fn format_timestamp(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// A Markdown code fence longer than any run of backticks in `text`
+fn fence(text: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        current = if c == '`' { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    "`".repeat((longest + 1).max(3))
+}
+
+fn render_markdown(metadata: &ExportMetadata, items: &[TranscriptItem]) -> String {
+    let mut output = format!(
+        "# Claude conversation\n\n- Session: `{}`\n",
+        metadata.conversation_id
+    );
+    if let Some(repository) = &metadata.repository {
+        output.push_str(&format!("- Repository: `{}`\n", repository));
+    }
+    if let Some(total_cost) = metadata.total_cost.filter(|cost| *cost > 0.0) {
+        output.push_str(&format!("- Total cost: ${:.4}\n", total_cost));
+    }
+
+    for item in items {
+        match item {
+            TranscriptItem::Prompt { text, timestamp } => {
+                output.push_str("\n## 🧑 You");
+                if let Some(timestamp) = timestamp {
+                    output.push_str(&format!(" · {}", format_timestamp(timestamp)));
+                }
+                output.push_str(&format!("\n\n{}\n", text.trim()));
+            }
+            TranscriptItem::Reply { text } => {
+                output.push_str(&format!("\n### 🤖 Claude\n\n{}\n", text.trim()));
+            }
+            TranscriptItem::ToolCall {
+                name,
+                input,
+                result,
+                is_error,
+                ..
+            } => {
+                let icon = if *is_error { "❌" } else { "🔧" };
+                output.push_str(&format!(
+                    "\n<details>\n<summary>{} {}</summary>\n\n",
+                    icon,
+                    escape_html(&tool_summary(name, input))
+                ));
+
+                let input = cut(&tool_input_text(input));
+                if !input.is_empty() {
+                    let fence = fence(&input);
+                    output.push_str(&format!("{}json\n{}\n{}\n\n", fence, input, fence));
+                }
+                if let Some(result) = result {
+                    let result = cut(result);
+                    let fence = fence(&result);
+                    output.push_str(&format!("{}\n{}\n{}\n\n", fence, result, fence));
+                }
+                output.push_str("</details>\n");
+            }
+            TranscriptItem::TurnCost(cost) => {
+                output.push_str(&format!("\n_💰 ${:.4}_\n", cost));
+            }
+        }
+    }
+
+    output
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(metadata: &ExportMetadata, items: &[TranscriptItem]) -> String {
+    let mut body = format!(
+        "<h1>Claude conversation</h1>\n<ul>\n<li>Session: <code>{}</code></li>\n",
+        escape_html(&metadata.conversation_id)
+    );
+    if let Some(repository) = &metadata.repository {
+        body.push_str(&format!(
+            "<li>Repository: <code>{}</code></li>\n",
+            escape_html(repository)
+        ));
+    }
+    if let Some(total_cost) = metadata.total_cost.filter(|cost| *cost > 0.0) {
+        body.push_str(&format!("<li>Total cost: ${:.4}</li>\n", total_cost));
+    }
+    body.push_str("</ul>\n");
+
+    for item in items {
+        match item {
+            TranscriptItem::Prompt { text, timestamp } => {
+                let timestamp = timestamp
+                    .as_deref()
+                    .map(|timestamp| {
+                        format!(
+                            " <small>{}</small>",
+                            escape_html(&format_timestamp(timestamp))
+                        )
+                    })
+                    .unwrap_or_default();
+                body.push_str(&format!(
+                    "<div class=\"prompt\"><h2>🧑 You{}</h2>\n<p>{}</p></div>\n",
+                    timestamp,
+                    escape_html(text.trim())
+                ));
+            }
+            TranscriptItem::Reply { text } => {
+                body.push_str(&format!(
+                    "<div class=\"reply\"><h3>🤖 Claude</h3>\n<p>{}</p></div>\n",
+                    escape_html(text.trim())
+                ));
+            }
+            TranscriptItem::ToolCall {
+                name,
+                input,
+                result,
+                is_error,
+                ..
+            } => {
+                let icon = if *is_error { "❌" } else { "🔧" };
+                body.push_str(&format!(
+                    "<details><summary>{} {}</summary>\n",
+                    icon,
+                    escape_html(&tool_summary(name, input))
+                ));
+
+                let input = cut(&tool_input_text(input));
+                if !input.is_empty() {
+                    body.push_str(&format!("<pre>{}</pre>\n", escape_html(&input)));
+                }
+                if let Some(result) = result {
+                    body.push_str(&format!(
+                        "<pre class=\"result\">{}</pre>\n",
+                        escape_html(&cut(result))
+                    ));
+                }
+                body.push_str("</details>\n");
+            }
+            TranscriptItem::TurnCost(cost) => {
+                body.push_str(&format!("<p class=\"cost\">💰 ${:.4}</p>\n", cost));
+            }
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Claude conversation \
+         {}</title>\n<style>\nbody {{ font-family: sans-serif; max-width: 50em; margin: auto; \
+         }}\np {{ white-space: pre-wrap; }}\npre {{ background: #f4f4f4; padding: 0.5em; \
+         overflow-x: auto; }}\n.prompt {{ border-left: 4px solid #4a90d9; padding-left: 1em; \
+         }}\n.cost {{ color: #888; font-style: italic; }}\n</style>\n</head>\n<body>\n{}</body>\
+         \n</html>\n",
+        escape_html(&metadata.conversation_id),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSCRIPT: &str = r#"{"type":"summary","summary":"Fix tests"}
+{"type":"user","timestamp":"2024-05-01T12:30:00.000Z","message":{"role":"user","content":"Fix the failing test"}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Let me run it."},{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"cargo test"}}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"1 failed","is_error":true}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Fixed."}]}}
+not json
+{"type":"user","isMeta":true,"message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#;
+
+    fn metadata() -> ExportMetadata {
+        ExportMetadata {
+            conversation_id: "3f2a9c1d-0000".to_string(),
+            repository: Some("api".to_string()),
+            total_cost: Some(0.03),
+            run_costs: vec![RunCost {
+                prompt: "Fix the failing test".to_string(),
+                cost: 0.03,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_export_format_parse() {
+        assert_eq!(ExportFormat::parse(""), Some(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::parse("HTML"), Some(ExportFormat::Html));
+        assert_eq!(ExportFormat::parse("raw"), Some(ExportFormat::Jsonl));
+        assert_eq!(ExportFormat::parse("pdf"), None);
+    }
+
+    #[test]
+    fn test_parse_transcript() {
+        let items = parse_transcript(TRANSCRIPT);
+
+        assert_eq!(items.len(), 4);
+        assert_eq!(
+            items[0],
+            TranscriptItem::Prompt {
+                text: "Fix the failing test".to_string(),
+                timestamp: Some("2024-05-01T12:30:00.000Z".to_string()),
+            }
+        );
+        assert_eq!(
+            items[2],
+            TranscriptItem::ToolCall {
+                id: "toolu_1".to_string(),
+                name: "Bash".to_string(),
+                input: serde_json::json!({"command": "cargo test"}),
+                result: Some("1 failed".to_string()),
+                is_error: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let metadata = metadata();
+        let items = annotate_turn_costs(parse_transcript(TRANSCRIPT), &metadata.run_costs);
+        let markdown = render_markdown(&metadata, &items);
+
+        assert!(markdown.starts_with(
+            "# Claude conversation\n\n- Session: `3f2a9c1d-0000`\n- Repository: `api`\n- Total \
+             cost: $0.0300\n"
+        ));
+        assert!(markdown.contains("## 🧑 You · 2024-05-01 12:30 UTC\n\nFix the failing test\n"));
+        assert!(markdown.contains(
+            "<details>\n<summary>❌ Bash: cargo test</summary>\n\n```json\n{\n  \"command\": \
+             \"cargo test\"\n}\n```\n\n```\n1 failed\n```\n\n</details>\n"
+        ));
+        assert!(markdown.ends_with("\n### 🤖 Claude\n\nFixed.\n\n_💰 $0.0300_\n"));
+    }
+
+    #[test]
+    fn test_annotate_turn_costs() {
+        let prompt = |text: &str| TranscriptItem::Prompt {
+            text: text.to_string(),
+            timestamp: None,
+        };
+        let reply = TranscriptItem::Reply {
+            text: "Done.".to_string(),
+        };
+        let run_cost = |prompt: &str, cost: f64| RunCost {
+            prompt: prompt.to_string(),
+            cost,
+        };
+
+        // The first turn happened before a /fork, so the bot has no cost for it
+        let items = vec![
+            prompt("Fix the bug"),
+            reply.clone(),
+            prompt("Try another way"),
+            reply.clone(),
+            prompt("Add a test"),
+            reply.clone(),
+        ];
+        let runs = [
+            run_cost("Try another way", 0.25),
+            run_cost("Add a test", 0.5),
+        ];
+
+        assert_eq!(
+            annotate_turn_costs(items, &runs),
+            vec![
+                prompt("Fix the bug"),
+                reply.clone(),
+                prompt("Try another way"),
+                reply.clone(),
+                TranscriptItem::TurnCost(0.25),
+                prompt("Add a test"),
+                reply,
+                TranscriptItem::TurnCost(0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_escapes_tool_summary() {
+        let items = vec![TranscriptItem::ToolCall {
+            id: "toolu_1".to_string(),
+            name: "Bash".to_string(),
+            input: serde_json::json!({"command": "echo '</summary>' > out.html"}),
+            result: None,
+            is_error: false,
+        }];
+        let markdown = render_markdown(&metadata(), &items);
+
+        assert!(
+            markdown.contains("<summary>🔧 Bash: echo '&lt;/summary&gt;' &gt; out.html</summary>")
+        );
+    }
+
+    #[test]
+    fn test_render_html() {
+        let items = vec![TranscriptItem::Reply {
+            text: "Use <T> & friends".to_string(),
+        }];
+        let html = render_html(&metadata(), &items);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<p>Use &lt;T&gt; &amp; friends</p>"));
+    }
+
+    #[test]
+    fn test_fence() {
+        assert_eq!(fence("plain"), "```");
+        assert_eq!(fence("has ```` inside"), "`````");
+    }
+
+    #[test]
+    fn test_is_session_id() {
+        assert!(is_session_id("3f2a9c1d-8b7a-4e4d-9c2b-1a09f8e7d6c5"));
+        assert!(!is_session_id("../etc"));
+        assert!(!is_session_id("*"));
+    }
+}
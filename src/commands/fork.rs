@@ -0,0 +1,135 @@
+use crate::bot::claude_session::PendingFork;
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use teloxide::{prelude::*, types::ParseMode};
+
+use super::checkpoints::{create_checkpoint, working_dir_client};
+
+/// Handle the /fork command
+///
+/// Branches the current conversation so an alternative approach can be tried without losing
+/// it: the next message (or the prompt given with /fork) continues in a copy of the
+/// conversation under a new session id, and both stay in /history. The working tree is saved
+/// as a checkpoint first so it can be brought back with /undo.
+pub async fn handle_fork(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    user_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let prompt = args.trim();
+
+    let conversation_id = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .and_then(|session| session.conversation_id.clone())
+    };
+    let Some(conversation_id) = conversation_id else {
+        bot.send_message(
+            chat_id,
+            "ℹ️ *No conversation to fork*\n\nStart one with /claude, or pick one from /history \
+             first\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let Some(client) = working_dir_client(&bot, chat_id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let checkpoint = match create_checkpoint(&client, "/fork").await {
+        Ok(checkpoint) => checkpoint,
+        Err(e) => {
+            log::warn!("Failed to create checkpoint for chat {}: {}", chat_id.0, e);
+            None
+        }
+    };
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.pending_fork = Some(PendingFork {
+                conversation_id: conversation_id.clone(),
+                checkpoint: checkpoint.clone(),
+            });
+            session.is_active = true;
+        }
+    }
+
+    bot.send_message(chat_id, format_fork_started(checkpoint.as_deref(), prompt))
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    if prompt.is_empty() {
+        return Ok(());
+    }
+
+    if let Err(e) = super::execute_claude_command(
+        bot.clone(),
+        chat_id,
+        bot_state.clone(),
+        user_id,
+        prompt,
+        Some(conversation_id),
+    )
+    .await
+    {
+        let full_message = format!(
+            "❌ Claude command failed: {}",
+            escape_markdown_v2(&e.to_string())
+        );
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+        bot.send_message(chat_id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn format_fork_started(checkpoint: Option<&str>, prompt: &str) -> String {
+    let mut message = "🌿 *Conversation forked*\n\n".to_string();
+
+    if prompt.is_empty() {
+        message.push_str(
+            "Your next message starts a new branch of this conversation\\. The original stays \
+             in /history\\.",
+        );
+    } else {
+        message.push_str("Continuing in a new branch\\. The original stays in /history\\.");
+    }
+
+    if let Some(checkpoint) = checkpoint {
+        message.push_str(&format!(
+            "\n\n📸 Working tree saved, restore it with `/undo {}`",
+            escape_markdown_v2(checkpoint)
+        ));
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_fork_started() {
+        assert_eq!(
+            format_fork_started(Some("20240101-120000-000"), ""),
+            "🌿 *Conversation forked*\n\nYour next message starts a new branch of this \
+             conversation\\. The original stays in /history\\.\n\n📸 Working tree saved, restore \
+             it with `/undo 20240101\\-120000\\-000`"
+        );
+        assert_eq!(
+            format_fork_started(None, "Try sessions instead"),
+            "🌿 *Conversation forked*\n\nContinuing in a new branch\\. The original stays in \
+             /history\\."
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+use super::checkpoints::{send_error, working_dir_client};
+
+/// Number of commits listed by /log
+const LOG_LENGTH: usize = 10;
+
+/// Separates the fields of a commit in `git log --format` output
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Escaped diff text per message, leaving room for the code fences
+const DIFF_CHUNK_LENGTH: usize = 3800;
+
+/// Diffs longer than this many messages are cut off
+const MAX_DIFF_MESSAGES: usize = 5;
+
+/// Characters of a commit subject shown on its button
+const SUBJECT_PREVIEW_LENGTH: usize = 40;
+
+/// A commit as listed by /log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitSummary {
+    pub sha: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+}
+
+/// Handle the /log command
+///
+/// Lists the most recent commits, optionally only those touching a path, with a button per
+/// commit to view its diff.
+pub async fn handle_log(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let path = args.trim();
+    let Some(client) = working_dir_client(&bot, msg.chat.id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let mut command = vec![
+        "git".to_string(),
+        "log".to_string(),
+        format!("-n{}", LOG_LENGTH),
+        format!("--format=%h{0}%an{0}%ar{0}%s", FIELD_SEPARATOR),
+    ];
+    if !path.is_empty() {
+        command.push("--".to_string());
+        command.push(path.to_string());
+    }
+
+    let output = match client.exec_basic_command(command).await {
+        Ok(output) => output,
+        Err(e) => {
+            send_error(
+                &bot,
+                msg.chat.id,
+                "Failed to read the git log",
+                &e.to_string(),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let commits = parse_log(&output);
+    if commits.is_empty() {
+        let message = if path.is_empty() {
+            "ℹ️ *No commits yet*".to_string()
+        } else {
+            format!("ℹ️ *No commits touch* `{}`", escape_markdown_v2(path))
+        };
+        bot.send_message(msg.chat.id, message)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    }
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&format_log(&commits, path));
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(log_keyboard(&commits))
+        .await?;
+
+    Ok(())
+}
+
+/// Show a commit's message, changed files and diff
+pub async fn handle_log_show(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    sha: &str,
+) -> ResponseResult<()> {
+    if sha.is_empty() || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(());
+    }
+
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    let header = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "show".to_string(),
+            "--stat".to_string(),
+            "--format=commit %H%nAuthor: %an <%ae>%nDate:   %ad%n%n%B".to_string(),
+            sha.to_string(),
+        ])
+        .await;
+    let header = match header {
+        Ok(header) => header,
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to show the commit", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    let patch = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "show".to_string(),
+            "--format=".to_string(),
+            "--patch".to_string(),
+            sha.to_string(),
+        ])
+        .await;
+    let patch = match patch {
+        Ok(patch) => patch,
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to show the commit", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    send_code_blocks(&bot, chat_id, &header, "").await?;
+    send_code_blocks(&bot, chat_id, &patch, "diff").await
+}
+
+/// Send text as code blocks split at line boundaries, up to `MAX_DIFF_MESSAGES` messages
+pub async fn send_code_blocks(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    language: &str,
+) -> ResponseResult<()> {
+    let chunks = chunk_lines(&escape_markdown_v2(text), DIFF_CHUNK_LENGTH);
+
+    for chunk in chunks.iter().take(MAX_DIFF_MESSAGES) {
+        bot.send_message(chat_id, format!("```{}\n{}\n```", language, chunk))
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+    }
+
+    if chunks.len() > MAX_DIFF_MESSAGES {
+        bot.send_message(
+            chat_id,
+            format!(
+                "✂️ Cut off here, {} more messages were not sent\\.",
+                chunks.len() - MAX_DIFF_MESSAGES
+            ),
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+    }
+
+    Ok(())
+}
+
+fn parse_log(output: &str) -> Vec<CommitSummary> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, FIELD_SEPARATOR);
+            Some(CommitSummary {
+                sha: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn format_log(commits: &[CommitSummary], path: &str) -> String {
+    let mut message = if path.is_empty() {
+        "📜 *Recent commits*\n".to_string()
+    } else {
+        format!(
+            "📜 *Recent commits touching* `{}`\n",
+            escape_markdown_v2(path)
+        )
+    };
+
+    for commit in commits {
+        message.push_str(&format!(
+            "\n`{}` {}\n_{}, {}_\n",
+            commit.sha,
+            escape_markdown_v2(&commit.subject),
+            escape_markdown_v2(&commit.author),
+            escape_markdown_v2(&commit.date)
+        ));
+    }
+
+    message
+}
+
+fn log_keyboard(commits: &[CommitSummary]) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(commits.iter().map(|commit| {
+        vec![InlineKeyboardButton::callback(
+            format!("📄 {} {}", commit.sha, preview(&commit.subject)),
+            format!("log_show:{}", commit.sha),
+        )]
+    }))
+}
+
+/// Shorten text to fit on a button
+pub fn preview(text: &str) -> String {
+    if text.chars().count() <= SUBJECT_PREVIEW_LENGTH {
+        return text.to_string();
+    }
+
+    let mut preview: String = text.chars().take(SUBJECT_PREVIEW_LENGTH - 1).collect();
+    preview.push('…');
+    preview
+}
+
+/// Split text into chunks of at most `max_length` bytes, breaking between lines
+///
+/// Lines that don't fit in a chunk on their own are cut.
+fn chunk_lines(text: &str, max_length: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        let mut line = line;
+        if line.len() > max_length {
+            let mut end = max_length;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            // Don't leave half of an escape sequence at the cut
+            line = line[..end].trim_end_matches('\\');
+        }
+
+        if !current.is_empty() && current.len() + 1 + line.len() > max_length {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = "abc1234\u{1f}Ada\u{1f}2 days ago\u{1f}Fix the parser\n\
+                      def5678\u{1f}Linus\u{1f}3 weeks ago\u{1f}Add \u{1f} separator test";
+        let commits = parse_log(output);
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(
+            commits[0],
+            CommitSummary {
+                sha: "abc1234".to_string(),
+                author: "Ada".to_string(),
+                date: "2 days ago".to_string(),
+                subject: "Fix the parser".to_string(),
+            }
+        );
+        assert_eq!(commits[1].subject, "Add \u{1f} separator test");
+        assert!(parse_log("").is_empty());
+    }
+
+    #[test]
+    fn test_format_log() {
+        let commits = vec![CommitSummary {
+            sha: "abc1234".to_string(),
+            author: "Ada".to_string(),
+            date: "2 days ago".to_string(),
+            subject: "Fix parser.rs".to_string(),
+        }];
+
+        assert_eq!(
+            format_log(&commits, "src/parser.rs"),
+            "📜 *Recent commits touching* `src/parser\\.rs`\n\n`abc1234` Fix parser\\.rs\n_Ada, 2 \
+             days ago_\n"
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        assert_eq!(preview("Short"), "Short");
+        let long = "x".repeat(SUBJECT_PREVIEW_LENGTH + 5);
+        assert_eq!(preview(&long).chars().count(), SUBJECT_PREVIEW_LENGTH);
+        assert!(preview(&long).ends_with('…'));
+    }
+
+    #[test]
+    fn test_chunk_lines() {
+        assert_eq!(chunk_lines("a\nb\nc", 3), vec!["a\nb", "c"]);
+        assert_eq!(chunk_lines("abcdef\ng", 4), vec!["abcd", "g"]);
+        assert!(chunk_lines("", 10).is_empty());
+
+        let text = "line\n".repeat(100);
+        for chunk in chunk_lines(&text, 50) {
+            assert!(chunk.len() <= 50);
+        }
+    }
+}
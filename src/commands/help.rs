@@ -45,10 +45,10 @@ mod tests {
         let help_text = generate_help_text();
 
         // Regex to match the pattern "command - description"
-        // Command: lowercase letters (no slash prefix)
+        // Command: lowercase letters and underscores, as BotFather allows (no slash prefix)
         // Separator: " - " (space-hyphen-space)
         // Description: any non-empty text
-        let line_pattern = Regex::new(r"^[a-z]+ - .+$").unwrap();
+        let line_pattern = Regex::new(r"^[a-z_]+ - .+$").unwrap();
 
         // Verify each line follows the pattern "command - description"
         for line in help_text.lines() {
@@ -0,0 +1,263 @@
+use crate::bot::claude_session::{ClaudeSession, ConversationRecord};
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use chrono::{DateTime, Utc};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+use super::git_log::preview;
+use super::repo::repository_name;
+
+/// Number of conversations listed by /history
+const HISTORY_LENGTH: usize = 10;
+
+/// Handle the /history command
+///
+/// Lists the conversations of this session, most recent first, with a button per conversation
+/// to resume it.
+pub async fn handle_history(bot: Bot, msg: Message, bot_state: BotState) -> ResponseResult<()> {
+    let (conversations, current) = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&msg.chat.id.0)
+            .map(|session| {
+                let recent: Vec<ConversationRecord> = session
+                    .conversations
+                    .iter()
+                    .rev()
+                    .take(HISTORY_LENGTH)
+                    .cloned()
+                    .collect();
+                (recent, session.conversation_id.clone())
+            })
+            .unwrap_or_default()
+    };
+
+    if conversations.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "🗂 *No conversations yet*\n\nStart one with /claude, then use /history to come back \
+             to it later\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&format_history(
+        &conversations,
+        current.as_deref(),
+        Utc::now(),
+    ));
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(history_keyboard(&conversations, current.as_deref()))
+        .await?;
+
+    Ok(())
+}
+
+/// Resume a conversation from the history, switching to its repository if needed
+pub async fn handle_history_resume(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    conversation_id: &str,
+) -> ResponseResult<()> {
+    let outcome = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        let session = sessions.entry(chat_id.0).or_insert_with(ClaudeSession::new);
+        resume(session, conversation_id)
+    };
+
+    let message = match outcome {
+        ResumeOutcome::Resumed(record) => {
+            let mut message = format!(
+                "▶️ *Resumed conversation*\n\n{}\n",
+                escape_markdown_v2(&preview(&record.first_prompt))
+            );
+            if let Some(repository) = &record.repository {
+                message.push_str(&format!(
+                    "📁 {}\n",
+                    escape_markdown_v2(repository_name(repository))
+                ));
+            }
+            message.push_str("\nSend a message to continue where you left off\\.");
+            message
+        }
+        ResumeOutcome::NotFound => {
+            "❓ *Conversation not found*\n\nIt may have been cleared\\. Use /history to see the \
+             conversations of this session\\."
+                .to_string()
+        }
+        ResumeOutcome::ParallelTasks => {
+            "⚠️ *Parallel tasks in progress*\n\nThis conversation ran in another repository\\. \
+             Remove your tasks with `/task remove <name>` before resuming it\\."
+                .to_string()
+        }
+    };
+
+    bot.send_message(chat_id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+enum ResumeOutcome {
+    Resumed(ConversationRecord),
+    NotFound,
+    ParallelTasks,
+}
+
+fn resume(session: &mut ClaudeSession, conversation_id: &str) -> ResumeOutcome {
+    let Some(record) = session.conversation(conversation_id).cloned() else {
+        return ResumeOutcome::NotFound;
+    };
+
+    // Claude only finds a conversation from the directory it ran in
+    if let Some(repository) = &record.repository {
+        if session.get_working_directory() != Some(repository) {
+            if session.has_parallel_tasks() {
+                return ResumeOutcome::ParallelTasks;
+            }
+            session.switch_directory(repository.clone());
+        }
+    }
+
+    session.conversation_id = Some(record.id.clone());
+    session.is_active = true;
+    ResumeOutcome::Resumed(record)
+}
+
+/// Time since `time`, like "5m ago"
+fn format_age(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let minutes = (now - time).num_minutes().max(0);
+    match minutes {
+        0 => "just now".to_string(),
+        1..=59 => format!("{}m ago", minutes),
+        60..=1439 => format!("{}h ago", minutes / 60),
+        _ => format!("{}d ago", minutes / 1440),
+    }
+}
+
+fn format_history(
+    conversations: &[ConversationRecord],
+    current: Option<&str>,
+    now: DateTime<Utc>,
+) -> String {
+    let mut message = "🗂 *Conversations*\n".to_string();
+
+    for record in conversations {
+        let marker = if current == Some(record.id.as_str()) {
+            "✅"
+        } else {
+            "💬"
+        };
+
+        let mut details = vec![format_age(record.last_active_at, now)];
+        if let Some(repository) = &record.repository {
+            details.insert(0, repository_name(repository).to_string());
+        }
+        if record.forked_from.is_some() {
+            details.push("🌿 fork".to_string());
+        }
+        if record.total_cost > 0.0 {
+            details.push(format!("${:.2}", record.total_cost));
+        }
+
+        message.push_str(&format!(
+            "\n{} {}\n_{}_\n",
+            marker,
+            escape_markdown_v2(&preview(&record.first_prompt)),
+            escape_markdown_v2(&details.join(" · "))
+        ));
+    }
+
+    message
+}
+
+fn history_keyboard(
+    conversations: &[ConversationRecord],
+    current: Option<&str>,
+) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(conversations.iter().map(|record| {
+        let marker = if current == Some(record.id.as_str()) {
+            "✅"
+        } else {
+            "▶️"
+        };
+        vec![InlineKeyboardButton::callback(
+            format!("{} {}", marker, preview(&record.first_prompt)),
+            format!("history_resume:{}", record.id),
+        )]
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn record(id: &str, repository: &str, now: DateTime<Utc>) -> ConversationRecord {
+        ConversationRecord {
+            id: id.to_string(),
+            first_prompt: "Fix the login bug".to_string(),
+            started_at: now - Duration::hours(3),
+            last_active_at: now - Duration::hours(2),
+            repository: Some(repository.to_string()),
+            total_cost: 0.5,
+            run_costs: Vec::new(),
+            forked_from: None,
+            checkpoint: None,
+        }
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now = Utc::now();
+        assert_eq!(format_age(now, now), "just now");
+        assert_eq!(format_age(now - Duration::minutes(5), now), "5m ago");
+        assert_eq!(format_age(now - Duration::hours(3), now), "3h ago");
+        assert_eq!(format_age(now - Duration::days(2), now), "2d ago");
+        assert_eq!(format_age(now + Duration::minutes(5), now), "just now");
+    }
+
+    #[test]
+    fn test_format_history() {
+        let now = Utc::now();
+        let conversations = vec![record("a", "my-api", now)];
+
+        assert_eq!(
+            format_history(&conversations, Some("a"), now),
+            "🗂 *Conversations*\n\n✅ Fix the login bug\n_my\\-api · 2h ago · $0\\.50_\n"
+        );
+    }
+
+    #[test]
+    fn test_resume_switches_repository() {
+        let now = Utc::now();
+        let mut session = ClaudeSession::new();
+        session.add_repository("api".to_string());
+        session.add_repository("web".to_string());
+        session.set_working_directory("web".to_string());
+        session.conversations.push(record("a", "api", now));
+
+        assert!(matches!(
+            resume(&mut session, "missing"),
+            ResumeOutcome::NotFound
+        ));
+        assert!(matches!(
+            resume(&mut session, "a"),
+            ResumeOutcome::Resumed(_)
+        ));
+        assert_eq!(
+            session.get_working_directory().map(String::as_str),
+            Some("api")
+        );
+        assert_eq!(session.conversation_id.as_deref(), Some("a"));
+        assert!(session.is_active);
+    }
+}
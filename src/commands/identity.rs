@@ -0,0 +1,218 @@
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::github_client::{GithubClient, GithubClientConfig};
+use crate::BotState;
+use telegram_bot::claude_code_client::{container_utils, ClaudeCodeClient};
+use teloxide::{prelude::*, types::ParseMode};
+
+use super::commit::{co_author_enabled, CO_AUTHOR_CONFIG_KEY};
+
+/// A change to the per-user git configuration requested via /identity
+#[derive(Debug, PartialEq, Eq)]
+enum IdentityAction {
+    Show,
+    SetName(String),
+    SetEmail(String),
+    FromGithub,
+    CoAuthor(bool),
+    Reset,
+}
+
+impl IdentityAction {
+    fn parse(args: &str) -> Option<Self> {
+        let args = args.trim();
+        let (subcommand, value) = args.split_once(' ').unwrap_or((args, ""));
+        let value = value.trim();
+
+        match (subcommand, value) {
+            ("" | "show", "") => Some(Self::Show),
+            ("name", name) if !name.is_empty() => Some(Self::SetName(name.to_string())),
+            ("email", email) if email.contains('@') && !email.contains(' ') => {
+                Some(Self::SetEmail(email.to_string()))
+            }
+            ("github", "") => Some(Self::FromGithub),
+            ("coauthor", "on") => Some(Self::CoAuthor(true)),
+            ("coauthor", "off") => Some(Self::CoAuthor(false)),
+            ("reset", "") => Some(Self::Reset),
+            _ => None,
+        }
+    }
+}
+
+/// Handle the /identity command
+///
+/// Manages the git author identity used for commits in the user's coding sessions. Settings
+/// are written to the per-user git config on the persistent volume.
+pub async fn handle_identity(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let Some(action) = IdentityAction::parse(&args) else {
+        bot.send_message(
+            msg.chat.id,
+            "🪪 *Git Identity*\n\nUsage:\n• `/identity` \\- Show the current identity\n• \
+             `/identity name <name>` \\- Set your commit name\n• `/identity email <email>` \\- \
+             Set your commit email\n• `/identity github` \\- Use your GitHub account's name and \
+             email\n• `/identity coauthor on|off` \\- Credit Claude with a `Co-authored-by` \
+             trailer in /commit\n• `/identity reset` \\- Go back to the default identity",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let container_name = format!("coding-session-{}", chat_id);
+    let client =
+        match ClaudeCodeClient::for_session(bot_state.docker.clone(), &container_name).await {
+            Ok(client) => client,
+            Err(e) => {
+                let full_message = format!(
+                    "❌ No active coding session found: {}\n\nPlease start a coding session first \
+                 using /start",
+                    escape_markdown_v2(&e.to_string())
+                );
+                let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+
+                bot.send_message(msg.chat.id, message_to_send)
+                    .parse_mode(ParseMode::MarkdownV2)
+                    .await?;
+                return Ok(());
+            }
+        };
+
+    let result = match action {
+        IdentityAction::Show => Ok(()),
+        IdentityAction::SetName(name) => set_user_config(&client, "user.name", &name).await,
+        IdentityAction::SetEmail(email) => set_user_config(&client, "user.email", &email).await,
+        IdentityAction::FromGithub => {
+            let github_client = GithubClient::new(
+                bot_state.docker.clone(),
+                client.container_id().to_string(),
+                GithubClientConfig::default(),
+            );
+            match github_client.current_user().await {
+                Ok(profile) => {
+                    match set_user_config(&client, "user.name", profile.commit_name()).await {
+                        Ok(()) => {
+                            set_user_config(&client, "user.email", &profile.commit_email()).await
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                Err(e) => Err(format!(
+                    "Could not read your GitHub profile ({}). Authenticate with /auth login first.",
+                    e
+                )
+                .into()),
+            }
+        }
+        IdentityAction::CoAuthor(enabled) => {
+            set_user_config(&client, CO_AUTHOR_CONFIG_KEY, &enabled.to_string()).await
+        }
+        IdentityAction::Reset => client
+            .exec_basic_command(vec![
+                "rm".to_string(),
+                "-f".to_string(),
+                container_utils::USER_GITCONFIG_PATH.to_string(),
+            ])
+            .await
+            .map(|_| ()),
+    };
+
+    let message = match result {
+        Ok(()) => format_identity(&client).await,
+        Err(e) => format!(
+            "❌ *Failed to update git identity:*\n```\n{}\n```",
+            escape_markdown_v2(&e.to_string())
+        ),
+    };
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&message);
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Write a value to the per-user git config on the volume
+async fn set_user_config(
+    client: &ClaudeCodeClient,
+    key: &str,
+    value: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "config".to_string(),
+            "--file".to_string(),
+            container_utils::USER_GITCONFIG_PATH.to_string(),
+            key.to_string(),
+            value.to_string(),
+        ])
+        .await
+        .map(|_| ())
+}
+
+/// Describe the effective git identity and co-author setting
+async fn format_identity(client: &ClaudeCodeClient) -> String {
+    let mut values = Vec::new();
+    for key in ["user.name", "user.email"] {
+        let value = client
+            .exec_basic_command(vec![
+                "git".to_string(),
+                "config".to_string(),
+                "--get".to_string(),
+                key.to_string(),
+            ])
+            .await
+            .map(|v| v.trim().to_string())
+            .unwrap_or_else(|_| "not set".to_string());
+        values.push(value);
+    }
+
+    let co_author = if co_author_enabled(client).await {
+        "on"
+    } else {
+        "off"
+    };
+
+    format!(
+        "🪪 *Git Identity*\n\n👤 Name: `{}`\n📧 Email: `{}`\n🤝 Claude co\\-author trailer: \
+         {}\n\nChange it with `/identity name`, `/identity email` or `/identity github`\\.",
+        escape_markdown_v2(&values[0]),
+        escape_markdown_v2(&values[1]),
+        co_author
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_action_parse() {
+        assert_eq!(IdentityAction::parse(""), Some(IdentityAction::Show));
+        assert_eq!(
+            IdentityAction::parse("name Ada Lovelace"),
+            Some(IdentityAction::SetName("Ada Lovelace".to_string()))
+        );
+        assert_eq!(
+            IdentityAction::parse("email ada@example.com"),
+            Some(IdentityAction::SetEmail("ada@example.com".to_string()))
+        );
+        assert_eq!(
+            IdentityAction::parse("coauthor on"),
+            Some(IdentityAction::CoAuthor(true))
+        );
+        assert_eq!(
+            IdentityAction::parse("github"),
+            Some(IdentityAction::FromGithub)
+        );
+        assert_eq!(IdentityAction::parse("email not-an-email"), None);
+        assert_eq!(IdentityAction::parse("name"), None);
+        assert_eq!(IdentityAction::parse("coauthor maybe"), None);
+    }
+}
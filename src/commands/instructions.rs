@@ -0,0 +1,77 @@
+use crate::bot::claude_session::ClaudeSession;
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use teloxide::{prelude::*, types::ParseMode};
+
+/// Handle the /instructions command
+///
+/// Shows, replaces or clears the instructions appended to Claude's system prompt for every
+/// run in this chat.
+pub async fn handle_instructions(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let args = args.trim();
+
+    let message = if args.is_empty() {
+        let instructions = {
+            let sessions = bot_state.claude_sessions.lock().await;
+            sessions
+                .get(&msg.chat.id.0)
+                .and_then(|session| session.custom_instructions.clone())
+        };
+        format_instructions(instructions.as_deref())
+    } else {
+        let instructions = (args != "clear").then(|| args.to_string());
+        {
+            let mut sessions = bot_state.claude_sessions.lock().await;
+            let session = sessions
+                .entry(msg.chat.id.0)
+                .or_insert_with(ClaudeSession::new);
+            session.custom_instructions = instructions.clone();
+        }
+
+        match instructions {
+            Some(_) => "✅ *Instructions saved*\n\nClaude gets them with every prompt in this \
+                        chat\\. Use /instructions to view them\\."
+                .to_string(),
+            None => "🗑️ *Instructions cleared*".to_string(),
+        }
+    };
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&message);
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+fn format_instructions(instructions: Option<&str>) -> String {
+    match instructions {
+        Some(instructions) => format!(
+            "📝 *Instructions for this chat*\n```\n{}\n```\nReplace them with `/instructions \
+             <text>` or remove them with `/instructions clear`\\.",
+            escape_markdown_v2(instructions)
+        ),
+        None => "📝 *No instructions set*\n\nUse `/instructions <text>` to add instructions \
+                 to Claude's system prompt for every prompt in this chat, e\\.g\\. `/instructions \
+                 Always write tests first`\\. For instructions about a repository, edit its \
+                 CLAUDE\\.md with /claudemd\\."
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_instructions() {
+        assert!(format_instructions(None).starts_with("📝 *No instructions set*"));
+        assert!(format_instructions(Some("Use tabs."))
+            .starts_with("📝 *Instructions for this chat*\n```\nUse tabs\\.\n```"));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::bot::claude_session::ClaudeSession;
+use crate::BotState;
+use std::sync::Arc;
+use telegram_bot::claude_code_client::{ClaudeCodeClient, ClaudeExecutionResult};
+use teloxide::{prelude::*, types::ParseMode};
+
+/// Handle the /interactive command
+///
+/// Toggles the per-chat mode that keeps one Claude process running between prompts instead
+/// of starting `claude --resume` for every message, which saves its startup and the reload
+/// of the conversation on each turn.
+pub async fn handle_interactive(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let requested = match args.trim() {
+        "" => None,
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => {
+            bot.send_message(
+                msg.chat.id,
+                "⚡ *Interactive mode*\n\nUsage:\n• `/interactive` \\- Show whether interactive \
+                 mode is on\n• `/interactive on` \\- Keep Claude running between prompts\n• \
+                 `/interactive off` \\- Start Claude for every prompt",
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let enabled = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        let session = sessions.entry(chat_id).or_insert_with(ClaudeSession::new);
+        if let Some(enabled) = requested {
+            session.interactive_mode = enabled;
+            if !enabled {
+                session.interactive_process = None;
+            }
+        }
+        session.interactive_mode
+    };
+
+    let message = if enabled {
+        "⚡ *Interactive mode is on*\n\nClaude keeps running between your prompts, so follow\\-ups \
+         start faster\\. If the process stops, prompts start Claude for every message again until \
+         a new one is running\\."
+    } else {
+        "⚡ *Interactive mode is off*\n\nClaude starts for every prompt\\. Use `/interactive on` \
+         to keep it running between prompts\\."
+    };
+
+    bot.send_message(msg.chat.id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Send the prompt to the chat's long-lived Claude process, starting one if needed
+///
+/// Returns `None` when interactive mode is off or the process can't take the prompt, so the
+/// caller starts Claude for this prompt instead.
+pub async fn execute_interactive_turn(
+    bot_state: &BotState,
+    chat_id: i64,
+    client: &ClaudeCodeClient,
+    prompt: &str,
+    conversation_id: Option<&str>,
+) -> Option<ClaudeExecutionResult> {
+    let process = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        let session = sessions.get_mut(&chat_id)?;
+        if !session.interactive_mode {
+            return None;
+        }
+
+        if let Some(process) = &session.interactive_process {
+            if !process.is_alive() {
+                // Run this prompt on its own; the next one starts a new process
+                log::warn!("Interactive Claude process for chat {} exited", chat_id);
+                session.interactive_process = None;
+                return None;
+            }
+            // A process in another conversation, directory or configuration can't be reused
+            if !client.can_continue_interactive(process, conversation_id) {
+                log::info!("Replacing interactive Claude process for chat {}", chat_id);
+                session.interactive_process = None;
+            }
+        }
+        session.interactive_process.clone()
+    };
+
+    let process = match process {
+        Some(process) => process,
+        None => match client.start_interactive_claude(conversation_id).await {
+            Ok(process) => {
+                let process = Arc::new(process);
+                let mut sessions = bot_state.claude_sessions.lock().await;
+                if let Some(session) = sessions.get_mut(&chat_id) {
+                    session.interactive_process = Some(process.clone());
+                }
+                process
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to start interactive Claude for chat {}, running per prompt: {}",
+                    chat_id,
+                    e
+                );
+                return None;
+            }
+        },
+    };
+
+    match process.send_prompt(prompt).await {
+        Ok(stream) => Some(ClaudeExecutionResult::Streaming(stream)),
+        Err(e) => {
+            log::warn!(
+                "Interactive Claude for chat {} can't take the prompt, running per prompt: {}",
+                chat_id,
+                e
+            );
+            let mut sessions = bot_state.claude_sessions.lock().await;
+            if let Some(session) = sessions.get_mut(&chat_id) {
+                if session
+                    .interactive_process
+                    .as_ref()
+                    .is_some_and(|current| Arc::ptr_eq(current, &process))
+                {
+                    session.interactive_process = None;
+                }
+            }
+            None
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::bot::claude_session::ClaudeSession;
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use std::time::Duration;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+/// Prompt sent to Claude when a run stopped at a limit is continued
+const CONTINUE_PROMPT: &str = "Continue where you left off.";
+
+/// Stop the Claude runs working in directory $1
+///
+/// Other runs in the container, like those of parallel tasks, work in other directories. The
+/// bracket keeps the pattern from matching this script itself.
+const STOP_SCRIPT: &str = r#"
+for pid in $(pgrep -f -- '--output-format [s]tream-json'); do
+    if [ "$(readlink "/proc/$pid/cwd")" = "$1" ]; then
+        kill -TERM "$pid"
+    fi
+done
+exit 0
+"#;
+
+/// A change requested with /limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitChange {
+    MaxTurns(Option<u32>),
+    Timeout(Option<Duration>),
+}
+
+/// Handle the /limits command
+///
+/// Shows or sets the per-chat limits on Claude runs: the most agentic turns a run may take
+/// and how long it may run before it's stopped.
+pub async fn handle_limits(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let change = if args.trim().is_empty() {
+        None
+    } else {
+        match parse_limit_change(&args) {
+            Some(change) => Some(change),
+            None => {
+                bot.send_message(
+                    msg.chat.id,
+                    "⏱️ *Limits*\n\nUsage:\n• `/limits` \\- Show the limits of Claude runs\n• \
+                     `/limits turns <n|off>` \\- Stop runs after n agentic turns\n• `/limits \
+                     timeout <duration|off>` \\- Stop runs after e\\.g\\. `90s`, `30m` or `2h`",
+                )
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+                return Ok(());
+            }
+        }
+    };
+
+    let (max_turns, run_timeout) = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        let session = sessions.entry(chat_id).or_insert_with(ClaudeSession::new);
+        match change {
+            Some(LimitChange::MaxTurns(max_turns)) => session.max_turns = max_turns,
+            Some(LimitChange::Timeout(run_timeout)) => session.run_timeout = run_timeout,
+            None => {}
+        }
+        (session.max_turns, session.run_timeout)
+    };
+
+    bot.send_message(msg.chat.id, format_limits(max_turns, run_timeout))
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Stop a run that hit the chat's time limit and offer to continue it
+pub async fn handle_run_timeout(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    client: &ClaudeCodeClient,
+    working_directory: Option<&str>,
+    timeout: Duration,
+) -> ResponseResult<()> {
+    log::info!(
+        "Claude run for chat {} hit its time limit of {:?}",
+        chat_id.0,
+        timeout
+    );
+
+    let result = client
+        .exec_basic_command(vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            STOP_SCRIPT.to_string(),
+            "stop-claude".to_string(),
+            working_directory.unwrap_or("/workspace").to_string(),
+        ])
+        .await;
+    if let Err(e) = result {
+        log::warn!("Failed to stop Claude for chat {}: {}", chat_id.0, e);
+    }
+
+    let conversation_id = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions.get_mut(&chat_id.0).and_then(|session| {
+            // An interactive process was stopped along with the run
+            session.interactive_process = None;
+            session.conversation_id.clone()
+        })
+    };
+
+    let message = format!(
+        "⏱️ *Time limit reached*\n\nClaude was stopped after {}\\. Change the limit with \
+         /limits\\.",
+        escape_markdown_v2(&format_duration(timeout))
+    );
+    let mut request = bot
+        .send_message(chat_id, message)
+        .parse_mode(ParseMode::MarkdownV2);
+    if let Some(conversation_id) = conversation_id {
+        request = request.reply_markup(continue_keyboard(&conversation_id));
+    }
+    request.await?;
+
+    Ok(())
+}
+
+/// Button that resumes a conversation stopped at a limit
+pub fn continue_keyboard(conversation_id: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        "▶️ Continue",
+        format!("continue:{}", conversation_id),
+    )]])
+}
+
+/// Handle the Continue button of a run stopped at a limit
+pub async fn handle_continue(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    conversation_id: &str,
+) -> ResponseResult<()> {
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.conversation_id = Some(conversation_id.to_string());
+            session.is_active = true;
+        }
+    }
+
+    if let Err(e) = super::execute_claude_command(
+        bot.clone(),
+        chat_id,
+        bot_state.clone(),
+        user_id,
+        CONTINUE_PROMPT,
+        Some(conversation_id.to_string()),
+    )
+    .await
+    {
+        let full_message = format!(
+            "❌ Claude command failed: {}",
+            escape_markdown_v2(&e.to_string())
+        );
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+        bot.send_message(chat_id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Parse `turns <n|off>` or `timeout <duration|off>`
+fn parse_limit_change(args: &str) -> Option<LimitChange> {
+    let mut words = args.split_whitespace();
+    let (limit, value) = (words.next()?, words.next()?);
+    if words.next().is_some() {
+        return None;
+    }
+
+    match (limit.to_lowercase().as_str(), value.to_lowercase().as_str()) {
+        ("turns", "off") => Some(LimitChange::MaxTurns(None)),
+        ("turns", value) => match value.parse::<u32>() {
+            Ok(0) | Err(_) => None,
+            Ok(turns) => Some(LimitChange::MaxTurns(Some(turns))),
+        },
+        ("timeout", "off") => Some(LimitChange::Timeout(None)),
+        ("timeout", value) => parse_duration(value).map(|d| LimitChange::Timeout(Some(d))),
+        _ => None,
+    }
+}
+
+/// Parse a duration like `90s`, `30m` or `2h`; plain numbers are minutes
+fn parse_duration(value: &str) -> Option<Duration> {
+    let (number, unit_secs) = match value.char_indices().last()? {
+        (i, 's') => (&value[..i], 1),
+        (i, 'm') => (&value[..i], 60),
+        (i, 'h') => (&value[..i], 60 * 60),
+        _ => (value, 60),
+    };
+
+    match number.parse::<u64>() {
+        Ok(0) | Err(_) => None,
+        Ok(number) => Some(Duration::from_secs(number.checked_mul(unit_secs)?)),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs.is_multiple_of(3600) {
+        format!("{}h", secs / 3600)
+    } else if secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn format_limits(max_turns: Option<u32>, run_timeout: Option<Duration>) -> String {
+    let turns = match max_turns {
+        Some(turns) => format!("{} per run", turns),
+        None => "no limit".to_string(),
+    };
+    let timeout = match run_timeout {
+        Some(timeout) => format!("{} per run", format_duration(timeout)),
+        None => "no limit".to_string(),
+    };
+
+    format!(
+        "⏱️ *Limits*\n\n🔁 *Turns:* {}\n⌛ *Time:* {}\n\nRuns that hit a limit stop with a \
+         Continue button\\. Change them with `/limits turns <n|off>` and `/limits timeout \
+         <duration|off>`\\.",
+        escape_markdown_v2(&turns),
+        escape_markdown_v2(&timeout)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_limit_change() {
+        assert_eq!(
+            parse_limit_change("turns 20"),
+            Some(LimitChange::MaxTurns(Some(20)))
+        );
+        assert_eq!(
+            parse_limit_change("Turns OFF"),
+            Some(LimitChange::MaxTurns(None))
+        );
+        assert_eq!(
+            parse_limit_change("timeout 30m"),
+            Some(LimitChange::Timeout(Some(Duration::from_secs(1800))))
+        );
+        assert_eq!(
+            parse_limit_change("timeout off"),
+            Some(LimitChange::Timeout(None))
+        );
+        assert_eq!(parse_limit_change("turns 0"), None);
+        assert_eq!(parse_limit_change("turns"), None);
+        assert_eq!(parse_limit_change("timeout 5 m"), None);
+        assert_eq!(parse_limit_change("cost 5"), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("15"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn test_format_limits() {
+        assert_eq!(
+            format_limits(Some(20), Some(Duration::from_secs(90))),
+            "⏱️ *Limits*\n\n🔁 *Turns:* 20 per run\n⌛ *Time:* 90s per run\n\nRuns that hit a \
+             limit stop with a Continue button\\. Change them with `/limits turns <n|off>` and \
+             `/limits timeout <duration|off>`\\."
+        );
+        assert!(format_limits(None, None).contains("*Turns:* no limit"));
+        assert_eq!(format_duration(Duration::from_secs(1800)), "30m");
+    }
+}
@@ -0,0 +1,357 @@
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use telegram_bot::claude_code_client::container_utils::container_put_file;
+use telegram_bot::claude_code_client::{ClaudeCodeClient, McpConfig};
+use teloxide::{prelude::*, types::ParseMode};
+
+use super::checkpoints::{send_error, working_dir_client};
+
+/// MCP server definitions, on the user's persistent volume
+pub const MCP_CONFIG_PATH: &str = "/root/.claude/mcp-servers.json";
+
+/// Print "exists" and the MCP configuration, if there is one
+const READ_SCRIPT: &str = r#"
+if [ -f "$1" ]; then
+    echo exists
+    cat -- "$1"
+fi
+"#;
+
+/// Handle the /mcp command
+///
+/// Manages the MCP servers started for Claude: `list` shows them with whether they connected
+/// in the last run, `add <name> <command> [args...]` or `add <name> <url>` defines one and
+/// `remove <name>` deletes it.
+pub async fn handle_mcp(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let args = args.trim();
+    let (subcommand, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+
+    if !matches!(subcommand, "" | "list" | "add" | "remove") {
+        send_usage(&bot, chat_id).await?;
+        return Ok(());
+    }
+
+    let Some(client) = working_dir_client(&bot, chat_id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let mut config = match read_mcp_config(&client).await {
+        Ok(config) => config,
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to read the MCP configuration", &e).await?;
+            return Ok(());
+        }
+    };
+
+    let message = match subcommand {
+        "add" => {
+            let (name, definition) = match parse_server_definition(rest) {
+                Ok(server) => server,
+                Err(problem) => {
+                    bot.send_message(chat_id, format!("❌ {}", escape_markdown_v2(problem)))
+                        .parse_mode(ParseMode::MarkdownV2)
+                        .await?;
+                    return send_usage(&bot, chat_id).await;
+                }
+            };
+            let description = describe_server(&definition);
+            servers_mut(&mut config).insert(name.clone(), definition);
+            format!(
+                "✅ *Added MCP server {}*\n`{}`\n\nIt starts with the next prompt\\.",
+                escape_markdown_v2(&name),
+                escape_markdown_v2(&description)
+            )
+        }
+        "remove" => {
+            let name = rest.trim();
+            if servers_mut(&mut config).remove(name).is_none() {
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "❓ No MCP server named `{}`\\. Use /mcp to list them\\.",
+                        escape_markdown_v2(name)
+                    ),
+                )
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+                return Ok(());
+            }
+            format!("🗑️ *Removed MCP server {}*", escape_markdown_v2(name))
+        }
+        _ => {
+            let tools = {
+                let sessions = bot_state.claude_sessions.lock().await;
+                sessions
+                    .get(&chat_id.0)
+                    .and_then(|session| session.mcp_tools.clone())
+            };
+            let (message_to_send, _was_truncated) =
+                truncate_if_needed(&format_server_list(&config, tools.as_deref()));
+            bot.send_message(chat_id, message_to_send)
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let content = serde_json::to_string_pretty(&config).unwrap_or_default();
+    let written = container_put_file(
+        &bot_state.docker,
+        client.container_id(),
+        MCP_CONFIG_PATH,
+        content.as_bytes(),
+        Some(0o600),
+    )
+    .await;
+    if let Err(e) = written {
+        send_error(
+            &bot,
+            chat_id,
+            "Failed to save the MCP configuration",
+            &e.to_string(),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_message(chat_id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// The MCP configuration to pass to Claude, or `None` without configured servers
+pub async fn load_mcp_config(client: &ClaudeCodeClient) -> Result<Option<McpConfig>, String> {
+    let config = read_mcp_config(client).await?;
+    let servers: Vec<String> = config["mcpServers"]
+        .as_object()
+        .map(|servers| servers.keys().cloned().collect())
+        .unwrap_or_default();
+
+    Ok((!servers.is_empty()).then(|| McpConfig {
+        path: MCP_CONFIG_PATH.to_string(),
+        servers,
+    }))
+}
+
+async fn read_mcp_config(client: &ClaudeCodeClient) -> Result<Value, String> {
+    let output = client
+        .exec_basic_command(vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            READ_SCRIPT.to_string(),
+            "mcp".to_string(),
+            MCP_CONFIG_PATH.to_string(),
+        ])
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match output.strip_prefix("exists") {
+        Some(content) if !content.trim().is_empty() => serde_json::from_str(content)
+            .map_err(|e| format!("{} is not valid JSON: {}", MCP_CONFIG_PATH, e)),
+        _ => Ok(json!({ "mcpServers": {} })),
+    }
+}
+
+fn servers_mut(config: &mut Value) -> &mut Map<String, Value> {
+    if !config["mcpServers"].is_object() {
+        config["mcpServers"] = json!({});
+    }
+    config["mcpServers"]
+        .as_object_mut()
+        .expect("mcpServers was just made an object")
+}
+
+async fn send_usage(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        "❓ *Usage:*\n`/mcp list`\n`/mcp add <name> <command> [args...]`\n`/mcp add <name> \
+         <url>`\n`/mcp remove <name>`\n\nExample: `/mcp add fetch uvx mcp\\-server\\-fetch`",
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+    Ok(())
+}
+
+/// Parse `<name> <command> [args...]` into a stdio server, or `<name> <url>` into an HTTP one
+fn parse_server_definition(args: &str) -> Result<(String, Value), &'static str> {
+    let words = shlex::split(args).ok_or("Unbalanced quotes in the server definition")?;
+    let (name, rest) = words
+        .split_first()
+        .ok_or("Give the server a name and a command or URL")?;
+
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("Server names may only contain letters, digits, '-' and '_'");
+    }
+    let (command, command_args) = rest
+        .split_first()
+        .ok_or("Give the server a command or URL")?;
+
+    let definition = if command.starts_with("http://") || command.starts_with("https://") {
+        if !command_args.is_empty() {
+            return Err("URL servers take no arguments");
+        }
+        let transport = if command.trim_end_matches('/').ends_with("/sse") {
+            "sse"
+        } else {
+            "http"
+        };
+        json!({ "type": transport, "url": command })
+    } else {
+        json!({ "command": command, "args": command_args })
+    };
+
+    Ok((name.clone(), definition))
+}
+
+/// One-line description of a server definition, like its command line or URL
+fn describe_server(definition: &Value) -> String {
+    if let Some(url) = definition["url"].as_str() {
+        return url.to_string();
+    }
+
+    let mut words = vec![definition["command"].as_str().unwrap_or("?").to_string()];
+    words.extend(
+        definition["args"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|arg| arg.as_str())
+            .map(|arg| shlex::try_quote(arg).unwrap_or_default().to_string()),
+    );
+    words.join(" ")
+}
+
+/// Number of tools per MCP server, from tool names like `mcp__<server>__<tool>`
+fn connected_servers(tools: &[String]) -> BTreeMap<String, usize> {
+    let mut servers = BTreeMap::new();
+    for tool in tools {
+        if let Some((server, _)) = tool
+            .strip_prefix("mcp__")
+            .and_then(|rest| rest.split_once("__"))
+        {
+            *servers.entry(server.to_string()).or_insert(0) += 1;
+        }
+    }
+    servers
+}
+
+fn format_server_list(config: &Value, tools: Option<&[String]>) -> String {
+    let servers = config["mcpServers"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+    if servers.is_empty() {
+        return "🔌 *No MCP servers*\n\nAdd one with `/mcp add <name> <command> [args...]` or \
+                `/mcp add <name> <url>`\\."
+            .to_string();
+    }
+
+    let connected = tools.map(connected_servers);
+    let mut message = "🔌 *MCP servers*\n".to_string();
+
+    for (name, definition) in &servers {
+        let status = match &connected {
+            None => "❔ not started yet".to_string(),
+            Some(connected) => match connected.get(name) {
+                Some(1) => "✅ connected, 1 tool".to_string(),
+                Some(count) => format!("✅ connected, {} tools", count),
+                None => "❌ not connected in the last run".to_string(),
+            },
+        };
+        message.push_str(&format!(
+            "\n*{}* \\- {}\n`{}`\n",
+            escape_markdown_v2(name),
+            escape_markdown_v2(&status),
+            escape_markdown_v2(&describe_server(definition))
+        ));
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_server_definition() {
+        assert_eq!(
+            parse_server_definition("fetch uvx mcp-server-fetch --ignore-robots-txt"),
+            Ok((
+                "fetch".to_string(),
+                json!({"command": "uvx", "args": ["mcp-server-fetch", "--ignore-robots-txt"]})
+            ))
+        );
+        assert_eq!(
+            parse_server_definition("docs http://localhost:8080/mcp"),
+            Ok((
+                "docs".to_string(),
+                json!({"type": "http", "url": "http://localhost:8080/mcp"})
+            ))
+        );
+        assert_eq!(
+            parse_server_definition("events http://localhost:9000/sse").map(|(_, d)| d),
+            Ok(json!({"type": "sse", "url": "http://localhost:9000/sse"}))
+        );
+        assert!(parse_server_definition("").is_err());
+        assert!(parse_server_definition("fetch").is_err());
+        assert!(parse_server_definition("bad/name uvx tool").is_err());
+    }
+
+    #[test]
+    fn test_describe_server() {
+        assert_eq!(
+            describe_server(&json!({"command": "npx", "args": ["-y", "a b"]})),
+            "npx -y 'a b'"
+        );
+        assert_eq!(
+            describe_server(&json!({"type": "http", "url": "http://localhost/mcp"})),
+            "http://localhost/mcp"
+        );
+    }
+
+    #[test]
+    fn test_connected_servers() {
+        let tools = vec![
+            "Bash".to_string(),
+            "mcp__github__list_issues".to_string(),
+            "mcp__github__create_pr".to_string(),
+            "mcp__fetch__fetch".to_string(),
+        ];
+        let connected = connected_servers(&tools);
+        assert_eq!(connected.get("github"), Some(&2));
+        assert_eq!(connected.get("fetch"), Some(&1));
+        assert_eq!(connected.len(), 2);
+    }
+
+    #[test]
+    fn test_format_server_list() {
+        let config = json!({"mcpServers": {
+            "fetch": {"command": "uvx", "args": ["mcp-server-fetch"]},
+            "docs": {"type": "http", "url": "http://localhost:8080/mcp"},
+        }});
+        let tools = vec!["mcp__fetch__fetch".to_string()];
+
+        assert_eq!(
+            format_server_list(&config, Some(&tools)),
+            "🔌 *MCP servers*\n\n*docs* \\- ❌ not connected in the last run\n\
+             `http://localhost:8080/mcp`\n\n*fetch* \\- ✅ connected, 1 tool\n`uvx \
+             mcp\\-server\\-fetch`\n"
+        );
+        assert!(format_server_list(&config, None).contains("❔ not started yet"));
+        assert!(format_server_list(&json!({}), None).starts_with("🔌 *No MCP servers*"));
+    }
+}
@@ -2,20 +2,73 @@
 // This module contains all the individual command handlers for the Telegram bot
 
 pub mod auth;
+pub mod auto_commit;
+pub mod blame;
+pub mod checkpoints;
+pub mod ci;
 pub mod claude;
+pub mod claude_md;
 pub mod claude_status;
 pub mod clear_session;
+pub mod clone;
 pub mod commit;
+pub mod deploy_key;
+pub mod discard;
+pub mod export;
+pub mod fork;
+pub mod git_log;
+pub mod github_token;
 pub mod help;
+pub mod history;
+pub mod identity;
+pub mod instructions;
+pub mod interactive;
+pub mod limits;
+pub mod mcp;
+pub mod pr_review;
+pub mod repo;
+pub mod repo_picker;
+pub mod review;
+pub mod signing_key;
 pub mod start;
+pub mod stash;
+pub mod sync;
+pub mod task;
 pub mod update_claude;
+pub mod usage;
 
 // Re-export all command handlers for easy access
 pub use auth::*;
+pub use auto_commit::*;
+pub use blame::*;
+pub use checkpoints::*;
+pub use ci::*;
 pub use claude::*;
+pub use claude_md::*;
 pub use claude_status::*;
 pub use clear_session::*;
+pub use clone::*;
 pub use commit::*;
+pub use deploy_key::*;
+pub use discard::*;
+pub use export::*;
+pub use fork::*;
+pub use git_log::*;
+pub use github_token::*;
 pub use help::*;
+pub use history::*;
+pub use identity::*;
+pub use instructions::*;
+pub use interactive::*;
+pub use limits::*;
+pub use mcp::*;
+pub use pr_review::*;
+pub use repo::*;
+pub use review::*;
+pub use signing_key::*;
 pub use start::*;
+pub use stash::*;
+pub use sync::*;
+pub use task::*;
 pub use update_claude::*;
+pub use usage::*;
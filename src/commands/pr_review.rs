@@ -0,0 +1,601 @@
+use crate::bot::claude_session::{ClaudeSession, PrReviewState};
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::github_client::{group_review_threads, GithubClient, GithubClientConfig};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ParseMode},
+};
+
+use super::checkpoints::working_dir_client;
+use super::commit::{auto_commit, AutoCommitOutcome};
+
+/// Maximum number of characters of a review comment shown in the thread list
+const COMMENT_PREVIEW_LENGTH: usize = 200;
+
+/// Handle the /pr_review command
+pub async fn handle_pr_review(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let pr_number = match args.trim().trim_start_matches('#').parse::<u64>() {
+        Ok(number) => number,
+        Err(_) => {
+            bot.send_message(
+                msg.chat.id,
+                "❓ *Usage:* `/pr_review <number>`\n\nExample: `/pr_review 42`",
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let Some(github_client) = repository_github_client(&bot, msg.chat.id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    bot.send_message(
+        msg.chat.id,
+        format!(
+            "🔄 *Checking out PR \\#{}\\.\\.\\.*\n\nFetching review threads\\.\\.\\.",
+            pr_number
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    if let Err(e) = github_client.pr_checkout(pr_number).await {
+        let full_message = format!(
+            "❌ *Failed to check out PR \\#{}:*\n```\n{}\n```",
+            pr_number,
+            escape_markdown_v2(&e.to_string())
+        );
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+        bot.send_message(msg.chat.id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    }
+
+    let comments = match github_client.pr_review_comments(pr_number).await {
+        Ok(comments) => comments,
+        Err(e) => {
+            let full_message = format!(
+                "❌ *Failed to fetch review comments:*\n```\n{}\n```",
+                escape_markdown_v2(&e.to_string())
+            );
+            let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+            bot.send_message(msg.chat.id, message_to_send)
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let threads = group_review_threads(comments);
+    if threads.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "ℹ️ *No review comments*\n\nPR \\#{} is checked out, but it has no review \
+                 threads to address\\.",
+                pr_number
+            ),
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    let state = PrReviewState::new(pr_number, threads);
+    let message = format_review_threads(&state);
+    let keyboard = review_threads_keyboard(&state);
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .entry(chat_id)
+            .or_insert_with(ClaudeSession::new)
+            .pr_review = Some(state);
+    }
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&message);
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Toggle a review thread in the selection and refresh the keyboard
+pub async fn handle_pr_review_toggle(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    bot_state: &BotState,
+    comment_id: u64,
+) -> ResponseResult<()> {
+    let keyboard = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get_mut(&chat_id.0)
+            .and_then(|s| s.pr_review.as_mut())
+            .map(|state| {
+                state.toggle(comment_id);
+                review_threads_keyboard(state)
+            })
+    };
+
+    let Some(keyboard) = keyboard else {
+        return send_no_review_in_progress(bot, chat_id).await;
+    };
+
+    bot.edit_message_reply_markup(chat_id, message_id)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Let Claude address the selected review threads
+pub async fn handle_pr_review_address(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+) -> ResponseResult<()> {
+    let review = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions.get_mut(&chat_id.0).and_then(|session| {
+            let state = session.pr_review.as_ref()?;
+            let selection = if state.selected.is_empty() {
+                None
+            } else {
+                Some((build_review_prompt(state), state.pr_number))
+            };
+            if selection.is_some() {
+                // Keep the conversation open so the user can follow up on Claude's changes
+                session.is_active = true;
+            }
+            Some((selection, session.conversation_id.clone()))
+        })
+    };
+
+    let Some((selection, conversation_id)) = review else {
+        return send_no_review_in_progress(bot, chat_id).await;
+    };
+
+    let Some((prompt, pr_number)) = selection else {
+        bot.send_message(
+            chat_id,
+            "☝️ Select at least one review thread to address first\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "🤖 *Addressing review comments on PR \\#{}\\.\\.\\.*",
+            pr_number
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    if let Err(e) = super::execute_claude_command(
+        bot.clone(),
+        chat_id,
+        bot_state.clone(),
+        user_id,
+        &prompt,
+        conversation_id,
+    )
+    .await
+    {
+        let full_message = format!(
+            "❌ Claude command failed: {}",
+            escape_markdown_v2(&e.to_string())
+        );
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+        bot.send_message(chat_id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    }
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback("🚀 Push", "pr_review_push")],
+        vec![InlineKeyboardButton::callback(
+            "🚀 Push & Reply to Threads",
+            "pr_review_push_reply",
+        )],
+        vec![InlineKeyboardButton::callback(
+            "❌ Done",
+            "pr_review_cancel",
+        )],
+    ]);
+
+    bot.send_message(
+        chat_id,
+        "✅ *Review comments addressed*\n\nReview the changes above, then push them\\. \
+         You can also keep chatting with Claude to refine the fix\\.",
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .reply_markup(keyboard)
+    .await?;
+
+    Ok(())
+}
+
+/// Commit any pending changes, push the PR branch and optionally reply to the threads
+pub async fn handle_pr_review_push(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    reply_to_threads: bool,
+) -> ResponseResult<()> {
+    let review = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions.get(&chat_id.0).and_then(|session| {
+            session
+                .pr_review
+                .clone()
+                .map(|state| (state, session.last_run_result.clone()))
+        })
+    };
+
+    let Some((state, summary)) = review else {
+        return send_no_review_in_progress(bot, chat_id).await;
+    };
+
+    let Some(github_client) = repository_github_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    // Commit whatever Claude left uncommitted, with a generated message like /commit, so the
+    // push contains the fix
+    let commit_result = match auto_commit(&bot, chat_id, bot_state, user_id, &client).await {
+        Ok(AutoCommitOutcome::OperationInProgress) => {
+            Err("A merge, rebase or cherry-pick is in progress; finish it with /sync first".into())
+        }
+        Ok(AutoCommitOutcome::NothingToCommit | AutoCommitOutcome::Committed(_)) => Ok(()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = commit_result {
+        let full_message = format!(
+            "❌ *Failed to commit changes:*\n```\n{}\n```",
+            escape_markdown_v2(&e.to_string())
+        );
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+        bot.send_message(chat_id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    }
+
+    if let Err(e) = github_client.push_current_branch().await {
+        let full_message = format!(
+            "❌ *Push failed:*\n```\n{}\n```",
+            escape_markdown_v2(&e.to_string())
+        );
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+        bot.send_message(chat_id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    }
+
+    let head = github_client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "rev-parse".to_string(),
+            "--short".to_string(),
+            "HEAD".to_string(),
+        ])
+        .await
+        .unwrap_or_default();
+
+    let mut report = vec![format!(
+        "✅ Pushed PR #{} ({})",
+        state.pr_number,
+        head.trim()
+    )];
+
+    if reply_to_threads {
+        let reply_body = build_thread_reply(head.trim(), summary.as_deref());
+        for thread in state.selected_threads() {
+            match github_client
+                .pr_reply_to_comment(state.pr_number, thread.root.id, &reply_body)
+                .await
+            {
+                Ok(()) => report.push(format!("💬 Replied on {}", thread.location())),
+                Err(e) => report.push(format!(
+                    "⚠️ Failed to reply on {}: {}",
+                    thread.location(),
+                    e
+                )),
+            }
+        }
+    }
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.pr_review = None;
+        }
+    }
+
+    let (message_to_send, _was_truncated) =
+        truncate_if_needed(&escape_markdown_v2(&report.join("\n")));
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    super::ci::watch_head(&bot, chat_id, bot_state, github_client).await?;
+
+    Ok(())
+}
+
+/// Drop the review state for this chat
+pub async fn handle_pr_review_cancel(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.pr_review = None;
+        }
+    }
+
+    bot.send_message(chat_id, "👋 PR review closed\\.")
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Build a GitHub client rooted at the session's repository, informing the user on failure
+pub async fn repository_github_client(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<Option<GithubClient>> {
+    let container_name = format!("coding-session-{}", chat_id.0);
+
+    let client =
+        match ClaudeCodeClient::for_session(bot_state.docker.clone(), &container_name).await {
+            Ok(client) => client,
+            Err(e) => {
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "❌ No active coding session found: {}\n\nPlease start a coding session \
+                     first using /start",
+                        escape_markdown_v2(&e.to_string())
+                    ),
+                )
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+                return Ok(None);
+            }
+        };
+
+    let working_directory = {
+        let claude_sessions = bot_state.claude_sessions.lock().await;
+        claude_sessions
+            .get(&chat_id.0)
+            .and_then(|session| session.get_working_directory().cloned())
+    };
+
+    let Some(working_directory) = working_directory else {
+        bot.send_message(
+            chat_id,
+            "📂 *No repository selected*\n\nClone a repository first, then try again\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(None);
+    };
+
+    Ok(Some(GithubClient::new(
+        bot_state.docker.clone(),
+        client.container_id().to_string(),
+        GithubClientConfig {
+            working_directory: Some(working_directory),
+            ..Default::default()
+        },
+    )))
+}
+
+async fn send_no_review_in_progress(bot: Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        "ℹ️ No PR review in progress\\. Start one with `/pr_review <number>`\\.",
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+    Ok(())
+}
+
+/// Shorten a comment body for display in the thread list
+fn preview(text: &str) -> String {
+    let single_line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if single_line.chars().count() > COMMENT_PREVIEW_LENGTH {
+        let truncated: String = single_line.chars().take(COMMENT_PREVIEW_LENGTH).collect();
+        format!("{}…", truncated)
+    } else {
+        single_line
+    }
+}
+
+/// Render the list of review threads as a MarkdownV2 message
+fn format_review_threads(state: &PrReviewState) -> String {
+    let mut message = format!(
+        "📝 *Review threads on PR \\#{}*\n\nSelect the threads Claude should address:\n",
+        state.pr_number
+    );
+
+    for (index, thread) in state.threads.iter().enumerate() {
+        message.push_str(&format!(
+            "\n*{}\\.* `{}` — @{}\n{}\n",
+            index + 1,
+            escape_markdown_v2(&thread.location()),
+            escape_markdown_v2(thread.root.author()),
+            escape_markdown_v2(&preview(&thread.root.body))
+        ));
+        if !thread.replies.is_empty() {
+            message.push_str(&format!(
+                "_{} {}_\n",
+                thread.replies.len(),
+                if thread.replies.len() == 1 {
+                    "reply"
+                } else {
+                    "replies"
+                }
+            ));
+        }
+    }
+
+    message
+}
+
+/// Build the selection keyboard for the review threads
+fn review_threads_keyboard(state: &PrReviewState) -> InlineKeyboardMarkup {
+    let mut keyboard_rows: Vec<Vec<InlineKeyboardButton>> = state
+        .threads
+        .iter()
+        .enumerate()
+        .map(|(index, thread)| {
+            let marker = if state.selected.contains(&thread.root.id) {
+                "☑️"
+            } else {
+                "⬜"
+            };
+            vec![InlineKeyboardButton::callback(
+                format!("{} {}. {}", marker, index + 1, thread.location()),
+                format!("pr_review_toggle:{}", thread.root.id),
+            )]
+        })
+        .collect();
+
+    keyboard_rows.push(vec![InlineKeyboardButton::callback(
+        format!("🤖 Address Selected ({})", state.selected.len()),
+        "pr_review_address",
+    )]);
+    keyboard_rows.push(vec![InlineKeyboardButton::callback(
+        "❌ Cancel",
+        "pr_review_cancel",
+    )]);
+
+    InlineKeyboardMarkup::new(keyboard_rows)
+}
+
+/// Build the Claude prompt describing the selected review threads
+fn build_review_prompt(state: &PrReviewState) -> String {
+    let mut prompt = format!(
+        "Address the following review comments on pull request #{}. The PR branch is already \
+         checked out. Make the necessary code changes but do not commit or push.\n",
+        state.pr_number
+    );
+
+    for (index, thread) in state.selected_threads().iter().enumerate() {
+        prompt.push_str(&format!(
+            "\n### Comment {} — {} (by @{})\n{}\n",
+            index + 1,
+            thread.location(),
+            thread.root.author(),
+            thread.root.body
+        ));
+        for reply in &thread.replies {
+            prompt.push_str(&format!(
+                "\n> Reply by @{}: {}\n",
+                reply.author(),
+                reply.body
+            ));
+        }
+    }
+
+    prompt
+}
+
+/// Build the reply posted on each addressed thread
+fn build_thread_reply(commit: &str, summary: Option<&str>) -> String {
+    let mut reply = format!("Addressed in {}.", commit);
+    if let Some(summary) = summary.map(str::trim).filter(|s| !s.is_empty()) {
+        reply.push_str("\n\n");
+        reply.push_str(summary);
+    }
+    reply
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github_client::types::GithubUser;
+    use crate::github_client::{PullRequestReviewComment, ReviewThread};
+
+    fn thread(id: u64, body: &str) -> ReviewThread {
+        ReviewThread {
+            root: PullRequestReviewComment {
+                id,
+                path: "src/app.rs".to_string(),
+                line: Some(12),
+                original_line: None,
+                body: body.to_string(),
+                user: Some(GithubUser {
+                    login: "alice".to_string(),
+                }),
+                in_reply_to_id: None,
+            },
+            replies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_review_prompt_includes_only_selected_threads() {
+        let mut state = PrReviewState::new(5, vec![thread(1, "Rename foo"), thread(2, "Add test")]);
+        state.toggle(2);
+
+        let prompt = build_review_prompt(&state);
+
+        assert!(prompt.contains("pull request #5"));
+        assert!(prompt.contains("Add test"));
+        assert!(prompt.contains("src/app.rs:12"));
+        assert!(!prompt.contains("Rename foo"));
+    }
+
+    #[test]
+    fn test_build_thread_reply() {
+        assert_eq!(build_thread_reply("abc1234", None), "Addressed in abc1234.");
+        assert_eq!(
+            build_thread_reply("abc1234", Some("  Renamed the helper.  ")),
+            "Addressed in abc1234.\n\nRenamed the helper."
+        );
+    }
+
+    #[test]
+    fn test_preview_truncates_long_comments() {
+        let long = "word ".repeat(100);
+        let shortened = preview(&long);
+        assert!(shortened.ends_with('…'));
+        assert_eq!(shortened.chars().count(), COMMENT_PREVIEW_LENGTH + 1);
+        assert_eq!(preview("short\n comment"), "short comment");
+    }
+}
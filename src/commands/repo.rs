@@ -0,0 +1,270 @@
+use crate::bot::claude_session::ClaudeSession;
+use crate::bot::markdown::escape_markdown_v2;
+use crate::BotState;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+/// Directory all repositories are cloned into; Claude sees every repository from here
+pub const WORKSPACE_DIRECTORY: &str = "/workspace";
+
+/// Handle the /repo command
+///
+/// Lists the repositories cloned in this session and switches between them. Each repository
+/// keeps its own Claude conversation. `/repo all` runs Claude from the workspace root so it
+/// can make changes across repositories.
+pub async fn handle_repo(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let target = args.trim();
+    if target.is_empty() {
+        return send_repository_list(bot, msg.chat.id, &bot_state).await;
+    }
+
+    if matches!(target, "all" | "workspace") {
+        return switch_to(
+            bot,
+            msg.chat.id,
+            &bot_state,
+            WORKSPACE_DIRECTORY.to_string(),
+        )
+        .await;
+    }
+
+    let directory = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions.get(&msg.chat.id.0).and_then(|session| {
+            session
+                .repositories
+                .iter()
+                .find(|directory| *directory == target || repository_name(directory) == target)
+                .cloned()
+        })
+    };
+
+    match directory {
+        Some(directory) => switch_to(bot, msg.chat.id, &bot_state, directory).await,
+        None => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "❓ No repository named `{}` in this session\\. Use /repo to list them, or \
+                     /clone to add it\\.",
+                    escape_markdown_v2(target)
+                ),
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+/// Switch to the repository at `index` in the session's list, or to the workspace root
+pub async fn handle_repo_switch(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    target: &str,
+) -> ResponseResult<()> {
+    if target == "all" {
+        return switch_to(bot, chat_id, bot_state, WORKSPACE_DIRECTORY.to_string()).await;
+    }
+
+    let directory = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        target.parse::<usize>().ok().and_then(|index| {
+            sessions
+                .get(&chat_id.0)
+                .and_then(|session| session.repositories.get(index).cloned())
+        })
+    };
+
+    match directory {
+        Some(directory) => switch_to(bot, chat_id, bot_state, directory).await,
+        None => send_repository_list(bot, chat_id, bot_state).await,
+    }
+}
+
+async fn switch_to(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    directory: String,
+) -> ResponseResult<()> {
+    let resumed = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        let session = sessions.entry(chat_id.0).or_insert_with(ClaudeSession::new);
+
+        if session.has_parallel_tasks() {
+            None
+        } else {
+            session.switch_directory(directory.clone());
+            Some(session.conversation_id.is_some())
+        }
+    };
+
+    let Some(resumed) = resumed else {
+        bot.send_message(
+            chat_id,
+            "⚠️ *Parallel tasks in progress*\n\nRemove them with `/task remove <name>` before \
+             switching repositories\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let conversation = if resumed {
+        "💬 Resuming the previous conversation here"
+    } else {
+        "💬 Starting a new conversation here"
+    };
+
+    let message = if directory == WORKSPACE_DIRECTORY {
+        format!(
+            "🌐 *All repositories*\n\nClaude now runs from `{}` and sees every cloned \
+             repository, for changes that span them\\. Git commands like /commit need a single \
+             repository, so pick one with /repo first\\.\n\n{}",
+            escape_markdown_v2(WORKSPACE_DIRECTORY),
+            conversation
+        )
+    } else {
+        format!(
+            "🔀 *Switched to {}*\n\n📁 {}\n{}",
+            escape_markdown_v2(repository_name(&directory)),
+            escape_markdown_v2(&directory),
+            conversation
+        )
+    };
+
+    bot.send_message(chat_id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+async fn send_repository_list(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    let (repositories, current) = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .map(|session| {
+                (
+                    session.repositories.clone(),
+                    session.get_working_directory().cloned(),
+                )
+            })
+            .unwrap_or_default()
+    };
+
+    if repositories.is_empty() {
+        bot.send_message(
+            chat_id,
+            "📂 *No repositories yet*\n\nClone one with /clone, then use /repo to switch \
+             between them\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        chat_id,
+        format_repository_list(&repositories, current.as_deref()),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .reply_markup(repository_keyboard(&repositories, current.as_deref()))
+    .await?;
+
+    Ok(())
+}
+
+/// Display name of a repository: the last component of its directory
+pub fn repository_name(directory: &str) -> &str {
+    let trimmed = directory.trim_end_matches('/');
+    trimmed.rsplit('/').next().unwrap_or(trimmed)
+}
+
+fn format_repository_list(repositories: &[String], current: Option<&str>) -> String {
+    let mut message = "📚 *Repositories*\n".to_string();
+
+    for directory in repositories {
+        let marker = if current == Some(directory.as_str()) {
+            "✅"
+        } else {
+            "📦"
+        };
+        message.push_str(&format!(
+            "\n{} *{}* \\- `{}`",
+            marker,
+            escape_markdown_v2(repository_name(directory)),
+            escape_markdown_v2(directory)
+        ));
+    }
+
+    if current == Some(WORKSPACE_DIRECTORY) {
+        message.push_str("\n\n🌐 Claude currently runs from the workspace root with all of them");
+    }
+
+    message
+}
+
+fn repository_keyboard(repositories: &[String], current: Option<&str>) -> InlineKeyboardMarkup {
+    let mut keyboard_rows: Vec<Vec<InlineKeyboardButton>> = repositories
+        .iter()
+        .enumerate()
+        .map(|(index, directory)| {
+            let marker = if current == Some(directory.as_str()) {
+                "✅"
+            } else {
+                "📦"
+            };
+            vec![InlineKeyboardButton::callback(
+                format!("{} {}", marker, repository_name(directory)),
+                format!("repo_switch:{}", index),
+            )]
+        })
+        .collect();
+
+    keyboard_rows.push(vec![InlineKeyboardButton::callback(
+        "🌐 All repositories",
+        "repo_switch:all",
+    )]);
+
+    InlineKeyboardMarkup::new(keyboard_rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_name() {
+        assert_eq!(repository_name("api"), "api");
+        assert_eq!(repository_name("/workspace/web/"), "web");
+    }
+
+    #[test]
+    fn test_format_repository_list() {
+        let repositories = vec!["api".to_string(), "my-web".to_string()];
+
+        assert_eq!(
+            format_repository_list(&repositories, Some("api")),
+            "📚 *Repositories*\n\n✅ *api* \\- `api`\n📦 *my\\-web* \\- `my\\-web`"
+        );
+        assert!(
+            format_repository_list(&repositories, Some(WORKSPACE_DIRECTORY))
+                .ends_with("with all of them")
+        );
+    }
+}
@@ -0,0 +1,433 @@
+use crate::bot::claude_session::{ClaudeSession, PendingInput, RepoPickerState};
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::github_client::{GithubClient, GithubClientConfig};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ParseMode},
+};
+
+/// Maximum number of characters of a repository description shown in the picker
+const DESCRIPTION_PREVIEW_LENGTH: usize = 80;
+
+/// Fetch the user's repositories and show the first page of the picker
+pub async fn show_repository_picker(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    claude_client: &ClaudeCodeClient,
+    intro: &str,
+) -> ResponseResult<()> {
+    let github_client = GithubClient::new(
+        bot_state.docker.clone(),
+        claude_client.container_id().to_string(),
+        GithubClientConfig::default(),
+    );
+
+    let repos = match github_client.repo_list_detailed().await {
+        Ok(repos) => repos,
+        Err(e) => {
+            log::warn!("Failed to list repositories for chat {}: {}", chat_id.0, e);
+
+            // Show manual entry option if repo listing fails
+            bot.send_message(
+                chat_id,
+                format!(
+                    "{}\n\n⚠️ Could not list repositories\\. You can enter one manually:",
+                    intro
+                ),
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .reply_markup(InlineKeyboardMarkup::new(fallback_rows()))
+            .await?;
+            return Ok(());
+        }
+    };
+
+    if repos.is_empty() {
+        bot.send_message(
+            chat_id,
+            "📁 *No Repositories Found*\n\n💡 No repositories found or accessible\\. You \
+             can:\n\n• Create a new repository on GitHub\n• Get access to existing \
+             repositories\n• Enter any git URL manually",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(InlineKeyboardMarkup::new(fallback_rows()))
+        .await?;
+        return Ok(());
+    }
+
+    let picker = RepoPickerState::new(repos);
+    let (text, keyboard) = render_picker(&picker);
+    store_picker(bot_state, chat_id, picker).await;
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&format!("{}\n\n{}", intro, text));
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Switch the picker message to another page
+pub async fn handle_repo_page(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    bot_state: &BotState,
+    page: usize,
+) -> ResponseResult<()> {
+    let rendered = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get_mut(&chat_id.0)
+            .and_then(|s| s.repo_picker.as_mut())
+            .map(|picker| {
+                let previous_page = picker.page;
+                picker.set_page(page);
+                (picker.page != previous_page).then(|| render_picker(picker))
+            })
+    };
+
+    match rendered {
+        // Page indicator pressed or already at the edge: nothing to redraw
+        Some(None) => Ok(()),
+        Some(Some((text, keyboard))) => {
+            edit_picker_message(bot, chat_id, message_id, text, keyboard).await
+        }
+        None => send_picker_expired(bot, chat_id).await,
+    }
+}
+
+/// Clone the repository picked from the list
+pub async fn handle_repo_pick(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    index: usize,
+) -> ResponseResult<()> {
+    let repository = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions.get_mut(&chat_id.0).and_then(|s| {
+            let repository = s
+                .repo_picker
+                .as_ref()
+                .and_then(|picker| picker.repos.get(index))
+                .map(|repo| repo.name_with_owner.clone());
+            if repository.is_some() {
+                s.repo_picker = None;
+                s.pending_input = None;
+            }
+            repository
+        })
+    };
+
+    let Some(repository) = repository else {
+        return send_picker_expired(bot, chat_id).await;
+    };
+
+    super::start::handle_repository_clone_in_start(bot, chat_id, bot_state, &repository).await
+}
+
+/// Ask the user to type a search query for the picker
+pub async fn handle_repo_search_prompt(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    let has_picker = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        match sessions.get_mut(&chat_id.0) {
+            Some(session) if session.repo_picker.is_some() => {
+                session.pending_input = Some(PendingInput::RepoSearch);
+                true
+            }
+            _ => false,
+        }
+    };
+
+    if !has_picker {
+        return send_picker_expired(bot, chat_id).await;
+    }
+
+    bot.send_message(
+        chat_id,
+        "🔍 *Search Repositories*\n\nType part of a repository name or description:",
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    Ok(())
+}
+
+/// Filter the picker with a query typed by the user and show the results
+pub async fn handle_repo_search_query(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    query: &str,
+) -> ResponseResult<()> {
+    let rendered = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get_mut(&chat_id.0)
+            .and_then(|s| s.repo_picker.as_mut())
+            .map(|picker| {
+                picker.set_query(Some(query.trim().to_string()));
+                render_picker(picker)
+            })
+    };
+
+    let Some((text, keyboard)) = rendered else {
+        return send_picker_expired(bot, chat_id).await;
+    };
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&text);
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Remove the search filter from the picker
+pub async fn handle_repo_search_clear(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    let rendered = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions.get_mut(&chat_id.0).and_then(|s| {
+            s.pending_input = None;
+            s.repo_picker.as_mut().map(|picker| {
+                picker.set_query(None);
+                render_picker(picker)
+            })
+        })
+    };
+
+    match rendered {
+        Some((text, keyboard)) => {
+            edit_picker_message(bot, chat_id, message_id, text, keyboard).await
+        }
+        None => send_picker_expired(bot, chat_id).await,
+    }
+}
+
+async fn store_picker(bot_state: &BotState, chat_id: ChatId, picker: RepoPickerState) {
+    let mut sessions = bot_state.claude_sessions.lock().await;
+    let session = sessions.entry(chat_id.0).or_insert_with(ClaudeSession::new);
+    session.repo_picker = Some(picker);
+    session.pending_input = None;
+}
+
+async fn edit_picker_message(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    text: String,
+    keyboard: InlineKeyboardMarkup,
+) -> ResponseResult<()> {
+    let (message_to_send, _was_truncated) = truncate_if_needed(&text);
+    bot.edit_message_text(chat_id, message_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+async fn send_picker_expired(bot: Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        "⚠️ *Repository list expired*\n\nUse the 📂 List Repositories button or /start to load \
+         it again\\.",
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    Ok(())
+}
+
+/// Manual entry and skip buttons shown below every picker
+fn fallback_rows() -> Vec<Vec<InlineKeyboardButton>> {
+    vec![
+        vec![InlineKeyboardButton::callback(
+            "✏️ Enter Repository Manually",
+            "manual_repo_entry",
+        )],
+        vec![InlineKeyboardButton::callback(
+            "⏭️ Skip Repository Setup",
+            "skip_repo_setup",
+        )],
+    ]
+}
+
+/// Render the current picker page as message text plus inline keyboard
+fn render_picker(picker: &RepoPickerState) -> (String, InlineKeyboardMarkup) {
+    let filtered_count = picker.filtered().len();
+    let page = picker.current_page();
+
+    let mut text = match &picker.query {
+        Some(query) => format!(
+            "🔍 *{} of {} repositories match* `{}`",
+            filtered_count,
+            picker.repos.len(),
+            escape_markdown_v2(query)
+        ),
+        None => format!("📂 *{} repositories*", picker.repos.len()),
+    };
+    text.push_str(&format!(
+        " \\(page {}/{}\\)\n",
+        picker.page + 1,
+        picker.page_count()
+    ));
+
+    if page.is_empty() {
+        text.push_str("\nNo repositories match your search\\.");
+    }
+
+    let mut rows = Vec::new();
+    for (index, repo) in &page {
+        let lock = if repo.is_private() { " 🔒" } else { "" };
+
+        text.push_str(&format!(
+            "\n📦 *{}*{}",
+            escape_markdown_v2(&repo.name_with_owner),
+            lock
+        ));
+        if let Some(date) = repo.updated_date() {
+            text.push_str(&format!(" · _{}_", escape_markdown_v2(date)));
+        }
+        if let Some(description) = repo.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            text.push_str(&format!(
+                "\n    {}",
+                escape_markdown_v2(&preview(description))
+            ));
+        }
+
+        rows.push(vec![InlineKeyboardButton::callback(
+            format!("📦 {}{}", repo.name_with_owner, lock),
+            format!("repo_pick:{}", index),
+        )]);
+    }
+
+    if picker.page_count() > 1 {
+        let mut nav = Vec::new();
+        if picker.page > 0 {
+            nav.push(InlineKeyboardButton::callback(
+                "⬅️ Prev",
+                format!("repo_page:{}", picker.page - 1),
+            ));
+        }
+        nav.push(InlineKeyboardButton::callback(
+            format!("{}/{}", picker.page + 1, picker.page_count()),
+            format!("repo_page:{}", picker.page),
+        ));
+        if picker.page + 1 < picker.page_count() {
+            nav.push(InlineKeyboardButton::callback(
+                "Next ➡️",
+                format!("repo_page:{}", picker.page + 1),
+            ));
+        }
+        rows.push(nav);
+    }
+
+    let mut search_row = vec![InlineKeyboardButton::callback("🔍 Search", "repo_search")];
+    if picker.query.is_some() {
+        search_row.push(InlineKeyboardButton::callback(
+            "✖️ Clear Search",
+            "repo_search_clear",
+        ));
+    }
+    rows.push(search_row);
+    rows.extend(fallback_rows());
+
+    (text, InlineKeyboardMarkup::new(rows))
+}
+
+fn preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or("").trim();
+    if line.chars().count() > DESCRIPTION_PREVIEW_LENGTH {
+        format!(
+            "{}…",
+            line.chars()
+                .take(DESCRIPTION_PREVIEW_LENGTH)
+                .collect::<String>()
+        )
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github_client::RepositoryInfo;
+    use teloxide::types::InlineKeyboardButtonKind;
+
+    fn repo(name: &str, visibility: &str) -> RepositoryInfo {
+        RepositoryInfo {
+            name_with_owner: format!("owner/{}", name),
+            name: name.to_string(),
+            description: Some(format!("The {} project", name)),
+            visibility: Some(visibility.to_string()),
+            updated_at: Some("2024-05-01T12:34:56Z".to_string()),
+        }
+    }
+
+    fn callback_data(keyboard: &InlineKeyboardMarkup) -> Vec<String> {
+        keyboard
+            .inline_keyboard
+            .iter()
+            .flatten()
+            .filter_map(|button| match &button.kind {
+                InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_picker_pagination() {
+        let repos = (0..10)
+            .map(|i| repo(&format!("repo-{}", i), "PUBLIC"))
+            .collect();
+        let mut picker = RepoPickerState::new(repos);
+
+        let (text, keyboard) = render_picker(&picker);
+        let data = callback_data(&keyboard);
+        assert!(text.contains("page 1/2"));
+        assert!(data.contains(&"repo_pick:0".to_string()));
+        assert!(data.contains(&"repo_page:1".to_string()));
+        assert!(!data.contains(&"repo_pick:8".to_string()));
+        assert!(!data.contains(&"repo_search_clear".to_string()));
+
+        picker.set_page(1);
+        let (_, keyboard) = render_picker(&picker);
+        let data = callback_data(&keyboard);
+        assert!(data.contains(&"repo_pick:8".to_string()));
+        assert!(data.contains(&"repo_page:0".to_string()));
+    }
+
+    #[test]
+    fn test_render_picker_search() {
+        let mut picker =
+            RepoPickerState::new(vec![repo("alpha", "PUBLIC"), repo("beta", "PRIVATE")]);
+        picker.set_query(Some("beta".to_string()));
+
+        let (text, keyboard) = render_picker(&picker);
+        let data = callback_data(&keyboard);
+        assert!(text.contains("1 of 2 repositories match"));
+        assert!(text.contains("🔒"));
+        assert!(data.contains(&"repo_pick:1".to_string()));
+        assert!(!data.contains(&"repo_pick:0".to_string()));
+        assert!(data.contains(&"repo_search_clear".to_string()));
+        assert!(!data.iter().any(|d| d.starts_with("repo_page:")));
+    }
+}
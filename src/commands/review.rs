@@ -0,0 +1,592 @@
+use crate::bot::claude_session::{ClaudeSession, CodeReviewState};
+use crate::bot::markdown::{capitalize, escape_markdown_v2, truncate_if_needed};
+use crate::github_client::{ReviewFinding, ReviewSeverity};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+use super::checkpoints::{send_error, working_dir_client};
+use super::pr_review::repository_github_client;
+use super::sync::{run_git_script, UPSTREAM_SCRIPT};
+
+/// System prompt that turns Claude into a reviewer answering with JSON findings
+const REVIEW_SYSTEM_PROMPT: &str = "You are a meticulous senior engineer reviewing a code \
+change. Focus on bugs, security issues, data loss, race conditions, error handling and missing \
+tests; only mention style when it hurts readability. You may read files in the repository for \
+context, but do not modify anything.
+
+Respond with only a JSON array and no other text. Each element is one finding:
+{\"file\": \"path relative to the repository root\", \"line\": <line number in the new version \
+of the file, or null>, \"severity\": \"critical\" | \"major\" | \"minor\" | \"nit\", \
+\"suggestion\": \"what is wrong and how to fix it\"}
+
+Only point at lines that are part of the diff. Respond with [] when the change looks good.";
+
+/// Diffs are passed to Claude on the command line, which limits a single argument to 128 KiB
+const MAX_DIFF_BYTES: usize = 60_000;
+
+/// Findings beyond this many are summarized instead of sent one message each
+const MAX_FINDING_MESSAGES: usize = 20;
+
+/// What /review looks at
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ReviewTarget {
+    /// Committed and uncommitted changes of the current branch against the default branch
+    WorkingTree,
+    PullRequest(u64),
+    /// A branch compared with the default branch
+    Branch(String),
+}
+
+impl ReviewTarget {
+    fn parse(args: &str) -> Self {
+        let args = args.trim();
+        if args.is_empty() {
+            return ReviewTarget::WorkingTree;
+        }
+
+        match args.trim_start_matches('#').parse::<u64>() {
+            Ok(number) => ReviewTarget::PullRequest(number),
+            Err(_) => ReviewTarget::Branch(args.to_string()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            ReviewTarget::WorkingTree => "the changes on the current branch".to_string(),
+            ReviewTarget::PullRequest(number) => format!("PR #{}", number),
+            ReviewTarget::Branch(branch) => format!("branch {}", branch),
+        }
+    }
+
+    /// Command Claude can run to see the full diff when it was cut off
+    fn diff_command(&self) -> String {
+        match self {
+            ReviewTarget::WorkingTree => "git diff $(git merge-base origin/HEAD HEAD)".to_string(),
+            ReviewTarget::PullRequest(number) => format!("gh pr diff {}", number),
+            ReviewTarget::Branch(branch) => format!("git diff origin/HEAD...{}", branch),
+        }
+    }
+}
+
+/// Handle the /review command
+///
+/// Runs Claude as a reviewer over a pull request, a branch or the current changes and sends
+/// each finding as its own message. Findings on a pull request can then be posted to GitHub
+/// as a review.
+pub async fn handle_review(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let target = ReviewTarget::parse(&args);
+    let user_id = msg
+        .from
+        .as_ref()
+        .map(|user| user.id.0 as i64)
+        .unwrap_or(chat_id);
+
+    let Some(client) = working_dir_client(&bot, msg.chat.id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let (diff, head_sha) = match &target {
+        ReviewTarget::PullRequest(number) => {
+            let Some(github_client) =
+                repository_github_client(&bot, msg.chat.id, &bot_state).await?
+            else {
+                return Ok(());
+            };
+
+            let diff = match github_client.pr_diff(*number).await {
+                Ok(diff) => diff,
+                Err(e) => {
+                    let title = format!("Failed to fetch the diff of PR #{}", number);
+                    send_error(&bot, msg.chat.id, &title, &e.to_string()).await?;
+                    return Ok(());
+                }
+            };
+            let head_sha = match github_client.pr_head_sha(*number).await {
+                Ok(sha) => sha,
+                Err(e) => {
+                    let title = format!("Failed to look up PR #{}", number);
+                    send_error(&bot, msg.chat.id, &title, &e.to_string()).await?;
+                    return Ok(());
+                }
+            };
+
+            (diff, Some(head_sha))
+        }
+        ReviewTarget::WorkingTree => match branch_diff(&client, None).await {
+            Ok(diff) => (diff, None),
+            Err(e) => {
+                send_error(&bot, msg.chat.id, "Failed to diff", &e.to_string()).await?;
+                return Ok(());
+            }
+        },
+        ReviewTarget::Branch(branch) => match branch_diff(&client, Some(branch)).await {
+            Ok(diff) => (diff, None),
+            Err(e) => {
+                send_error(&bot, msg.chat.id, "Failed to diff", &e.to_string()).await?;
+                return Ok(());
+            }
+        },
+    };
+
+    if diff.trim().is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "ℹ️ *Nothing to review*\n\n{} has no changes\\.",
+                escape_markdown_v2(&capitalize(&target.describe()))
+            ),
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        msg.chat.id,
+        format!(
+            "🔍 *Reviewing {}\\.\\.\\.*\n\nThis can take a few minutes\\.",
+            escape_markdown_v2(&target.describe())
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    let output = match run_review(
+        &bot,
+        msg.chat.id,
+        &bot_state,
+        user_id,
+        &client,
+        &target,
+        &diff,
+    )
+    .await
+    {
+        Ok(Some(output)) => output,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            send_error(&bot, msg.chat.id, "Review failed", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    let Some(findings) = parse_findings(&output) else {
+        let full_message = format!(
+            "⚠️ *Claude didn't return structured findings*\n\n{}",
+            escape_markdown_v2(&output)
+        );
+        let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+        bot.send_message(msg.chat.id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    };
+
+    send_findings(&bot, msg.chat.id, &target, &findings).await?;
+
+    let (ReviewTarget::PullRequest(pr_number), Some(head_sha)) = (&target, head_sha) else {
+        return Ok(());
+    };
+    if findings.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "📝 Post these {} findings as a review on PR \\#{}?",
+        findings.len(),
+        pr_number
+    );
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .entry(chat_id)
+            .or_insert_with(ClaudeSession::new)
+            .code_review = Some(CodeReviewState {
+            pr_number: *pr_number,
+            head_sha,
+            findings,
+        });
+    }
+
+    bot.send_message(msg.chat.id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("📝 Post as PR review", "review_post"),
+        ]]))
+        .await?;
+
+    Ok(())
+}
+
+/// Post the findings of the last pull request review to GitHub
+pub async fn handle_review_post(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    let review = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .and_then(|session| session.code_review.clone())
+    };
+
+    let Some(review) = review else {
+        bot.send_message(
+            chat_id,
+            "ℹ️ *No review to post*\n\nRun `/review <pr-number>` first\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let Some(github_client) = repository_github_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    let (inline, general): (Vec<_>, Vec<_>) = review
+        .findings
+        .iter()
+        .cloned()
+        .partition(|finding| finding.line.is_some());
+
+    let posted = match github_client
+        .pr_create_review(
+            review.pr_number,
+            &review.head_sha,
+            &review_body(&general),
+            &inline,
+        )
+        .await
+    {
+        Ok(url) => Ok(url),
+        Err(e) => {
+            // GitHub rejects the whole review when a comment points outside the diff, so
+            // fall back to listing every finding in the review body
+            log::warn!(
+                "Posting inline review comments failed, retrying without: {}",
+                e
+            );
+            github_client
+                .pr_create_review(
+                    review.pr_number,
+                    &review.head_sha,
+                    &review_body(&review.findings),
+                    &[],
+                )
+                .await
+        }
+    };
+
+    match posted {
+        Ok(url) => {
+            {
+                let mut sessions = bot_state.claude_sessions.lock().await;
+                if let Some(session) = sessions.get_mut(&chat_id.0) {
+                    session.code_review = None;
+                }
+            }
+
+            bot.send_message(
+                chat_id,
+                format!(
+                    "✅ *Review posted on PR \\#{}*\n\n{}",
+                    review.pr_number,
+                    escape_markdown_v2(&url)
+                ),
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        }
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to post the review", &e.to_string()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Diff a branch (or the current changes) against the remote default branch
+async fn branch_diff(
+    client: &ClaudeCodeClient,
+    branch: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let upstream = run_git_script(client, UPSTREAM_SCRIPT, vec![]).await?;
+    let upstream = upstream.trim();
+    if upstream.is_empty() {
+        return Err("origin has no HEAD branch".into());
+    }
+
+    let base = match branch {
+        Some(branch) => format!("{}...{}", upstream, branch),
+        // Diffing against the merge base includes uncommitted changes
+        None => client
+            .exec_basic_command(vec![
+                "git".to_string(),
+                "merge-base".to_string(),
+                upstream.to_string(),
+                "HEAD".to_string(),
+            ])
+            .await?
+            .trim()
+            .to_string(),
+    };
+
+    client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "diff".to_string(),
+            base,
+            "--".to_string(),
+        ])
+        .await
+}
+
+/// Returns None when a budget is used up
+async fn run_review(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    client: &ClaudeCodeClient,
+    target: &ReviewTarget,
+    diff: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let args = vec![
+        "--append-system-prompt".to_string(),
+        REVIEW_SYSTEM_PROMPT.to_string(),
+        build_review_prompt(target, diff),
+    ];
+    super::usage::run_claude_print(bot, chat_id, bot_state, user_id, client, args).await
+}
+
+fn build_review_prompt(target: &ReviewTarget, diff: &str) -> String {
+    let (diff, was_truncated) = truncate_diff(diff);
+    let mut prompt = format!("Review {}.\n\n```diff\n{}\n```", target.describe(), diff);
+
+    if was_truncated {
+        prompt.push_str(&format!(
+            "\n\nThe diff was cut off after {} bytes; run `{}` to see the rest.",
+            MAX_DIFF_BYTES,
+            target.diff_command()
+        ));
+    }
+
+    prompt
+}
+
+fn truncate_diff(diff: &str) -> (&str, bool) {
+    if diff.len() <= MAX_DIFF_BYTES {
+        return (diff, false);
+    }
+
+    let mut end = MAX_DIFF_BYTES;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    (&diff[..end], true)
+}
+
+/// Extract the JSON array of findings from Claude's answer, most severe first
+///
+/// Tolerates code fences or a sentence around the array.
+fn parse_findings(output: &str) -> Option<Vec<ReviewFinding>> {
+    let start = output.find('[')?;
+    let end = output.rfind(']')?;
+    if end < start {
+        return None;
+    }
+
+    let mut findings: Vec<ReviewFinding> = serde_json::from_str(&output[start..=end]).ok()?;
+    findings.sort_by_key(|finding| finding.severity);
+    Some(findings)
+}
+
+async fn send_findings(
+    bot: &Bot,
+    chat_id: ChatId,
+    target: &ReviewTarget,
+    findings: &[ReviewFinding],
+) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "🔍 *Review of {}*\n\n{}",
+            escape_markdown_v2(&target.describe()),
+            escape_markdown_v2(&summarize_findings(findings))
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    for finding in findings.iter().take(MAX_FINDING_MESSAGES) {
+        let (message_to_send, _was_truncated) = truncate_if_needed(&format_finding(finding));
+        bot.send_message(chat_id, message_to_send)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+    }
+
+    if findings.len() > MAX_FINDING_MESSAGES {
+        bot.send_message(
+            chat_id,
+            format!(
+                "… and {} more findings of lower severity\\.",
+                findings.len() - MAX_FINDING_MESSAGES
+            ),
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+    }
+
+    Ok(())
+}
+
+fn summarize_findings(findings: &[ReviewFinding]) -> String {
+    if findings.is_empty() {
+        return "✅ No issues found.".to_string();
+    }
+
+    let counts: Vec<String> = [
+        ReviewSeverity::Critical,
+        ReviewSeverity::Major,
+        ReviewSeverity::Minor,
+        ReviewSeverity::Nit,
+    ]
+    .iter()
+    .filter_map(|severity| {
+        let count = findings.iter().filter(|f| f.severity == *severity).count();
+        (count > 0).then(|| format!("{} {}", count, severity.label().to_lowercase()))
+    })
+    .collect();
+
+    let noun = if findings.len() == 1 {
+        "finding"
+    } else {
+        "findings"
+    };
+    format!("{} {}: {}", findings.len(), noun, counts.join(", "))
+}
+
+fn format_finding(finding: &ReviewFinding) -> String {
+    format!(
+        "{} *{}* · `{}`\n\n{}",
+        finding.severity.emoji(),
+        finding.severity.label(),
+        escape_markdown_v2(&finding.location()),
+        escape_markdown_v2(&finding.suggestion)
+    )
+}
+
+/// Body of the posted review, listing the findings that can't be inline comments
+fn review_body(findings: &[ReviewFinding]) -> String {
+    let mut body = "Automated review by Claude Code.".to_string();
+    for finding in findings {
+        body.push_str(&format!(
+            "\n\n- `{}` {}",
+            finding.location(),
+            finding.comment_body()
+        ));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(severity: ReviewSeverity, line: Option<u64>) -> ReviewFinding {
+        ReviewFinding {
+            file: "src/lib.rs".to_string(),
+            line,
+            severity,
+            suggestion: "Check the result".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_review_target_parse() {
+        assert_eq!(ReviewTarget::parse(""), ReviewTarget::WorkingTree);
+        assert_eq!(ReviewTarget::parse(" 42 "), ReviewTarget::PullRequest(42));
+        assert_eq!(ReviewTarget::parse("#7"), ReviewTarget::PullRequest(7));
+        assert_eq!(
+            ReviewTarget::parse("feature/login"),
+            ReviewTarget::Branch("feature/login".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_findings() {
+        let output = "Here is my review:\n```json\n[\
+            {\"file\":\"a.rs\",\"line\":3,\"severity\":\"nit\",\"suggestion\":\"Rename\"},\
+            {\"file\":\"b.rs\",\"line\":null,\"severity\":\"critical\",\"suggestion\":\"Fix\"}\
+            ]\n```";
+        let findings = parse_findings(output).unwrap();
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].file, "b.rs");
+        assert_eq!(findings[0].line, None);
+        assert_eq!(findings[1].severity, ReviewSeverity::Nit);
+
+        assert_eq!(parse_findings("[]"), Some(Vec::new()));
+        assert_eq!(parse_findings("Looks good to me!"), None);
+        assert_eq!(parse_findings("[not json]"), None);
+    }
+
+    #[test]
+    fn test_truncate_diff() {
+        let short = "+ok";
+        assert_eq!(truncate_diff(short), (short, false));
+
+        let long = "é".repeat(MAX_DIFF_BYTES);
+        let (truncated, was_truncated) = truncate_diff(&long);
+        assert!(was_truncated);
+        assert!(truncated.len() <= MAX_DIFF_BYTES);
+
+        let prompt = build_review_prompt(&ReviewTarget::PullRequest(3), &long);
+        assert!(prompt.contains("run `gh pr diff 3` to see the rest"));
+    }
+
+    #[test]
+    fn test_summarize_findings() {
+        assert_eq!(summarize_findings(&[]), "✅ No issues found.");
+        assert_eq!(
+            summarize_findings(&[finding(ReviewSeverity::Major, Some(1))]),
+            "1 finding: 1 major"
+        );
+        assert_eq!(
+            summarize_findings(&[
+                finding(ReviewSeverity::Critical, Some(1)),
+                finding(ReviewSeverity::Nit, None),
+                finding(ReviewSeverity::Nit, Some(2)),
+            ]),
+            "3 findings: 1 critical, 2 nit"
+        );
+    }
+
+    #[test]
+    fn test_format_finding() {
+        assert_eq!(
+            format_finding(&finding(ReviewSeverity::Minor, Some(12))),
+            "🟡 *Minor* · `src/lib\\.rs:12`\n\nCheck the result"
+        );
+    }
+
+    #[test]
+    fn test_review_body() {
+        assert_eq!(
+            review_body(&[finding(ReviewSeverity::Major, None)]),
+            "Automated review by Claude Code.\n\n- `src/lib.rs` **Major:** Check the result"
+        );
+    }
+}
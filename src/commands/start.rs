@@ -1,4 +1,4 @@
-use crate::github_client::{GithubClient, GithubClientConfig};
+use crate::github_client::{CloneRequest, GithubClient, GithubClientConfig};
 use crate::{escape_markdown_v2, BotState};
 use telegram_bot::claude_code_client::{container_utils, ClaudeCodeClient, ClaudeCodeConfig};
 use teloxide::types::CopyTextButton;
@@ -8,39 +8,6 @@ use teloxide::{
 };
 use url;
 
-/// Parse repository list into structured data
-#[derive(Debug)]
-pub struct Repository {
-    pub full_name: String,
-    pub name: String,
-}
-
-pub fn parse_repository_list(repo_list: &str) -> Vec<Repository> {
-    let lines: Vec<&str> = repo_list.trim().lines().collect();
-    let mut repos = Vec::new();
-
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        // gh repo list output format is typically: "repo_name    description"
-        // Split by whitespace and take the first part as the repo name
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if let Some(full_name) = parts.first() {
-            let name = full_name.split('/').last().unwrap_or(full_name).to_string();
-
-            repos.push(Repository {
-                full_name: full_name.to_string(),
-                name,
-            });
-        }
-    }
-
-    repos
-}
-
 /// Perform the actual GitHub clone operation
 pub async fn perform_github_clone(
     bot: &Bot,
@@ -49,42 +16,91 @@ pub async fn perform_github_clone(
     repository: &str,
     bot_state: &BotState,
 ) -> ResponseResult<()> {
+    let request = match CloneRequest::parse(repository) {
+        Ok(request) => request,
+        Err(e) => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "❌ *Invalid Clone Request*\n\n🔍 Error: {}\n\nUsage: `/clone <owner/repo | git URL> \
+                     [--branch <name>] [--depth <n>]`",
+                    escape_markdown_v2(&e)
+                ),
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let has_parallel_tasks = {
+        let claude_sessions = bot_state.claude_sessions.lock().await;
+        claude_sessions
+            .get(&chat_id.0)
+            .is_some_and(|session| session.has_parallel_tasks())
+    };
+    if has_parallel_tasks {
+        bot.send_message(
+            chat_id,
+            "⚠️ *Parallel tasks in progress*\n\nRemove them with `/task remove <name>` before \
+             cloning another repository\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
     bot.send_message(
         chat_id,
         format!(
             "🔄 *Cloning Repository*\n\n📦 Repository: {}\n⏳ Please wait\\.\\.\\.",
-            escape_markdown_v2(repository)
+            escape_markdown_v2(&request.repository)
         ),
     )
     .parse_mode(ParseMode::MarkdownV2)
     .await?;
 
-    match github_client.repo_clone(repository, None).await {
+    match github_client
+        .repo_clone_with_options(&request.repository, None, &request.options)
+        .await
+    {
         Ok(clone_result) => {
             let message = if clone_result.success {
-                // Update working directory for this session
-                {
+                // Add the repository to the session and switch to it
+                let repository_count = {
                     let mut claude_sessions = bot_state.claude_sessions.lock().await;
-                    claude_sessions
+                    let session = claude_sessions
                         .entry(chat_id.0)
-                        .or_insert_with(crate::bot::claude_session::ClaudeSession::new)
-                        .set_working_directory(
-                            std::path::Path::new(&clone_result.target_directory)
-                                .canonicalize()
-                                .unwrap_or_else(|_| {
-                                    std::path::PathBuf::from(&clone_result.target_directory)
-                                })
-                                .to_string_lossy()
-                                .to_string(),
-                        );
-                }
+                        .or_insert_with(crate::bot::claude_session::ClaudeSession::new);
+                    session.add_repository(
+                        std::path::Path::new(&clone_result.target_directory)
+                            .canonicalize()
+                            .unwrap_or_else(|_| {
+                                std::path::PathBuf::from(&clone_result.target_directory)
+                            })
+                            .to_string_lossy()
+                            .to_string(),
+                    );
+                    session.repositories.len()
+                };
+
+                let switch_hint = if repository_count > 1 {
+                    format!(
+                        "\n\n🔀 This session has {} repositories, use /repo to switch between \
+                         them",
+                        repository_count
+                    )
+                } else {
+                    String::new()
+                };
 
                 format!(
                     "✅ *Repository Cloned Successfully*\n\n📦 Repository: {}\n📁 Location: \
-                     {}\n✨ {}\n\n🎯 *Working directory set for /claude commands*",
+                     {}\n✨ {}\n\n🎯 *Working directory set for /claude commands*{}",
                     escape_markdown_v2(&clone_result.repository),
                     escape_markdown_v2(&clone_result.target_directory),
-                    escape_markdown_v2(&clone_result.message)
+                    escape_markdown_v2(&clone_result.message),
+                    switch_hint
                 )
             } else {
                 format!(
@@ -148,12 +164,27 @@ pub async fn handle_start(
     .parse_mode(ParseMode::MarkdownV2)
     .await?;
 
+    // The user's own GitHub token (from /ghtoken) is injected into their container only; a
+    // group chat's container is shared by every member, so it never gets one
+    let github_token = if msg.chat.is_private() {
+        match bot_state.token_store.get(user_id).await {
+            Ok(token) => token,
+            Err(e) => {
+                log::warn!("Failed to load GitHub token for user {}: {}", user_id, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     match container_utils::start_coding_session(
         &bot_state.docker,
         &container_name,
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(user_id.to_string()),
+            github_token,
         },
     )
     .await
@@ -466,109 +497,8 @@ async fn prompt_for_repository_selection(
                    authenticated\\.\n\n📂 *Choose a Repository*\nSelect a repository to clone \
                    into your coding environment:";
 
-    let github_client = GithubClient::new(
-        bot_state.docker.clone(),
-        claude_client.container_id().to_string(),
-        GithubClientConfig::default(),
-    );
-
-    // Try to show repository selection
-    match github_client.repo_list().await {
-        Ok(repo_list) => {
-            if repo_list.trim().is_empty() {
-                bot.send_message(
-                    chat_id,
-                    "📁 *No Repositories Found*\n\n💡 No repositories found or accessible\\. You \
-                     can:\n\n• Create a new repository on GitHub\n• Get access to existing \
-                     repositories\n• Manually specify a public repository",
-                )
-                .parse_mode(ParseMode::MarkdownV2)
-                .await?;
-            } else {
-                // Parse repositories and create buttons
-                let repos = parse_repository_list(&repo_list);
-                if repos.is_empty() {
-                    bot.send_message(
-                        chat_id,
-                        "📁 *Repository Selection*\n\n💡 No valid repositories found\\.",
-                    )
-                    .parse_mode(ParseMode::MarkdownV2)
-                    .await?;
-                } else {
-                    // Create inline keyboard with repository buttons
-                    let mut keyboard_rows = Vec::new();
-
-                    // Show up to 8 repositories to avoid UI clutter
-                    let display_repos = &repos[..repos.len().min(8)];
-
-                    for repo in display_repos.iter() {
-                        let button = InlineKeyboardButton::callback(
-                            format!("📦 {}", repo.name),
-                            format!("start_clone:{}", repo.full_name),
-                        );
-                        keyboard_rows.push(vec![button]);
-                    }
-
-                    // Add option for manual repository entry
-                    keyboard_rows.push(vec![InlineKeyboardButton::callback(
-                        "✏️ Enter Repository Manually",
-                        "manual_repo_entry",
-                    )]);
-
-                    // Add skip option
-                    keyboard_rows.push(vec![InlineKeyboardButton::callback(
-                        "⏭️ Skip Repository Setup",
-                        "skip_repo_setup",
-                    )]);
-
-                    let keyboard = InlineKeyboardMarkup::new(keyboard_rows);
-
-                    let repo_count_text = if repos.len() > 8 {
-                        format!("\\(showing first 8 of {} repositories\\)", repos.len())
-                    } else {
-                        format!("\\({} repositories\\)", repos.len())
-                    };
-
-                    bot.send_message(
-                        chat_id,
-                        format!(
-                            "{}\n\n🎯 Select a repository to clone {}:",
-                            message, repo_count_text
-                        ),
-                    )
-                    .parse_mode(ParseMode::MarkdownV2)
-                    .reply_markup(keyboard)
-                    .await?;
-                }
-            }
-        }
-        Err(_) => {
-            // Show manual entry option if repo listing fails
-            let keyboard = InlineKeyboardMarkup::new(vec![
-                vec![InlineKeyboardButton::callback(
-                    "✏️ Enter Repository Manually",
-                    "manual_repo_entry",
-                )],
-                vec![InlineKeyboardButton::callback(
-                    "⏭️ Skip Repository Setup",
-                    "skip_repo_setup",
-                )],
-            ]);
-
-            bot.send_message(
-                chat_id,
-                format!(
-                    "{}\n\n⚠️ Could not list repositories\\. You can enter one manually:",
-                    message
-                ),
-            )
-            .parse_mode(ParseMode::MarkdownV2)
-            .reply_markup(keyboard)
-            .await?;
-        }
-    }
-
-    Ok(())
+    super::repo_picker::show_repository_picker(bot, chat_id, bot_state, claude_client, message)
+        .await
 }
 
 /// Handle repository cloning as part of the start workflow
@@ -621,9 +551,10 @@ pub async fn handle_repository_clone_in_start(
 pub async fn handle_manual_repository_entry(bot: Bot, chat_id: ChatId) -> ResponseResult<()> {
     bot.send_message(
         chat_id,
-        "✏️ *Enter Repository*\n\nPlease type the repository you want to clone in the \
-         format:\n`owner/repository`\n\nExamples:\n• `octocat/Hello-World`\n• \
-         `microsoft/vscode`\n• `golang/go`",
+        "✏️ *Enter Repository*\n\nPlease type the repository you want to clone as \
+         `owner/repository` or any HTTPS/SSH git URL, optionally followed by `--branch <name>` \
+         and `--depth <n>`\n\nExamples:\n• `octocat/Hello-World`\n• `golang/go --depth 1`\n• \
+         `https://gitlab.com/group/project.git`\n• `git@git.example.com:team/tool.git`",
     )
     .parse_mode(ParseMode::MarkdownV2)
     .await?;
@@ -650,7 +581,7 @@ mod tests {
     #[test]
     fn test_authentication_guidance_both_unauthenticated() {
         // This test verifies the logic for showing guidance when both services are unauthenticated
-        let auth_steps = vec![
+        let auth_steps = [
             "🐙 *Authenticate with GitHub* to access repositories",
             "🤖 *Authenticate with Claude* to use AI coding features",
         ];
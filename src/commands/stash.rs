@@ -0,0 +1,84 @@
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use teloxide::{prelude::*, types::ParseMode};
+
+use super::checkpoints::{send_error, working_dir_client};
+
+/// Handle the /stash command
+///
+/// Parks all changes, including untracked files, on the git stash so the working tree is
+/// clean for something else, and brings them back with `/stash pop`.
+pub async fn handle_stash(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    args: String,
+) -> ResponseResult<()> {
+    let args = args.trim();
+    let (subcommand, rest) = args.split_once(' ').unwrap_or((args, ""));
+
+    let (command, title) = match subcommand {
+        "pop" => (
+            vec!["git".to_string(), "stash".to_string(), "pop".to_string()],
+            "📤 Stash applied",
+        ),
+        "list" => (
+            vec!["git".to_string(), "stash".to_string(), "list".to_string()],
+            "📚 Stashes",
+        ),
+        _ => {
+            // `/stash push <message>`, or just `/stash <message>`
+            let message = match subcommand {
+                "" | "push" => rest.trim(),
+                _ => args,
+            };
+            let mut command = vec![
+                "git".to_string(),
+                "stash".to_string(),
+                "push".to_string(),
+                "--include-untracked".to_string(),
+            ];
+            if !message.is_empty() {
+                command.push("-m".to_string());
+                command.push(message.to_string());
+            }
+            (command, "📦 Changes stashed")
+        }
+    };
+
+    let Some(client) = working_dir_client(&bot, msg.chat.id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    let output = match client.exec_basic_command(command).await {
+        Ok(output) => output,
+        Err(e) => {
+            let title = match subcommand {
+                "pop" => "Failed to apply the stash",
+                "list" => "Failed to list stashes",
+                _ => "Failed to stash changes",
+            };
+            send_error(&bot, msg.chat.id, title, &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    let message = if output.contains("No local changes to save") {
+        "ℹ️ *Nothing to stash*\n\nThe working directory is clean\\.".to_string()
+    } else if subcommand == "list" && output.is_empty() {
+        "ℹ️ *No stashes*\n\nUse /stash to park your changes\\.".to_string()
+    } else {
+        format!(
+            "*{}*\n```\n{}\n```",
+            escape_markdown_v2(title),
+            escape_markdown_v2(&output)
+        )
+    };
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&message);
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
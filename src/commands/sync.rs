@@ -0,0 +1,651 @@
+use crate::bot::claude_session::{SyncOperation, SyncState};
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+use super::checkpoints::{send_error, working_dir_client};
+
+/// Maximum number of characters of a resolution diff shown for approval
+const DIFF_PREVIEW_LENGTH: usize = 3000;
+
+/// Prints `merge`, `rebase` or `none` depending on the operation in progress
+const OPERATION_SCRIPT: &str = r#"
+git_dir=$(git rev-parse --git-dir)
+if [ -d "$git_dir/rebase-merge" ] || [ -d "$git_dir/rebase-apply" ]; then
+    echo rebase
+elif [ -f "$git_dir/MERGE_HEAD" ]; then
+    echo merge
+else
+    echo none
+fi
+"#;
+
+/// Fetches origin and prints its default branch as `origin/<name>`
+pub const UPSTREAM_SCRIPT: &str = r#"
+git fetch --quiet origin
+upstream=$(git symbolic-ref --quiet --short refs/remotes/origin/HEAD || true)
+if [ -z "$upstream" ]; then
+    git remote set-head origin --auto >/dev/null
+    upstream=$(git symbolic-ref --quiet --short refs/remotes/origin/HEAD)
+fi
+echo "$upstream"
+"#;
+
+/// Succeeds when the file given as `$1` still contains conflict markers
+const MARKERS_SCRIPT: &str = r#"grep -qE '^(<<<<<<<|>>>>>>>)( |$)' -- "$1""#;
+
+/// Handle the /sync command
+///
+/// Merges the remote default branch into the current branch. When that (or a merge or rebase
+/// started by hand) stops on conflicts, the conflicted files are listed so Claude can resolve
+/// them one at a time.
+pub async fn handle_sync(bot: Bot, msg: Message, bot_state: BotState) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let Some(client) = working_dir_client(&bot, chat_id, &bot_state).await? else {
+        return Ok(());
+    };
+
+    // Pick up conflicts from an operation that is already in progress
+    if let Some(operation) = operation_in_progress(&client).await {
+        return show_conflicts(&bot, chat_id, &bot_state, &client, operation).await;
+    }
+
+    match client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "status".to_string(),
+            "--porcelain".to_string(),
+            "--untracked-files=no".to_string(),
+        ])
+        .await
+    {
+        Ok(status) if status.trim().is_empty() => {}
+        Ok(_) => {
+            bot.send_message(
+                chat_id,
+                "⚠️ *Uncommitted Changes*\n\nCommit your changes with /commit before syncing\\.",
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to check git status", &e.to_string()).await?;
+            return Ok(());
+        }
+    }
+
+    let upstream = match run_git_script(&client, UPSTREAM_SCRIPT, vec![]).await {
+        Ok(upstream) if !upstream.trim().is_empty() => upstream.trim().to_string(),
+        Ok(_) => {
+            send_error(
+                &bot,
+                chat_id,
+                "Failed to find the default branch",
+                "origin has no HEAD branch",
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to fetch origin", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "🔄 *Syncing with* `{}`\\.\\.\\.",
+            escape_markdown_v2(&upstream)
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    let merge_result = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "merge".to_string(),
+            "--no-edit".to_string(),
+            upstream.clone(),
+        ])
+        .await;
+
+    if let Some(operation) = operation_in_progress(&client).await {
+        return show_conflicts(&bot, chat_id, &bot_state, &client, operation).await;
+    }
+
+    match merge_result {
+        Ok(output) => {
+            let full_message = format!(
+                "✅ *Synced with* `{}`\n```\n{}\n```",
+                escape_markdown_v2(&upstream),
+                escape_markdown_v2(&output)
+            );
+            let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+            bot.send_message(chat_id, message_to_send)
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+        }
+        Err(e) => {
+            send_error(&bot, chat_id, "Sync failed", &e.to_string()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Let Claude resolve the conflicts in one file, then show the result for approval
+pub async fn handle_sync_resolve(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    index: usize,
+) -> ResponseResult<()> {
+    let Some((operation, file)) = conflicted_file(&bot, chat_id, bot_state, index).await? else {
+        return Ok(());
+    };
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "🤖 *Resolving conflicts in* `{}`\\.\\.\\.",
+            escape_markdown_v2(&file)
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    let conversation_id = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .and_then(|session| session.conversation_id.clone())
+    };
+
+    if let Err(e) = super::execute_claude_command(
+        bot.clone(),
+        chat_id,
+        bot_state.clone(),
+        user_id,
+        &build_resolve_prompt(operation, &file),
+        conversation_id,
+    )
+    .await
+    {
+        send_error(&bot, chat_id, "Claude command failed", &e.to_string()).await?;
+        return Ok(());
+    }
+
+    let diff = run_git_script(&client, r#"git diff -- ":(top)$1""#, vec![file.clone()])
+        .await
+        .unwrap_or_else(|e| format!("(failed to show diff: {})", e));
+    let markers_left = has_conflict_markers(&client, &file).await;
+
+    let mut text = format!(
+        "🔍 *Resolution for* `{}`\n```diff\n{}\n```",
+        escape_markdown_v2(&file),
+        escape_markdown_v2(&preview_diff(&diff))
+    );
+    if markers_left {
+        text.push_str("\n\n⚠️ The file still contains conflict markers\\.");
+    }
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("✅ Accept", format!("sync_accept:{}", index)),
+            InlineKeyboardButton::callback("↩️ Reject", format!("sync_reject:{}", index)),
+        ],
+        vec![InlineKeyboardButton::callback(
+            "🛑 Abort Sync",
+            "sync_abort",
+        )],
+    ]);
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&text);
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Mark a resolved file with `git add` and continue once no conflicts are left
+pub async fn handle_sync_accept(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    index: usize,
+) -> ResponseResult<()> {
+    let Some((operation, file)) = conflicted_file(&bot, chat_id, bot_state, index).await? else {
+        return Ok(());
+    };
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    if has_conflict_markers(&client, &file).await {
+        bot.send_message(
+            chat_id,
+            format!(
+                "⚠️ `{}` still contains conflict markers\\. Resolve it again or reject the \
+                 changes\\.",
+                escape_markdown_v2(&file)
+            ),
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    if let Err(e) = run_git_script(&client, r#"git add -- ":(top)$1""#, vec![file.clone()]).await {
+        send_error(
+            &bot,
+            chat_id,
+            "Failed to stage the resolution",
+            &e.to_string(),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        chat_id,
+        format!("✅ Resolved `{}`", escape_markdown_v2(&file)),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    show_conflicts(&bot, chat_id, bot_state, &client, operation).await
+}
+
+/// Throw away Claude's resolution and restore the conflict markers in a file
+pub async fn handle_sync_reject(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    index: usize,
+) -> ResponseResult<()> {
+    let Some((operation, file)) = conflicted_file(&bot, chat_id, bot_state, index).await? else {
+        return Ok(());
+    };
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    if let Err(e) = run_git_script(
+        &client,
+        r#"git checkout -m -- ":(top)$1""#,
+        vec![file.clone()],
+    )
+    .await
+    {
+        send_error(
+            &bot,
+            chat_id,
+            "Failed to restore the conflicts",
+            &e.to_string(),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "↩️ Restored the conflicts in `{}`",
+            escape_markdown_v2(&file)
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    show_conflicts(&bot, chat_id, bot_state, &client, operation).await
+}
+
+/// Abort the merge or rebase and go back to the state before /sync
+pub async fn handle_sync_abort(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+) -> ResponseResult<()> {
+    let Some(client) = working_dir_client(&bot, chat_id, bot_state).await? else {
+        return Ok(());
+    };
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.sync = None;
+        }
+    }
+
+    let Some(operation) = operation_in_progress(&client).await else {
+        bot.send_message(chat_id, "ℹ️ No merge or rebase in progress\\.")
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        return Ok(());
+    };
+
+    match client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            operation_command(operation).to_string(),
+            "--abort".to_string(),
+        ])
+        .await
+    {
+        Ok(_) => {
+            bot.send_message(
+                chat_id,
+                "🛑 *Sync Aborted*\n\nThe branch is back to where it was before the sync\\.",
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        }
+        Err(e) => {
+            send_error(&bot, chat_id, "Failed to abort", &e.to_string()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The merge or rebase currently in progress in the client's repository, if any
+pub async fn operation_in_progress(client: &ClaudeCodeClient) -> Option<SyncOperation> {
+    let output = client
+        .exec_basic_command(vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            OPERATION_SCRIPT.to_string(),
+        ])
+        .await
+        .ok()?;
+
+    parse_operation(&output)
+}
+
+/// List the remaining conflicts, or continue the operation when there are none
+async fn show_conflicts(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    client: &ClaudeCodeClient,
+    operation: SyncOperation,
+) -> ResponseResult<()> {
+    let conflicts = match conflicted_files(client).await {
+        Ok(conflicts) => conflicts,
+        Err(e) => {
+            send_error(bot, chat_id, "Failed to list conflicts", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    if conflicts.is_empty() {
+        return continue_operation(bot, chat_id, bot_state, client, operation).await;
+    }
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = conflicts
+        .iter()
+        .enumerate()
+        .map(|(index, file)| {
+            vec![InlineKeyboardButton::callback(
+                format!("🤖 Resolve {}", file),
+                format!("sync_resolve:{}", index),
+            )]
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback(
+        "🛑 Abort Sync",
+        "sync_abort",
+    )]);
+
+    let text = format_conflict_list(operation, &conflicts);
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.sync = Some(SyncState {
+                operation,
+                conflicts,
+            });
+        }
+    }
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&text);
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(InlineKeyboardMarkup::new(rows))
+        .await?;
+
+    Ok(())
+}
+
+/// Conclude the merge, or move the rebase on to its next commit
+async fn continue_operation(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    client: &ClaudeCodeClient,
+    operation: SyncOperation,
+) -> ResponseResult<()> {
+    let command = match operation {
+        SyncOperation::Merge => vec!["git", "commit", "--no-edit"],
+        SyncOperation::Rebase => vec!["git", "-c", "core.editor=true", "rebase", "--continue"],
+    };
+    let result = client
+        .exec_basic_command(command.into_iter().map(str::to_string).collect())
+        .await;
+
+    // A rebase stops again when one of the following commits conflicts too
+    if let Some(operation) = operation_in_progress(client).await {
+        if matches!(conflicted_files(client).await, Ok(conflicts) if !conflicts.is_empty()) {
+            return Box::pin(show_conflicts(bot, chat_id, bot_state, client, operation)).await;
+        }
+    }
+
+    if let Err(e) = result {
+        send_error(bot, chat_id, "Failed to continue", &e.to_string()).await?;
+        return Ok(());
+    }
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.sync = None;
+        }
+    }
+
+    let head = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "log".to_string(),
+            "--oneline".to_string(),
+            "-1".to_string(),
+        ])
+        .await
+        .unwrap_or_default();
+
+    let full_message = format!(
+        "🎉 *Sync Complete*\n\nAll conflicts are resolved\\.\n```\n{}\n```",
+        escape_markdown_v2(&head)
+    );
+    let (message_to_send, _was_truncated) = truncate_if_needed(&full_message);
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Look up a file from the conflict list shown to the user
+async fn conflicted_file(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    index: usize,
+) -> ResponseResult<Option<(SyncOperation, String)>> {
+    let file = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .and_then(|session| session.sync.as_ref())
+            .and_then(|sync| Some((sync.operation, sync.conflicts.get(index)?.clone())))
+    };
+
+    if file.is_none() {
+        bot.send_message(
+            chat_id,
+            "ℹ️ No conflicts in progress\\. Use /sync to update from the default branch\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+    }
+
+    Ok(file)
+}
+
+async fn conflicted_files(
+    client: &ClaudeCodeClient,
+) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let output = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "diff".to_string(),
+            "--name-only".to_string(),
+            "--diff-filter=U".to_string(),
+        ])
+        .await?;
+
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+async fn has_conflict_markers(client: &ClaudeCodeClient, file: &str) -> bool {
+    run_git_script(client, MARKERS_SCRIPT, vec![file.to_string()])
+        .await
+        .is_ok()
+}
+
+/// Run a script from the repository root, since conflicted paths are relative to it
+pub async fn run_git_script(
+    client: &ClaudeCodeClient,
+    script: &str,
+    args: Vec<String>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let mut command = vec![
+        "bash".to_string(),
+        "-c".to_string(),
+        format!(
+            "set -e\ncd \"$(git rev-parse --show-toplevel)\"\n{}",
+            script
+        ),
+        "sync".to_string(),
+    ];
+    command.extend(args);
+
+    client.exec_basic_command(command).await
+}
+
+fn parse_operation(output: &str) -> Option<SyncOperation> {
+    match output.trim() {
+        "merge" => Some(SyncOperation::Merge),
+        "rebase" => Some(SyncOperation::Rebase),
+        _ => None,
+    }
+}
+
+fn operation_command(operation: SyncOperation) -> &'static str {
+    match operation {
+        SyncOperation::Merge => "merge",
+        SyncOperation::Rebase => "rebase",
+    }
+}
+
+fn format_conflict_list(operation: SyncOperation, conflicts: &[String]) -> String {
+    let mut text = format!(
+        "⚔️ *{} Conflicts* \\({} file{}\\)\n",
+        match operation {
+            SyncOperation::Merge => "Merge",
+            SyncOperation::Rebase => "Rebase",
+        },
+        conflicts.len(),
+        if conflicts.len() == 1 { "" } else { "s" }
+    );
+
+    for file in conflicts {
+        text.push_str(&format!("\n• `{}`", escape_markdown_v2(file)));
+    }
+
+    text.push_str(
+        "\n\nTap a file to let Claude resolve it\\. You'll see the resolution before it is \
+         staged\\.",
+    );
+    text
+}
+
+fn build_resolve_prompt(operation: SyncOperation, file: &str) -> String {
+    format!(
+        "The file `{}` (relative to the repository root) has conflicts from a git {}. Resolve \
+         the conflict markers in this file only, keeping the intent of both sides. Do not edit \
+         other files and do not run git add, commit, merge or rebase commands.",
+        file,
+        operation_command(operation)
+    )
+}
+
+fn preview_diff(diff: &str) -> String {
+    if diff.chars().count() <= DIFF_PREVIEW_LENGTH {
+        diff.to_string()
+    } else {
+        let preview: String = diff.chars().take(DIFF_PREVIEW_LENGTH).collect();
+        format!("{}\n… (diff truncated)", preview)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_operation() {
+        assert_eq!(parse_operation("merge\n"), Some(SyncOperation::Merge));
+        assert_eq!(parse_operation("rebase"), Some(SyncOperation::Rebase));
+        assert_eq!(parse_operation("none"), None);
+    }
+
+    #[test]
+    fn test_format_conflict_list() {
+        let text = format_conflict_list(
+            SyncOperation::Merge,
+            &["src/main.rs".to_string(), "Cargo.toml".to_string()],
+        );
+
+        assert!(text.starts_with("⚔️ *Merge Conflicts* \\(2 files\\)"));
+        assert!(text.contains("• `src/main\\.rs`"));
+        assert!(text.contains("• `Cargo\\.toml`"));
+    }
+
+    #[test]
+    fn test_build_resolve_prompt() {
+        let prompt = build_resolve_prompt(SyncOperation::Rebase, "src/lib.rs");
+
+        assert!(prompt.contains("`src/lib.rs`"));
+        assert!(prompt.contains("git rebase"));
+        assert!(prompt.contains("this file only"));
+    }
+}
@@ -0,0 +1,447 @@
+use crate::bot::claude_session::{ClaudeSession, TaskState, MAIN_TASK_NAME};
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use telegram_bot::claude_code_client::ClaudeCodeClient;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+};
+
+use super::checkpoints::{directory_client, send_error};
+
+/// Maximum length of a task name (it becomes part of a branch and directory name)
+const MAX_TASK_NAME_LENGTH: usize = 32;
+
+/// Handle the /task command
+///
+/// Tasks are git worktrees of the current repository, each with its own branch and Claude
+/// conversation, so several features can progress in one container.
+pub async fn handle_task(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    args: String,
+) -> ResponseResult<()> {
+    let args = args.trim();
+    let (subcommand, name) = args.split_once(' ').unwrap_or((args, ""));
+    let name = name.trim();
+
+    match (subcommand, name) {
+        ("new", name) if !name.is_empty() => {
+            handle_task_new(bot, msg.chat.id, &bot_state, name).await
+        }
+        ("switch", "") => send_task_list(bot, msg.chat.id, &bot_state, "🔀 *Switch Task*").await,
+        ("switch", name) => handle_task_switch(bot, msg.chat.id, &bot_state, name).await,
+        ("" | "list", "") => send_task_list(bot, msg.chat.id, &bot_state, "🗂️ *Tasks*").await,
+        ("remove", name) if !name.is_empty() => {
+            handle_task_remove(bot, msg.chat.id, &bot_state, name).await
+        }
+        _ => {
+            log::debug!("Invalid /task arguments '{}' for chat {}", args, chat_id);
+            bot.send_message(
+                msg.chat.id,
+                "🗂️ *Parallel Tasks*\n\nUsage:\n• `/task new <name>` \\- Create a worktree with \
+                 its own branch and Claude conversation\n• `/task switch [name]` \\- Move to \
+                 another task\n• `/task list` \\- Show tasks with branch, dirty state and last \
+                 run cost\n• `/task remove <name>` \\- Delete a task's worktree",
+            )
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+/// Create a worktree for a new task and switch to it
+async fn handle_task_new(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    name: &str,
+) -> ResponseResult<()> {
+    if let Err(e) = validate_task_name(name) {
+        send_error(&bot, chat_id, "Invalid task name", &e).await?;
+        return Ok(());
+    }
+
+    // New worktrees always branch off the main checkout, even when started from another task
+    let (main_directory, exists) = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        match sessions.get(&chat_id.0) {
+            Some(session) => (
+                session
+                    .task(MAIN_TASK_NAME)
+                    .and_then(|t| t.working_directory.clone())
+                    .or_else(|| session.working_directory.clone()),
+                session.task(name).is_some(),
+            ),
+            None => (None, false),
+        }
+    };
+
+    if exists || name == MAIN_TASK_NAME {
+        send_error(
+            &bot,
+            chat_id,
+            "Task already exists",
+            &format!("Use /task switch {} instead.", name),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Some(main_directory) = main_directory else {
+        bot.send_message(
+            chat_id,
+            "📂 *No repository selected*\n\nClone a repository first, then try again\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    };
+
+    let Some(client) = directory_client(&bot, chat_id, bot_state, Some(main_directory)).await?
+    else {
+        return Ok(());
+    };
+
+    let repository_root = match client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "rev-parse".to_string(),
+            "--show-toplevel".to_string(),
+        ])
+        .await
+    {
+        Ok(root) => root.trim().to_string(),
+        Err(e) => {
+            send_error(&bot, chat_id, "Not a git repository", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+
+    let worktree_path = worktree_path(&repository_root, name);
+    let branch = format!("task/{}", name);
+
+    if let Err(e) = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "worktree".to_string(),
+            "add".to_string(),
+            "-b".to_string(),
+            branch.clone(),
+            worktree_path.clone(),
+        ])
+        .await
+    {
+        send_error(&bot, chat_id, "Failed to create worktree", &e.to_string()).await?;
+        return Ok(());
+    }
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        let session = sessions.entry(chat_id.0).or_insert_with(ClaudeSession::new);
+        if session.tasks.is_empty() {
+            // Record the repository root rather than whatever subdirectory was selected
+            session.set_working_directory(repository_root.clone());
+        }
+        session.add_task(TaskState::new(name.to_string(), worktree_path.clone()));
+        session.is_active = true;
+    }
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "✅ *Task* `{}` *created*\n\n🌿 Branch: `{}`\n📁 Worktree: `{}`\n\n💬 Messages now go \
+             to a fresh Claude conversation in this worktree\\. Use `/task switch` to move between \
+             tasks\\.",
+            escape_markdown_v2(name),
+            escape_markdown_v2(&branch),
+            escape_markdown_v2(&worktree_path)
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    Ok(())
+}
+
+/// Switch the chat to another task
+pub async fn handle_task_switch(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    name: &str,
+) -> ResponseResult<()> {
+    let switched = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get_mut(&chat_id.0)
+            .filter(|session| session.task(name).is_some())
+            .map(|session| {
+                session.switch_task(name);
+                session.is_active = true;
+                session.get_working_directory().cloned().unwrap_or_default()
+            })
+    };
+
+    let message = match switched {
+        Some(directory) => format!(
+            "🔀 *Switched to task* `{}`\n\n📁 `{}`\n\n💬 Messages now continue this task's Claude \
+             conversation\\.",
+            escape_markdown_v2(name),
+            escape_markdown_v2(&directory)
+        ),
+        None => format!(
+            "❓ *Unknown task* `{}`\n\nUse `/task list` to see available tasks\\.",
+            escape_markdown_v2(name)
+        ),
+    };
+
+    bot.send_message(chat_id, message)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Remove a task's worktree (its branch is kept)
+async fn handle_task_remove(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    name: &str,
+) -> ResponseResult<()> {
+    let (task, main_directory) = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        match sessions.get(&chat_id.0) {
+            Some(session) => (
+                session.task(name).cloned(),
+                session
+                    .task(MAIN_TASK_NAME)
+                    .and_then(|t| t.working_directory.clone()),
+            ),
+            None => (None, None),
+        }
+    };
+
+    let Some(task) = task.filter(|t| t.name != MAIN_TASK_NAME) else {
+        send_error(
+            &bot,
+            chat_id,
+            "Cannot remove task",
+            &format!("'{}' is not a removable task.", name),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let is_active = {
+        let sessions = bot_state.claude_sessions.lock().await;
+        sessions
+            .get(&chat_id.0)
+            .is_some_and(|s| s.active_task.as_deref() == Some(name))
+    };
+    if is_active {
+        send_error(
+            &bot,
+            chat_id,
+            "Cannot remove the active task",
+            "Switch to another task first.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Some(client) = directory_client(&bot, chat_id, bot_state, main_directory).await? else {
+        return Ok(());
+    };
+
+    // Without --force git refuses to remove worktrees with uncommitted changes
+    if let Err(e) = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "worktree".to_string(),
+            "remove".to_string(),
+            task.working_directory.clone().unwrap_or_default(),
+        ])
+        .await
+    {
+        send_error(&bot, chat_id, "Failed to remove worktree", &e.to_string()).await?;
+        return Ok(());
+    }
+
+    {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&chat_id.0) {
+            session.remove_task(name);
+        }
+    }
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "🗑️ *Task* `{}` *removed*\n\nIts branch `task/{}` was kept\\.",
+            escape_markdown_v2(name),
+            escape_markdown_v2(name)
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    Ok(())
+}
+
+/// List tasks with branch, dirty state and last run cost, plus switch buttons
+async fn send_task_list(
+    bot: Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    title: &str,
+) -> ResponseResult<()> {
+    let (tasks, active_task) = {
+        let mut sessions = bot_state.claude_sessions.lock().await;
+        match sessions.get_mut(&chat_id.0) {
+            Some(session) => {
+                session.save_active_task();
+                (session.tasks.clone(), session.active_task.clone())
+            }
+            None => (Vec::new(), None),
+        }
+    };
+
+    if tasks.is_empty() {
+        bot.send_message(
+            chat_id,
+            "🗂️ *No Tasks Yet*\n\nCreate one with `/task new <name>`\\.",
+        )
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+        return Ok(());
+    }
+
+    let mut text = format!("{}\n", title);
+    let mut rows = Vec::new();
+    for task in &tasks {
+        let is_active = active_task.as_deref() == Some(task.name.as_str());
+        let (branch, dirty) =
+            match directory_client(&bot, chat_id, bot_state, task.working_directory.clone()).await?
+            {
+                Some(client) => task_git_status(&client).await,
+                None => return Ok(()),
+            };
+
+        text.push_str(&format!(
+            "\n{} *{}* · 🌿 `{}` · {}{}",
+            if is_active { "▶️" } else { "▫️" },
+            escape_markdown_v2(&task.name),
+            escape_markdown_v2(&branch),
+            if dirty { "✏️ dirty" } else { "✅ clean" },
+            match task.last_run_cost {
+                Some(cost) => format!(" · 💰 {}", escape_markdown_v2(&format!("${:.4}", cost))),
+                None => String::new(),
+            }
+        ));
+
+        if !is_active {
+            rows.push(vec![InlineKeyboardButton::callback(
+                format!("🔀 {}", task.name),
+                format!("task_switch:{}", task.name),
+            )]);
+        }
+    }
+
+    let (message_to_send, _was_truncated) = truncate_if_needed(&text);
+    bot.send_message(chat_id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .reply_markup(InlineKeyboardMarkup::new(rows))
+        .await?;
+
+    Ok(())
+}
+
+/// Current branch and whether the worktree has uncommitted changes
+async fn task_git_status(client: &ClaudeCodeClient) -> (String, bool) {
+    let branch = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "rev-parse".to_string(),
+            "--abbrev-ref".to_string(),
+            "HEAD".to_string(),
+        ])
+        .await
+        .map(|b| b.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    let dirty = client
+        .exec_basic_command(vec![
+            "git".to_string(),
+            "status".to_string(),
+            "--porcelain".to_string(),
+        ])
+        .await
+        .map(|status| !status.trim().is_empty())
+        .unwrap_or(false);
+
+    (branch, dirty)
+}
+
+/// Task names end up in branch and directory names, so keep them simple
+fn validate_task_name(name: &str) -> Result<(), String> {
+    if name.len() > MAX_TASK_NAME_LENGTH {
+        return Err(format!(
+            "Task names can be at most {} characters.",
+            MAX_TASK_NAME_LENGTH
+        ));
+    }
+    if name.starts_with('-')
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(
+            "Use letters, digits, '-' and '_' only (and don't start with '-').".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Worktrees live next to the repository: `/workspace/app` -> `/workspace/app-<task>`
+fn worktree_path(repository_root: &str, name: &str) -> String {
+    let root = std::path::Path::new(repository_root.trim_end_matches('/'));
+    let repository_name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+    root.with_file_name(format!("{}-{}", repository_name, name))
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_task_name() {
+        assert!(validate_task_name("login-page").is_ok());
+        assert!(validate_task_name("fix_42").is_ok());
+        assert!(validate_task_name("-rf").is_err());
+        assert!(validate_task_name("../escape").is_err());
+        assert!(validate_task_name("with space").is_err());
+        assert!(validate_task_name(&"x".repeat(MAX_TASK_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_worktree_path() {
+        assert_eq!(
+            worktree_path("/workspace/app", "login"),
+            "/workspace/app-login"
+        );
+        assert_eq!(
+            worktree_path("/workspace/app/", "login"),
+            "/workspace/app-login"
+        );
+    }
+}
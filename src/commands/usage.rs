@@ -0,0 +1,355 @@
+use crate::bot::cost_ledger::{
+    spend, BudgetPeriod, BudgetScope, BudgetStatus, LedgerEntry, BUDGET_WARNING_RATIO,
+};
+use crate::bot::markdown::{escape_markdown_v2, truncate_if_needed};
+use crate::BotState;
+use chrono::{NaiveDate, Utc};
+use std::collections::BTreeMap;
+use telegram_bot::claude_code_client::{ClaudeCodeClient, ClaudeCodeResult};
+use teloxide::{prelude::*, types::ParseMode};
+
+use super::repo::repository_name;
+
+/// Handle the /usage command
+///
+/// Shows what you and this chat spent on Claude today and this month, against the configured
+/// budgets, with your spend broken down by day and repository.
+pub async fn handle_usage(
+    bot: Bot,
+    msg: Message,
+    bot_state: BotState,
+    chat_id: i64,
+    user_id: i64,
+) -> ResponseResult<()> {
+    let today = Utc::now().date_naive();
+    let entries = bot_state
+        .cost_ledger
+        .month_entries(today, user_id, chat_id)
+        .await;
+    let statuses = bot_state
+        .cost_ledger
+        .budget_statuses(today, user_id, chat_id)
+        .await;
+
+    let message = format_usage(&entries, &statuses, today, user_id, chat_id);
+    let (message_to_send, _was_truncated) = truncate_if_needed(&message);
+    bot.send_message(msg.chat.id, message_to_send)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(())
+}
+
+/// Check the user's and chat's budgets before a Claude run
+///
+/// Returns false, after explaining why, when a budget is used up.
+pub async fn check_budget(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+) -> ResponseResult<bool> {
+    let statuses = bot_state
+        .cost_ledger
+        .budget_statuses(Utc::now().date_naive(), user_id, chat_id.0)
+        .await;
+
+    let Some(exceeded) = statuses.iter().find(|status| status.is_exceeded()) else {
+        return Ok(true);
+    };
+
+    log::info!(
+        "Blocking Claude run for user {} in chat {}: {} is used up",
+        user_id,
+        chat_id.0,
+        exceeded.name()
+    );
+    bot.send_message(chat_id, format_blocked(exceeded))
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    Ok(false)
+}
+
+/// Add a run's cost to the ledger, warning when it pushes a budget past 80% or uses it up
+pub async fn record_run_cost(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    repository: Option<String>,
+    cost: f64,
+) -> ResponseResult<()> {
+    let ledger = &bot_state.cost_ledger;
+    let today = Utc::now().date_naive();
+
+    let before = ledger.budget_statuses(today, user_id, chat_id.0).await;
+    if let Err(e) = ledger
+        .record(today, user_id, chat_id.0, repository, cost)
+        .await
+    {
+        log::warn!("Failed to save the cost ledger: {}", e);
+    }
+    let after = ledger.budget_statuses(today, user_id, chat_id.0).await;
+
+    for notice in budget_notices(&before, &after) {
+        bot.send_message(chat_id, notice)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Run a one-off `claude --print` for a bot feature, within the user's and chat's budgets
+///
+/// `args` go after `claude --print --output-format json`. Returns None, after explaining why,
+/// when a budget is used up; otherwise the run's cost is recorded like a prompt's.
+pub async fn run_claude_print(
+    bot: &Bot,
+    chat_id: ChatId,
+    bot_state: &BotState,
+    user_id: i64,
+    client: &ClaudeCodeClient,
+    args: Vec<String>,
+) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    if !check_budget(bot, chat_id, bot_state, user_id).await? {
+        return Ok(None);
+    }
+
+    let mut command = vec![
+        "claude".to_string(),
+        "--print".to_string(),
+        "--output-format".to_string(),
+        "json".to_string(),
+    ];
+    command.extend(args);
+    let output = client.exec_basic_command(command).await?;
+
+    let result: ClaudeCodeResult = serde_json::from_str(&output)?;
+    if result.total_cost_usd > 0.0 {
+        let repository = {
+            let sessions = bot_state.claude_sessions.lock().await;
+            sessions
+                .get(&chat_id.0)
+                .and_then(|session| session.get_working_directory().cloned())
+        };
+        record_run_cost(
+            bot,
+            chat_id,
+            bot_state,
+            user_id,
+            repository,
+            result.total_cost_usd,
+        )
+        .await?;
+    }
+
+    if result.is_error {
+        return Err(result.result.into());
+    }
+    Ok(Some(result.result))
+}
+
+fn format_dollars(amount: f64) -> String {
+    escape_markdown_v2(&format!("${:.2}", amount))
+}
+
+fn blocked_until(period: BudgetPeriod) -> &'static str {
+    match period {
+        BudgetPeriod::Day => "tomorrow \\(UTC\\)",
+        BudgetPeriod::Month => "next month",
+    }
+}
+
+fn format_blocked(status: &BudgetStatus) -> String {
+    format!(
+        "🚫 *Budget used up*\n\nYou've used {} of {} of {}, so new prompts are blocked until \
+         {}\\. Use /usage for details\\.",
+        format_dollars(status.spent),
+        escape_markdown_v2(&status.name()),
+        format_dollars(status.limit),
+        blocked_until(status.period)
+    )
+}
+
+/// Messages for the budgets a run pushed past the warning threshold or used up
+fn budget_notices(before: &[BudgetStatus], after: &[BudgetStatus]) -> Vec<String> {
+    before
+        .iter()
+        .zip(after)
+        .filter_map(|(before, after)| {
+            if after.is_exceeded() && !before.is_exceeded() {
+                Some(format_blocked(after))
+            } else if after.ratio() >= BUDGET_WARNING_RATIO
+                && before.ratio() < BUDGET_WARNING_RATIO
+                && !after.is_exceeded()
+            {
+                Some(format!(
+                    "⚠️ *{:.0}% of {} used*\n\n{} of {} spent\\.",
+                    after.ratio() * 100.0,
+                    escape_markdown_v2(&after.name()),
+                    format_dollars(after.spent),
+                    format_dollars(after.limit)
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn format_usage(
+    entries: &[LedgerEntry],
+    statuses: &[BudgetStatus],
+    today: NaiveDate,
+    user_id: i64,
+    chat_id: i64,
+) -> String {
+    let (user_day, user_month) = spend(entries, BudgetScope::User, user_id, today);
+    let (chat_day, chat_month) = spend(entries, BudgetScope::Chat, chat_id, today);
+
+    let mut message = format!(
+        "💰 *Usage*\n\n👤 *You:* {} today, {} this month\n💬 *This chat:* {} today, {} this \
+         month\n",
+        format_dollars(user_day),
+        format_dollars(user_month),
+        format_dollars(chat_day),
+        format_dollars(chat_month)
+    );
+
+    if statuses.is_empty() {
+        message.push_str("\nNo budgets are configured\\.\n");
+    } else {
+        message.push_str("\n*Budgets*\n");
+        for status in statuses {
+            message.push_str(&format!(
+                "{} {}: {} of {} \\({:.0}%\\)\n",
+                if status.is_exceeded() { "🚫" } else { "•" },
+                escape_markdown_v2(&status.name()),
+                format_dollars(status.spent),
+                format_dollars(status.limit),
+                status.ratio() * 100.0
+            ));
+        }
+    }
+
+    let own: Vec<&LedgerEntry> = entries
+        .iter()
+        .filter(|entry| entry.user_id == user_id)
+        .collect();
+    if own.is_empty() {
+        return message;
+    }
+
+    let mut by_day: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    let mut by_repository: BTreeMap<String, (f64, u32)> = BTreeMap::new();
+    for entry in &own {
+        *by_day.entry(entry.date).or_default() += entry.cost;
+
+        let repository = entry
+            .repository
+            .as_deref()
+            .map(repository_name)
+            .unwrap_or("no repository")
+            .to_string();
+        let total = by_repository.entry(repository).or_default();
+        total.0 += entry.cost;
+        total.1 += entry.runs;
+    }
+
+    message.push_str("\n*Your spend by day*\n");
+    for (date, cost) in by_day.iter().rev() {
+        message.push_str(&format!(
+            "`{}` {}\n",
+            escape_markdown_v2(&date.to_string()),
+            format_dollars(*cost)
+        ));
+    }
+
+    let mut repositories: Vec<_> = by_repository.into_iter().collect();
+    repositories.sort_by(|a, b| b.1 .0.total_cmp(&a.1 .0));
+    message.push_str("\n*Your spend by repository*\n");
+    for (repository, (cost, runs)) in repositories {
+        message.push_str(&format!(
+            "`{}` {} \\({} {}\\)\n",
+            escape_markdown_v2(&repository),
+            format_dollars(cost),
+            runs,
+            if runs == 1 { "run" } else { "runs" }
+        ));
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(spent: f64) -> BudgetStatus {
+        BudgetStatus {
+            scope: BudgetScope::User,
+            period: BudgetPeriod::Day,
+            spent,
+            limit: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_budget_notices() {
+        assert!(budget_notices(&[status(1.0)], &[status(2.0)]).is_empty());
+        assert_eq!(
+            budget_notices(&[status(7.0)], &[status(8.5)]),
+            vec!["⚠️ *85% of your daily budget used*\n\n$8\\.50 of $10\\.00 spent\\."]
+        );
+        // Already warned
+        assert!(budget_notices(&[status(8.5)], &[status(9.0)]).is_empty());
+        assert_eq!(
+            budget_notices(&[status(9.0)], &[status(10.5)]),
+            vec![format_blocked(&status(10.5))]
+        );
+    }
+
+    #[test]
+    fn test_format_blocked() {
+        assert_eq!(
+            format_blocked(&status(10.5)),
+            "🚫 *Budget used up*\n\nYou've used $10\\.50 of your daily budget of $10\\.00, so \
+             new prompts are blocked until tomorrow \\(UTC\\)\\. Use /usage for details\\."
+        );
+    }
+
+    #[test]
+    fn test_format_usage() {
+        let day = |day| NaiveDate::from_ymd_opt(2024, 5, day).unwrap();
+        let entry = |date, user_id, repository: Option<&str>, cost| LedgerEntry {
+            date,
+            user_id,
+            chat_id: 100,
+            repository: repository.map(str::to_string),
+            cost,
+            runs: 1,
+        };
+        let entries = vec![
+            entry(day(1), 7, Some("/workspace/api"), 1.0),
+            entry(day(2), 7, Some("/workspace/api"), 0.5),
+            entry(day(2), 7, None, 2.0),
+            entry(day(2), 8, None, 4.0),
+        ];
+        let statuses = vec![BudgetStatus {
+            spent: 2.5,
+            limit: 5.0,
+            ..status(0.0)
+        }];
+
+        assert_eq!(
+            format_usage(&entries, &statuses, day(2), 7, 100),
+            "💰 *Usage*\n\n👤 *You:* $2\\.50 today, $3\\.50 this month\n💬 *This chat:* \
+             $6\\.50 today, $7\\.50 this month\n\n*Budgets*\n• your daily budget: $2\\.50 of \
+             $5\\.00 \\(50%\\)\n\n*Your spend by day*\n`2024\\-05\\-02` $2\\.50\n`2024\\-05\\-01` \
+             $1\\.00\n\n*Your spend by repository*\n`no repository` $2\\.00 \\(1 run\\)\n`api` \
+             $1\\.50 \\(2 runs\\)\n"
+        );
+    }
+}
@@ -340,18 +340,42 @@ impl GitHubAuth {
     /// Check authentication status
     pub async fn check_auth_status(
         &self,
+    ) -> Result<GithubAuthResult, Box<dyn std::error::Error + Send + Sync>> {
+        self.auth_status(None).await
+    }
+
+    /// Check whether a personal access token authenticates with GitHub
+    ///
+    /// The token is passed through the exec environment only, so it is not stored in the
+    /// container.
+    pub async fn check_token(
+        &self,
+        token: &str,
+    ) -> Result<GithubAuthResult, Box<dyn std::error::Error + Send + Sync>> {
+        self.auth_status(Some(token)).await
+    }
+
+    /// Run `gh auth status`, optionally with an explicit token
+    async fn auth_status(
+        &self,
+        token: Option<&str>,
     ) -> Result<GithubAuthResult, Box<dyn std::error::Error + Send + Sync>> {
         let auth_command = vec!["gh".to_string(), "auth".to_string(), "status".to_string()];
 
+        let mut env = vec![
+            "PATH=/usr/local/bin:/usr/bin:/bin:/usr/local/sbin:/usr/sbin:/sbin".to_string(),
+            "HOME=/root".to_string(),
+        ];
+        if let Some(token) = token {
+            env.push(format!("GH_TOKEN={}", token));
+        }
+
         let exec_config = CreateExecOptions {
             cmd: Some(auth_command),
             attach_stdout: Some(true),
             attach_stderr: Some(true),
             working_dir: self.config.working_directory.clone(),
-            env: Some(vec![
-                "PATH=/usr/local/bin:/usr/bin:/bin:/usr/local/sbin:/usr/sbin:/sbin".to_string(),
-                "HOME=/root".to_string(),
-            ]),
+            env: Some(env),
             ..Default::default()
         };
 
@@ -423,6 +447,12 @@ impl GitHubAuth {
                     return Some(username.to_string());
                 }
             }
+            // Newer gh versions print "Logged in to github.com account NAME (source)"
+            if let Some((_, rest)) = line.split_once("Logged in to github.com account ") {
+                if let Some(username) = rest.split_whitespace().next() {
+                    return Some(username.to_string());
+                }
+            }
         }
         None
     }
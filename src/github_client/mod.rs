@@ -4,7 +4,11 @@ pub mod types;
 
 pub use auth::{GitHubAuth, OAuthProcess};
 pub use operations::GitHubOperations;
-pub use types::{GithubAuthResult, GithubClientConfig, GithubCloneResult};
+pub use types::{
+    group_review_threads, CloneOptions, CloneRequest, GithubAuthResult, GithubClientConfig,
+    GithubCloneResult, PrCheck, PullRequestReviewComment, RepositoryInfo, ReviewFinding,
+    ReviewSeverity, ReviewThread, WorkflowRun,
+};
 
 use bollard::Docker;
 
@@ -44,6 +48,14 @@ impl GithubClient {
         self.auth.check_auth_status().await
     }
 
+    /// Check whether a personal access token authenticates with GitHub
+    pub async fn check_token(
+        &self,
+        token: &str,
+    ) -> Result<GithubAuthResult, Box<dyn std::error::Error + Send + Sync>> {
+        self.auth.check_token(token).await
+    }
+
     /// Logout from GitHub by removing stored credentials
     pub async fn logout(
         &self,
@@ -60,13 +72,16 @@ impl GithubClient {
         self.auth.wait_for_oauth_completion(oauth_process).await
     }
 
-    /// Clone a repository using gh client
-    pub async fn repo_clone(
+    /// Clone a repository (GitHub shorthand or any git URL) with branch/depth options
+    pub async fn repo_clone_with_options(
         &self,
         repository: &str,
         target_dir: Option<&str>,
+        options: &CloneOptions,
     ) -> Result<GithubCloneResult, Box<dyn std::error::Error + Send + Sync>> {
-        self.operations.repo_clone(repository, target_dir).await
+        self.operations
+            .repo_clone_with_options(repository, target_dir, options)
+            .await
     }
 
     /// Check if gh client is available
@@ -77,9 +92,114 @@ impl GithubClient {
         self.operations.check_availability().await
     }
 
-    /// List GitHub repositories for the authenticated user
-    pub async fn repo_list(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        self.operations.repo_list().await
+    /// List repositories of the authenticated user and their organizations with metadata
+    pub async fn repo_list_detailed(
+        &self,
+    ) -> Result<Vec<RepositoryInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.repo_list_detailed().await
+    }
+
+    /// Fetch the profile of the authenticated GitHub user
+    pub async fn current_user(
+        &self,
+    ) -> Result<types::GithubUserProfile, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.current_user().await
+    }
+
+    /// Check out a pull request branch in the working directory
+    pub async fn pr_checkout(
+        &self,
+        pr_number: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.pr_checkout(pr_number).await
+    }
+
+    /// Fetch the review comments of a pull request
+    pub async fn pr_review_comments(
+        &self,
+        pr_number: u64,
+    ) -> Result<Vec<PullRequestReviewComment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.pr_review_comments(pr_number).await
+    }
+
+    /// Reply to a pull request review comment thread
+    pub async fn pr_reply_to_comment(
+        &self,
+        pr_number: u64,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.operations
+            .pr_reply_to_comment(pr_number, comment_id, body)
+            .await
+    }
+
+    /// Fetch the diff of a pull request
+    pub async fn pr_diff(
+        &self,
+        pr_number: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.pr_diff(pr_number).await
+    }
+
+    /// Full commit id of the head of a pull request
+    pub async fn pr_head_sha(
+        &self,
+        pr_number: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.pr_head_sha(pr_number).await
+    }
+
+    /// Post review findings as a pull request review, returning its URL
+    pub async fn pr_create_review(
+        &self,
+        pr_number: u64,
+        commit_id: &str,
+        body: &str,
+        findings: &[ReviewFinding],
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations
+            .pr_create_review(pr_number, commit_id, body, findings)
+            .await
+    }
+
+    /// Current branch name and full commit id of HEAD
+    pub async fn head_commit(
+        &self,
+    ) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.head_commit().await
+    }
+
+    /// List the workflow runs triggered for a commit on a branch
+    pub async fn run_list_for_commit(
+        &self,
+        branch: &str,
+        sha: &str,
+    ) -> Result<Vec<WorkflowRun>, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.run_list_for_commit(branch, sha).await
+    }
+
+    /// List the checks of the pull request of a branch, or `None` when it has no pull request
+    pub async fn pr_checks(
+        &self,
+        branch: &str,
+    ) -> Result<Option<Vec<PrCheck>>, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.pr_checks(branch).await
+    }
+
+    /// Fetch the logs of the failed jobs of a workflow run
+    pub async fn run_failed_logs(
+        &self,
+        run_id: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.run_failed_logs(run_id).await
+    }
+
+    /// Push the current branch to its upstream
+    pub async fn push_current_branch(
+        &self,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.operations.push_current_branch().await
     }
 
     /// Helper method for basic command execution (used in tests)
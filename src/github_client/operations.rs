@@ -1,8 +1,12 @@
 use bollard::exec::{CreateExecOptions, StartExecOptions};
 use bollard::Docker;
 use futures_util::StreamExt;
+use std::collections::HashSet;
 
-use super::types::{GithubClientConfig, GithubCloneResult};
+use super::types::{
+    CloneOptions, CloneRequest, GithubClientConfig, GithubCloneResult, GithubUserProfile, PrCheck,
+    PullRequestReviewComment, RepositoryInfo, ReviewFinding, WorkflowRun,
+};
 
 /// GitHub repository operations functionality
 #[derive(Debug)]
@@ -27,31 +31,60 @@ impl GitHubOperations {
         &self.container_id
     }
 
-    /// Clone a repository using gh client
-    pub async fn repo_clone(
+    /// Clone a repository with branch/depth options
+    ///
+    /// GitHub `owner/repo` shorthand goes through `gh repo clone`; any other git URL
+    /// (GitLab, Gitea, self-hosted, SSH) is cloned with plain `git clone`.
+    pub async fn repo_clone_with_options(
         &self,
         repository: &str,
         target_dir: Option<&str>,
+        options: &CloneOptions,
     ) -> Result<GithubCloneResult, Box<dyn std::error::Error + Send + Sync>> {
-        log::info!("Cloning repository '{}' via gh client...", repository);
+        let request = CloneRequest {
+            repository: repository.to_string(),
+            options: options.clone(),
+        };
 
-        let mut clone_command = vec![
-            "gh".to_string(),
-            "repo".to_string(),
-            "clone".to_string(),
-            repository.to_string(),
-        ];
+        let mut git_flags = Vec::new();
+        if let Some(branch) = &options.branch {
+            git_flags.push("--branch".to_string());
+            git_flags.push(branch.clone());
+        }
+        if let Some(depth) = options.depth {
+            git_flags.push("--depth".to_string());
+            git_flags.push(depth.to_string());
+        }
+
+        let target_directory = target_dir
+            .map(|dir| dir.to_string())
+            .unwrap_or_else(|| request.default_directory());
 
-        let target_directory = if let Some(dir) = target_dir {
-            clone_command.push(dir.to_string());
-            dir.to_string()
+        let clone_command = if request.is_git_url() {
+            log::info!("Cloning repository '{}' via git...", repository);
+
+            let mut command = vec!["git".to_string(), "clone".to_string()];
+            command.extend(git_flags);
+            command.push(repository.to_string());
+            command.push(target_directory.clone());
+            command
         } else {
-            // Extract repo name from full repository path (e.g., "owner/repo" -> "repo")
-            repository
-                .split('/')
-                .last()
-                .unwrap_or(repository)
-                .to_string()
+            log::info!("Cloning repository '{}' via gh client...", repository);
+
+            let mut command = vec![
+                "gh".to_string(),
+                "repo".to_string(),
+                "clone".to_string(),
+                repository.to_string(),
+            ];
+            if let Some(dir) = target_dir {
+                command.push(dir.to_string());
+            }
+            if !git_flags.is_empty() {
+                command.push("--".to_string());
+                command.extend(git_flags);
+            }
+            command
         };
 
         match self.exec_command_allow_failure(clone_command).await {
@@ -115,37 +148,400 @@ impl GitHubOperations {
         self.exec_command(command).await
     }
 
-    /// List repositories for the authenticated user
-    pub async fn repo_list(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        log::info!("Listing repositories for authenticated user...");
+    /// List repositories of the authenticated user and all of their organizations
+    ///
+    /// Results are de-duplicated and sorted by most recently updated first.
+    pub async fn repo_list_detailed(
+        &self,
+    ) -> Result<Vec<RepositoryInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Listing repositories (JSON) for authenticated user and organizations...");
+
+        let mut owners: Vec<Option<String>> = vec![None];
+        match self
+            .exec_command(vec![
+                "gh".to_string(),
+                "api".to_string(),
+                "--paginate".to_string(),
+                "user/orgs".to_string(),
+                "--jq".to_string(),
+                ".[].login".to_string(),
+            ])
+            .await
+        {
+            Ok(output) => owners.extend(
+                output
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(|l| Some(l.to_string())),
+            ),
+            Err(e) => log::warn!(
+                "Failed to list organizations, showing user repos only: {}",
+                e
+            ),
+        }
+
+        let mut repositories: Vec<RepositoryInfo> = Vec::new();
+        let mut seen = HashSet::new();
+        for owner in owners {
+            let mut list_command = vec!["gh".to_string(), "repo".to_string(), "list".to_string()];
+            if let Some(owner) = &owner {
+                list_command.push(owner.clone());
+            }
+            list_command.extend([
+                "--limit".to_string(),
+                "1000".to_string(),
+                "--json".to_string(),
+                RepositoryInfo::JSON_FIELDS.to_string(),
+            ]);
+
+            let output = match self.exec_command(list_command).await {
+                Ok(output) => output,
+                // The user's own listing must work; organization listings are best-effort
+                Err(e) if owner.is_none() => return Err(e),
+                Err(e) => {
+                    log::warn!("Failed to list repositories for {:?}: {}", owner, e);
+                    continue;
+                }
+            };
+
+            let repos: Vec<RepositoryInfo> = serde_json::from_str(&output)
+                .map_err(|e| format!("Failed to parse repository list: {}", e))?;
+            repositories.extend(
+                repos
+                    .into_iter()
+                    .filter(|repo| seen.insert(repo.name_with_owner.clone())),
+            );
+        }
+
+        // ISO 8601 timestamps sort lexicographically
+        repositories.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+        Ok(repositories)
+    }
+
+    /// Fetch the profile of the authenticated user via the GitHub REST API
+    pub async fn current_user(
+        &self,
+    ) -> Result<GithubUserProfile, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Fetching authenticated GitHub user profile...");
+
+        let output = self
+            .exec_command(vec![
+                "gh".to_string(),
+                "api".to_string(),
+                "user".to_string(),
+            ])
+            .await?;
+
+        serde_json::from_str(&output)
+            .map_err(|e| format!("Failed to parse GitHub user profile: {}", e).into())
+    }
+
+    /// Check out a pull request branch using gh client
+    pub async fn pr_checkout(
+        &self,
+        pr_number: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Checking out pull request #{}...", pr_number);
+
+        let checkout_command = vec![
+            "gh".to_string(),
+            "pr".to_string(),
+            "checkout".to_string(),
+            pr_number.to_string(),
+        ];
+
+        self.exec_command(checkout_command).await
+    }
+
+    /// Fetch all review comments of a pull request via the GitHub REST API
+    pub async fn pr_review_comments(
+        &self,
+        pr_number: u64,
+    ) -> Result<Vec<PullRequestReviewComment>, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!(
+            "Fetching review comments for pull request #{}...",
+            pr_number
+        );
+
+        // `--jq '.[]'` flattens every page into one JSON object per line
+        let api_command = vec![
+            "gh".to_string(),
+            "api".to_string(),
+            "--paginate".to_string(),
+            format!("repos/{{owner}}/{{repo}}/pulls/{}/comments", pr_number),
+            "--jq".to_string(),
+            ".[]".to_string(),
+        ];
+
+        let output = self.exec_command(api_command).await?;
+
+        let mut comments = Vec::new();
+        for line in output.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<PullRequestReviewComment>(line) {
+                Ok(comment) => comments.push(comment),
+                Err(e) => log::warn!("Skipping unparseable review comment: {}", e),
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// Reply to a review comment thread on a pull request
+    pub async fn pr_reply_to_comment(
+        &self,
+        pr_number: u64,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        log::info!(
+            "Replying to review comment {} on pull request #{}...",
+            comment_id,
+            pr_number
+        );
+
+        let api_command = vec![
+            "gh".to_string(),
+            "api".to_string(),
+            "--method".to_string(),
+            "POST".to_string(),
+            format!(
+                "repos/{{owner}}/{{repo}}/pulls/{}/comments/{}/replies",
+                pr_number, comment_id
+            ),
+            "-f".to_string(),
+            format!("body={}", body),
+        ];
+
+        self.exec_command(api_command).await.map(|_| ())
+    }
+
+    /// Fetch the diff of a pull request
+    pub async fn pr_diff(
+        &self,
+        pr_number: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Fetching diff of pull request #{}...", pr_number);
+
+        self.exec_command(vec![
+            "gh".to_string(),
+            "pr".to_string(),
+            "diff".to_string(),
+            pr_number.to_string(),
+        ])
+        .await
+    }
+
+    /// Full commit id of the head of a pull request
+    pub async fn pr_head_sha(
+        &self,
+        pr_number: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let output = self
+            .exec_command(vec![
+                "gh".to_string(),
+                "pr".to_string(),
+                "view".to_string(),
+                pr_number.to_string(),
+                "--json".to_string(),
+                "headRefOid".to_string(),
+                "--jq".to_string(),
+                ".headRefOid".to_string(),
+            ])
+            .await?;
+
+        Ok(output.trim().to_string())
+    }
+
+    /// Post a pull request review with an inline comment for every finding that has a line
+    ///
+    /// Returns the URL of the created review.
+    pub async fn pr_create_review(
+        &self,
+        pr_number: u64,
+        commit_id: &str,
+        body: &str,
+        findings: &[ReviewFinding],
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!(
+            "Posting review with {} comments on pull request #{}...",
+            findings.len(),
+            pr_number
+        );
+
+        let comments: Vec<serde_json::Value> = findings
+            .iter()
+            .filter_map(|finding| {
+                finding.line.map(|line| {
+                    serde_json::json!({
+                        "path": finding.file,
+                        "line": line,
+                        "side": "RIGHT",
+                        "body": finding.comment_body(),
+                    })
+                })
+            })
+            .collect();
+        let payload = serde_json::json!({
+            "commit_id": commit_id,
+            "event": "COMMENT",
+            "body": body,
+            "comments": comments,
+        });
+
+        // The comments array can't be expressed with `-f` fields, so the payload goes on stdin
+        let api_command = vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            format!(
+                "printf '%s' \"$1\" | gh api --method POST \
+                 repos/{{owner}}/{{repo}}/pulls/{}/reviews --input - --jq .html_url",
+                pr_number
+            ),
+            "review".to_string(),
+            payload.to_string(),
+        ];
+
+        let output = self.exec_command(api_command).await?;
+        Ok(output.trim().to_string())
+    }
+
+    /// Push the currently checked out branch to its upstream
+    pub async fn push_current_branch(
+        &self,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Pushing current branch...");
+
+        let push_command = vec!["git".to_string(), "push".to_string()];
+
+        self.exec_command(push_command).await
+    }
+
+    /// Current branch name and full commit id of HEAD
+    pub async fn head_commit(
+        &self,
+    ) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+        let branch = self
+            .exec_command(vec![
+                "git".to_string(),
+                "rev-parse".to_string(),
+                "--abbrev-ref".to_string(),
+                "HEAD".to_string(),
+            ])
+            .await?;
+        let sha = self
+            .exec_command(vec![
+                "git".to_string(),
+                "rev-parse".to_string(),
+                "HEAD".to_string(),
+            ])
+            .await?;
+
+        Ok((branch.trim().to_string(), sha.trim().to_string()))
+    }
+
+    /// List the workflow runs of a branch that were triggered for the given commit
+    pub async fn run_list_for_commit(
+        &self,
+        branch: &str,
+        sha: &str,
+    ) -> Result<Vec<WorkflowRun>, Box<dyn std::error::Error + Send + Sync>> {
+        log::debug!("Listing workflow runs for {} at {}...", branch, sha);
 
         let list_command = vec![
             "gh".to_string(),
-            "repo".to_string(),
+            "run".to_string(),
             "list".to_string(),
+            "--branch".to_string(),
+            branch.to_string(),
             "--limit".to_string(),
-            "50".to_string(), // Reasonable limit
+            "50".to_string(),
+            "--json".to_string(),
+            WorkflowRun::JSON_FIELDS.to_string(),
         ];
 
-        match self.exec_command(list_command).await {
-            Ok(output) => {
-                log::debug!("Repository list output: {}", output);
-                Ok(output)
-            }
-            Err(e) => {
-                log::error!("Failed to list repositories: {}", e);
-                Err(e)
-            }
+        let output = self.exec_command(list_command).await?;
+        let runs: Vec<WorkflowRun> = serde_json::from_str(&output)
+            .map_err(|e| format!("Failed to parse workflow runs: {}", e))?;
+
+        Ok(runs.into_iter().filter(|run| run.head_sha == sha).collect())
+    }
+
+    /// List the checks of the pull request of a branch, or `None` when it has no pull request
+    pub async fn pr_checks(
+        &self,
+        branch: &str,
+    ) -> Result<Option<Vec<PrCheck>>, Box<dyn std::error::Error + Send + Sync>> {
+        log::debug!("Listing pull request checks for {}...", branch);
+
+        // gh exits with 8 while checks are pending and with 1 when one failed, also with --json
+        let script = r#"
+out=$(gh pr checks "$1" --json "$2" 2>&1)
+code=$?
+case "$out" in
+    *"no pull requests found"*) exit 0 ;;
+    *"no checks reported"*) echo "[]"; exit 0 ;;
+esac
+printf '%s\n' "$out"
+if [ "$code" -eq 8 ]; then exit 0; fi
+if [ "$code" -eq 1 ]; then case "$out" in "["*) exit 0 ;; esac; fi
+exit "$code"
+"#;
+
+        let output = self
+            .exec_command(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                script.to_string(),
+                "pr-checks".to_string(),
+                branch.to_string(),
+                PrCheck::JSON_FIELDS.to_string(),
+            ])
+            .await?;
+
+        if output.trim().is_empty() {
+            return Ok(None);
         }
+
+        let checks: Vec<PrCheck> = serde_json::from_str(&output)
+            .map_err(|e| format!("Failed to parse pull request checks: {}: {}", e, output))?;
+
+        Ok(Some(checks))
+    }
+
+    /// Fetch the logs of the failed jobs of a workflow run
+    pub async fn run_failed_logs(
+        &self,
+        run_id: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Fetching failed job logs for run {}...", run_id);
+
+        self.exec_command(vec![
+            "gh".to_string(),
+            "run".to_string(),
+            "view".to_string(),
+            run_id.to_string(),
+            "--log-failed".to_string(),
+        ])
+        .await
     }
 
     /// Analyze clone failure output to provide better error messages
     fn analyze_clone_failure(&self, output: &str) -> String {
         let output_lower = output.to_lowercase();
 
-        if output_lower.contains("repository not found") || output_lower.contains("not found") {
+        if output_lower.contains("remote branch") && output_lower.contains("not found") {
+            "Branch not found. Please check the branch name.".to_string()
+        } else if output_lower.contains("repository not found")
+            || output_lower.contains("not found")
+        {
             "Repository not found. Please check the repository name and your access permissions."
                 .to_string()
+        } else if output_lower.contains("host key verification failed") {
+            "The server's SSH host key is unknown. Check its fingerprint with the provider, add it to ~/.ssh/known_hosts in the session and try again.".to_string()
+        } else if output_lower.contains("publickey") {
+            "SSH access denied. Add your deploy key (see /deploykey) to the repository and try again.".to_string()
         } else if output_lower.contains("permission denied")
             || output_lower.contains("authentication")
         {
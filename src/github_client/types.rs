@@ -32,3 +32,567 @@ impl Default for GithubClientConfig {
         }
     }
 }
+
+/// GitHub user reference as embedded in REST API payloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GithubUser {
+    pub login: String,
+}
+
+/// Profile of the authenticated GitHub user as returned by `gh api user`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GithubUserProfile {
+    pub login: String,
+    pub id: u64,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+impl GithubUserProfile {
+    /// Name to use for commits, falling back to the login when no display name is set
+    pub fn commit_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or(&self.login)
+    }
+
+    /// Email to use for commits; private emails fall back to the GitHub noreply address
+    pub fn commit_email(&self) -> String {
+        match self.email.as_deref().filter(|e| !e.trim().is_empty()) {
+            Some(email) => email.to_string(),
+            None => format!("{}+{}@users.noreply.github.com", self.id, self.login),
+        }
+    }
+}
+
+/// A single pull request review comment as returned by the GitHub REST API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestReviewComment {
+    pub id: u64,
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub line: Option<u64>,
+    #[serde(default)]
+    pub original_line: Option<u64>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub user: Option<GithubUser>,
+    #[serde(default)]
+    pub in_reply_to_id: Option<u64>,
+}
+
+impl PullRequestReviewComment {
+    /// Login of the comment author, or "unknown" when GitHub omits it
+    pub fn author(&self) -> &str {
+        self.user
+            .as_ref()
+            .map(|u| u.login.as_str())
+            .unwrap_or("unknown")
+    }
+
+    /// Best-effort line number (falls back to the original line for outdated comments)
+    pub fn line_number(&self) -> Option<u64> {
+        self.line.or(self.original_line)
+    }
+}
+
+/// A review thread: the root comment plus any replies to it
+#[derive(Debug, Clone)]
+pub struct ReviewThread {
+    pub root: PullRequestReviewComment,
+    pub replies: Vec<PullRequestReviewComment>,
+}
+
+impl ReviewThread {
+    /// Location of the thread in `path:line` form
+    pub fn location(&self) -> String {
+        match self.root.line_number() {
+            Some(line) => format!("{}:{}", self.root.path, line),
+            None => self.root.path.clone(),
+        }
+    }
+}
+
+/// Group flat review comments into threads, preserving the order of root comments
+pub fn group_review_threads(comments: Vec<PullRequestReviewComment>) -> Vec<ReviewThread> {
+    let mut threads: Vec<ReviewThread> = Vec::new();
+    let mut replies = Vec::new();
+
+    for comment in comments {
+        if comment.in_reply_to_id.is_some() {
+            replies.push(comment);
+        } else {
+            threads.push(ReviewThread {
+                root: comment,
+                replies: Vec::new(),
+            });
+        }
+    }
+
+    for reply in replies {
+        if let Some(thread) = threads
+            .iter_mut()
+            .find(|t| Some(t.root.id) == reply.in_reply_to_id)
+        {
+            thread.replies.push(reply);
+        }
+    }
+
+    threads
+}
+
+/// Repository metadata as returned by `gh repo list --json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryInfo {
+    pub name_with_owner: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub visibility: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+impl RepositoryInfo {
+    /// Fields requested from `gh repo list --json`
+    pub const JSON_FIELDS: &'static str = "nameWithOwner,name,description,visibility,updatedAt";
+
+    /// Whether the repository is private or internal
+    pub fn is_private(&self) -> bool {
+        self.visibility
+            .as_deref()
+            .is_some_and(|v| !v.eq_ignore_ascii_case("public"))
+    }
+
+    /// Date part of the last update timestamp (`YYYY-MM-DD`)
+    pub fn updated_date(&self) -> Option<&str> {
+        self.updated_at.as_deref().map(|t| t.get(..10).unwrap_or(t))
+    }
+
+    /// Case-insensitive match of the query against the full name and description
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name_with_owner.to_lowercase().contains(&query)
+            || self
+                .description
+                .as_deref()
+                .is_some_and(|d| d.to_lowercase().contains(&query))
+    }
+}
+
+/// A GitHub Actions workflow run as returned by `gh run list --json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRun {
+    pub database_id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub conclusion: Option<String>,
+    #[serde(default)]
+    pub head_branch: String,
+    #[serde(default)]
+    pub head_sha: String,
+    #[serde(default)]
+    pub url: String,
+}
+
+impl WorkflowRun {
+    /// Fields requested from `gh run list --json`
+    pub const JSON_FIELDS: &'static str =
+        "databaseId,name,status,conclusion,headBranch,headSha,url";
+
+    pub fn is_completed(&self) -> bool {
+        self.status == "completed"
+    }
+
+    /// Whether the run finished without a failure (skipped and neutral runs count as passing)
+    pub fn is_success(&self) -> bool {
+        self.is_completed()
+            && matches!(
+                self.conclusion.as_deref(),
+                Some("success" | "skipped" | "neutral")
+            )
+    }
+}
+
+/// A check of a pull request as returned by `gh pr checks --json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrCheck {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub state: String,
+    /// `pass`, `fail`, `pending`, `skipping` or `cancel`
+    #[serde(default)]
+    pub bucket: String,
+    #[serde(default)]
+    pub link: String,
+}
+
+impl PrCheck {
+    /// Fields requested from `gh pr checks --json`
+    pub const JSON_FIELDS: &'static str = "name,state,bucket,link";
+
+    pub fn is_completed(&self) -> bool {
+        self.bucket != "pending"
+    }
+
+    /// Whether the check finished without a failure (skipped checks count as passing)
+    pub fn is_success(&self) -> bool {
+        matches!(self.bucket.as_str(), "pass" | "skipping")
+    }
+
+    /// Id of the workflow run behind a GitHub Actions check, taken from its link
+    pub fn run_id(&self) -> Option<u64> {
+        let (_, rest) = self.link.split_once("/actions/runs/")?;
+        rest.split('/').next()?.parse().ok()
+    }
+}
+
+/// Severity of a code review finding, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSeverity {
+    #[serde(alias = "blocker")]
+    Critical,
+    #[serde(alias = "high")]
+    Major,
+    #[serde(alias = "medium")]
+    Minor,
+    #[serde(alias = "low")]
+    Nit,
+}
+
+impl ReviewSeverity {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            ReviewSeverity::Critical => "🔴",
+            ReviewSeverity::Major => "🟠",
+            ReviewSeverity::Minor => "🟡",
+            ReviewSeverity::Nit => "⚪",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReviewSeverity::Critical => "Critical",
+            ReviewSeverity::Major => "Major",
+            ReviewSeverity::Minor => "Minor",
+            ReviewSeverity::Nit => "Nit",
+        }
+    }
+}
+
+/// A single issue found while reviewing a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewFinding {
+    pub file: String,
+    /// Line in the new version of the file; `None` for file-level findings
+    #[serde(default)]
+    pub line: Option<u64>,
+    pub severity: ReviewSeverity,
+    pub suggestion: String,
+}
+
+impl ReviewFinding {
+    /// Location as `file:line`, or just the file for file-level findings
+    pub fn location(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{}", self.file, line),
+            None => self.file.clone(),
+        }
+    }
+
+    /// Markdown body of the finding when posted as a GitHub review comment
+    pub fn comment_body(&self) -> String {
+        format!("**{}:** {}", self.severity.label(), self.suggestion)
+    }
+}
+
+/// Options controlling how a repository is cloned
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CloneOptions {
+    pub branch: Option<String>,
+    pub depth: Option<u32>,
+}
+
+/// A clone request parsed from user input: a repository plus clone options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloneRequest {
+    pub repository: String,
+    pub options: CloneOptions,
+}
+
+impl CloneRequest {
+    /// Parse `<repository> [--branch <name>] [--depth <n>]`
+    ///
+    /// The repository may be GitHub shorthand (`owner/repo`) or any HTTPS/SSH git URL.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = shlex::split(input.trim()).ok_or("Unbalanced quotes in clone request")?;
+        let mut tokens = tokens.into_iter();
+
+        let repository = tokens.next().ok_or("No repository given")?;
+        if !is_github_shorthand(&repository) && !is_git_url(&repository) {
+            return Err(format!(
+                "'{}' is neither an owner/repo name nor a git URL",
+                repository
+            ));
+        }
+
+        let mut options = CloneOptions::default();
+        while let Some(flag) = tokens.next() {
+            match flag.as_str() {
+                "--branch" | "-b" => {
+                    options.branch = Some(tokens.next().ok_or("--branch requires a value")?);
+                }
+                "--depth" => {
+                    let depth = tokens.next().ok_or("--depth requires a value")?;
+                    options.depth = Some(
+                        depth
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|d| *d > 0)
+                            .ok_or_else(|| format!("Invalid depth '{}'", depth))?,
+                    );
+                }
+                other => return Err(format!("Unknown clone option '{}'", other)),
+            }
+        }
+
+        Ok(Self {
+            repository,
+            options,
+        })
+    }
+
+    /// Whether the repository is a full git URL rather than GitHub shorthand
+    pub fn is_git_url(&self) -> bool {
+        is_git_url(&self.repository)
+    }
+
+    /// Directory name git would pick for this repository (e.g. `repo` for `.../repo.git`)
+    pub fn default_directory(&self) -> String {
+        let trimmed = self.repository.trim_end_matches('/');
+        let last = trimmed.rsplit(['/', ':']).next().unwrap_or(trimmed);
+        last.strip_suffix(".git").unwrap_or(last).to_string()
+    }
+}
+
+/// Whether the input looks like GitHub `owner/repo` shorthand
+pub fn is_github_shorthand(input: &str) -> bool {
+    let parts: Vec<&str> = input.split('/').collect();
+    parts.len() == 2
+        && parts.iter().all(|p| {
+            !p.is_empty()
+                && p.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+}
+
+/// Whether the input looks like a git URL (`https://`, `ssh://`, `git://` or scp-like `user@host:path`)
+pub fn is_git_url(input: &str) -> bool {
+    if input.chars().any(char::is_whitespace) {
+        return false;
+    }
+
+    if let Some((scheme, rest)) = input.split_once("://") {
+        return matches!(scheme, "https" | "http" | "ssh" | "git")
+            && rest.split_once('/').is_some_and(|(host, path)| {
+                !host.is_empty() && !path.trim_matches('/').is_empty()
+            });
+    }
+
+    // scp-like syntax: git@gitlab.com:group/project.git
+    match input.split_once(':') {
+        Some((user_host, path)) => {
+            user_host
+                .split_once('@')
+                .is_some_and(|(user, host)| !user.is_empty() && !host.is_empty())
+                && !path.is_empty()
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: u64, in_reply_to_id: Option<u64>, body: &str) -> PullRequestReviewComment {
+        PullRequestReviewComment {
+            id,
+            path: "src/main.rs".to_string(),
+            line: Some(10),
+            original_line: None,
+            body: body.to_string(),
+            user: Some(GithubUser {
+                login: "reviewer".to_string(),
+            }),
+            in_reply_to_id,
+        }
+    }
+
+    #[test]
+    fn test_group_review_threads() {
+        let comments = vec![
+            comment(1, None, "Please rename this"),
+            comment(2, Some(1), "Agreed"),
+            comment(3, None, "Missing error handling"),
+            comment(4, Some(99), "Orphaned reply"),
+        ];
+
+        let threads = group_review_threads(comments);
+
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].root.id, 1);
+        assert_eq!(threads[0].replies.len(), 1);
+        assert_eq!(threads[0].replies[0].body, "Agreed");
+        assert_eq!(threads[1].root.id, 3);
+        assert!(threads[1].replies.is_empty());
+        assert_eq!(threads[0].location(), "src/main.rs:10");
+    }
+
+    #[test]
+    fn test_review_comment_deserialization() {
+        let json = r#"{"id":42,"path":"lib.rs","line":null,"original_line":7,"body":"nit","user":{"login":"octocat"},"extra":"ignored"}"#;
+        let parsed: PullRequestReviewComment = serde_json::from_str(json).unwrap();
+
+        assert_eq!(parsed.id, 42);
+        assert_eq!(parsed.author(), "octocat");
+        assert_eq!(parsed.line_number(), Some(7));
+        assert!(parsed.in_reply_to_id.is_none());
+    }
+
+    #[test]
+    fn test_clone_request_parse() {
+        let request = CloneRequest::parse("octocat/Hello-World").unwrap();
+        assert_eq!(request.repository, "octocat/Hello-World");
+        assert_eq!(request.options, CloneOptions::default());
+        assert!(!request.is_git_url());
+        assert_eq!(request.default_directory(), "Hello-World");
+
+        let request =
+            CloneRequest::parse("https://gitlab.com/group/sub/project.git --branch dev --depth 1")
+                .unwrap();
+        assert!(request.is_git_url());
+        assert_eq!(request.options.branch.as_deref(), Some("dev"));
+        assert_eq!(request.options.depth, Some(1));
+        assert_eq!(request.default_directory(), "project");
+
+        let request = CloneRequest::parse("git@git.example.com:team/tool.git -b main").unwrap();
+        assert!(request.is_git_url());
+        assert_eq!(request.options.branch.as_deref(), Some("main"));
+        assert_eq!(request.default_directory(), "tool");
+
+        assert!(CloneRequest::parse("").is_err());
+        assert!(CloneRequest::parse("not a repo").is_err());
+        assert!(CloneRequest::parse("owner/repo --depth 0").is_err());
+        assert!(CloneRequest::parse("owner/repo --branch").is_err());
+        assert!(CloneRequest::parse("owner/repo --mirror").is_err());
+    }
+
+    #[test]
+    fn test_git_url_detection() {
+        assert!(is_git_url("https://gitea.example.org/me/repo"));
+        assert!(is_git_url("ssh://git@host:2222/me/repo.git"));
+        assert!(is_git_url("git@github.com:owner/repo.git"));
+        assert!(!is_git_url("owner/repo"));
+        assert!(!is_git_url("https://example.com"));
+        assert!(!is_git_url("ftp://example.com/repo"));
+        assert!(is_github_shorthand("owner/repo.rs"));
+        assert!(!is_github_shorthand("owner/repo/extra"));
+        assert!(!is_github_shorthand("hello world/x"));
+    }
+
+    #[test]
+    fn test_repository_info_deserialization() {
+        let json = r#"[{"name":"vscode","nameWithOwner":"microsoft/vscode","description":"Code editing.","visibility":"PUBLIC","updatedAt":"2024-05-01T12:34:56Z"},{"name":"secret","nameWithOwner":"me/secret","description":"","visibility":"PRIVATE","updatedAt":null}]"#;
+        let repos: Vec<RepositoryInfo> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(repos.len(), 2);
+        assert!(!repos[0].is_private());
+        assert_eq!(repos[0].updated_date(), Some("2024-05-01"));
+        assert!(repos[0].matches("EDITING"));
+        assert!(repos[0].matches("microsoft/"));
+        assert!(repos[1].is_private());
+        assert_eq!(repos[1].updated_date(), None);
+        assert!(!repos[1].matches("vscode"));
+    }
+
+    #[test]
+    fn test_github_user_profile_commit_identity() {
+        let json = r#"{"login":"octocat","id":583231,"name":"The Octocat","email":null}"#;
+        let profile: GithubUserProfile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile.commit_name(), "The Octocat");
+        assert_eq!(
+            profile.commit_email(),
+            "583231+octocat@users.noreply.github.com"
+        );
+
+        let json = r#"{"login":"octocat","id":1,"name":"","email":"octo@example.com"}"#;
+        let profile: GithubUserProfile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile.commit_name(), "octocat");
+        assert_eq!(profile.commit_email(), "octo@example.com");
+    }
+
+    #[test]
+    fn test_workflow_run_status() {
+        let json = r#"[
+            {"databaseId":1,"name":"CI","status":"completed","conclusion":"success","headBranch":"main","headSha":"abc","url":"https://github.com/o/r/actions/runs/1"},
+            {"databaseId":2,"name":"Lint","status":"completed","conclusion":"failure","headBranch":"main","headSha":"abc","url":""},
+            {"databaseId":3,"name":"Deploy","status":"in_progress","conclusion":"","headBranch":"main","headSha":"abc","url":""}
+        ]"#;
+        let runs: Vec<WorkflowRun> = serde_json::from_str(json).unwrap();
+
+        assert!(runs[0].is_success());
+        assert!(runs[1].is_completed());
+        assert!(!runs[1].is_success());
+        assert!(!runs[2].is_completed());
+        assert!(!runs[2].is_success());
+    }
+
+    #[test]
+    fn test_pr_check_status() {
+        let json = r#"[
+            {"name":"build","state":"SUCCESS","bucket":"pass","link":"https://github.com/o/r/actions/runs/42/job/7"},
+            {"name":"lint","state":"FAILURE","bucket":"fail","link":"https://github.com/o/r/actions/runs/43/job/8"},
+            {"name":"deploy","state":"PENDING","bucket":"pending","link":"https://ci.example.com/1"}
+        ]"#;
+        let checks: Vec<PrCheck> = serde_json::from_str(json).unwrap();
+
+        assert!(checks[0].is_success());
+        assert_eq!(checks[0].run_id(), Some(42));
+        assert!(checks[1].is_completed());
+        assert!(!checks[1].is_success());
+        assert_eq!(checks[1].run_id(), Some(43));
+        assert!(!checks[2].is_completed());
+        assert_eq!(checks[2].run_id(), None);
+    }
+
+    #[test]
+    fn test_review_finding_deserialization() {
+        let json = r#"[
+            {"file":"src/main.rs","line":12,"severity":"critical","suggestion":"Handle the error"},
+            {"file":"README.md","severity":"low","suggestion":"Fix the typo"}
+        ]"#;
+        let findings: Vec<ReviewFinding> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(findings[0].severity, ReviewSeverity::Critical);
+        assert_eq!(findings[0].location(), "src/main.rs:12");
+        assert_eq!(findings[0].comment_body(), "**Critical:** Handle the error");
+        assert_eq!(findings[1].severity, ReviewSeverity::Nit);
+        assert_eq!(findings[1].location(), "README.md");
+        assert!(ReviewSeverity::Critical < ReviewSeverity::Nit);
+    }
+}
@@ -12,7 +12,7 @@ mod github_client;
 
 use bot::{
     escape_markdown_v2, handle_auth_state_updates, handle_callback_query, handle_text_message,
-    AuthSession, AuthSessions, BotState, ClaudeSessions,
+    AuthSession, AuthSessions, BotState, ClaudeSessions, CostLedger, TokenStore,
 };
 use telegram_bot::claude_code_client::container_utils;
 
@@ -37,8 +37,66 @@ enum Command {
     UpdateClaude,
     #[command(description = "Start a new Claude conversation")]
     Claude,
+    #[command(description = "Browse past Claude conversations and resume one")]
+    History,
+    #[command(description = "Branch the current conversation to try another approach")]
+    Fork(String),
+    #[command(description = "Export the conversation as Markdown, HTML or JSONL")]
+    Export(String),
+    #[command(description = "Show Claude spend by day and repository, and budgets")]
+    Usage,
+    #[command(description = "Show or set instructions added to Claude's system prompt")]
+    Instructions(String),
+    #[command(description = "View or edit CLAUDE.md of the repository, or your own with user")]
+    ClaudeMd(String),
+    #[command(description = "Manage MCP servers for Claude: /mcp add|remove|list")]
+    Mcp(String),
+    #[command(description = "Toggle keeping one Claude process running between prompts")]
+    Interactive(String),
+    #[command(description = "Show or set max turns and time limit per Claude run")]
+    Limits(String),
     #[command(description = "Generate commit message and commit changes")]
     Commit,
+    #[command(description = "Toggle committing changes after every successful Claude run")]
+    AutoCommit(String),
+    #[command(description = "Stash your changes, or restore them with /stash pop")]
+    Stash(String),
+    #[command(description = "Pick changed files and discard their changes")]
+    Discard(String),
+    #[command(description = "Show recent commits, optionally for a path")]
+    Log(String),
+    #[command(description = "Show the commit that last changed a line: /blame <file> <line>")]
+    Blame(String),
+    // Telegram doesn't allow hyphens in command names, so /pr-review is spelled /pr_review
+    #[command(
+        rename = "pr_review",
+        description = "Address review comments on a pull request"
+    )]
+    PrReview(String),
+    #[command(description = "Let Claude review a pull request, a branch or your changes")]
+    Review(String),
+    #[command(description = "Clone a repository from GitHub or any git URL")]
+    Clone(String),
+    #[command(description = "Switch between cloned repositories, or use all of them")]
+    Repo(String),
+    #[command(description = "Show or manage your SSH deploy key")]
+    DeployKey(String),
+    #[command(description = "Store your own GitHub token (private chat only)")]
+    GhToken(String),
+    #[command(description = "Show or set the git identity used for commits")]
+    Identity(String),
+    #[command(description = "Show or manage your commit signing key")]
+    SigningKey(String),
+    #[command(description = "List working tree checkpoints taken before Claude runs")]
+    Checkpoints,
+    #[command(description = "Restore the latest (or a given) checkpoint")]
+    Undo(String),
+    #[command(description = "Run parallel tasks in separate git worktrees")]
+    Task(String),
+    #[command(description = "Watch CI checks for the current branch")]
+    Ci,
+    #[command(description = "Update from the default branch and resolve conflicts with Claude")]
+    Sync,
 }
 
 /// Pull the runtime image asynchronously in the background
@@ -105,6 +163,8 @@ async fn main() {
         docker: docker.clone(),
         auth_sessions: auth_sessions.clone(),
         claude_sessions: claude_sessions.clone(),
+        token_store: TokenStore::from_env(),
+        cost_ledger: CostLedger::from_env(),
     };
 
     // Set up message handler that handles both commands and regular text
@@ -183,9 +243,90 @@ async fn answer(bot: Bot, msg: Message, cmd: Command, bot_state: BotState) -> Re
         Command::Claude => {
             commands::handle_claude(bot, msg, bot_state, chat_id).await?;
         }
+        Command::History => {
+            commands::handle_history(bot, msg, bot_state).await?;
+        }
+        Command::Fork(args) => {
+            commands::handle_fork(bot, msg, bot_state, user_id, args).await?;
+        }
+        Command::Export(args) => {
+            commands::handle_export(bot, msg, bot_state, args).await?;
+        }
+        Command::Usage => {
+            commands::handle_usage(bot, msg, bot_state, chat_id, user_id).await?;
+        }
+        Command::Instructions(args) => {
+            commands::handle_instructions(bot, msg, bot_state, args).await?;
+        }
+        Command::ClaudeMd(args) => {
+            commands::handle_claude_md(bot, msg, bot_state, args).await?;
+        }
+        Command::Mcp(args) => {
+            commands::handle_mcp(bot, msg, bot_state, args).await?;
+        }
+        Command::Interactive(args) => {
+            commands::handle_interactive(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Limits(args) => {
+            commands::handle_limits(bot, msg, bot_state, chat_id, args).await?;
+        }
         Command::Commit => {
             commands::handle_commit(bot, msg, bot_state, chat_id).await?;
         }
+        Command::AutoCommit(args) => {
+            commands::handle_auto_commit(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Stash(args) => {
+            commands::handle_stash(bot, msg, bot_state, args).await?;
+        }
+        Command::Discard(args) => {
+            commands::handle_discard(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Log(args) => {
+            commands::handle_log(bot, msg, bot_state, args).await?;
+        }
+        Command::Blame(args) => {
+            commands::handle_blame(bot, msg, bot_state, args).await?;
+        }
+        Command::PrReview(args) => {
+            commands::handle_pr_review(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Review(args) => {
+            commands::handle_review(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Clone(args) => {
+            commands::handle_clone(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Repo(args) => {
+            commands::handle_repo(bot, msg, bot_state, args).await?;
+        }
+        Command::DeployKey(args) => {
+            commands::handle_deploy_key(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::GhToken(args) => {
+            commands::handle_github_token(bot, msg, bot_state, chat_id, user_id, args).await?;
+        }
+        Command::SigningKey(args) => {
+            commands::handle_signing_key(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Identity(args) => {
+            commands::handle_identity(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Checkpoints => {
+            commands::handle_checkpoints(bot, msg, bot_state, chat_id).await?;
+        }
+        Command::Undo(args) => {
+            commands::handle_undo(bot, msg, bot_state, args).await?;
+        }
+        Command::Task(args) => {
+            commands::handle_task(bot, msg, bot_state, chat_id, args).await?;
+        }
+        Command::Ci => {
+            commands::handle_ci(bot, msg, bot_state).await?;
+        }
+        Command::Sync => {
+            commands::handle_sync(bot, msg, bot_state).await?;
+        }
     }
 
     Ok(())
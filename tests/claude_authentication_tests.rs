@@ -101,16 +101,11 @@ async fn test_claude_authentication_command_workflow(
         // Step 2: Simulate finding the session (what happens in /authenticateclaude command)
         println!("=== STEP 2: Finding session for authentication ===");
 
-        let auth_client_result =
-            ClaudeCodeClient::for_session(docker.clone(), &container_name).await;
-        if auth_client_result.is_err() {
-            return Err(format!(
-                "Failed to find session: {:?}",
-                auth_client_result.unwrap_err()
-            )
-            .into());
-        }
-        let auth_client = auth_client_result.unwrap();
+        let auth_client = match ClaudeCodeClient::for_session(docker.clone(), &container_name).await
+        {
+            Ok(client) => client,
+            Err(e) => return Err(format!("Failed to find session: {:?}", e).into()),
+        };
 
         // Step 3: Test the Claude account authentication method (core of /authenticateclaude command)
         println!("=== STEP 3: Testing Claude account authentication ===");
@@ -1395,6 +1390,7 @@ async fn test_claude_json_initialization_from_runtime(docker: Docker) {
         ClaudeCodeConfig::default(),
         CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -1466,6 +1462,7 @@ async fn test_claude_json_persistence_across_sessions(docker: Docker) {
         ClaudeCodeConfig::default(),
         CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -1505,6 +1502,7 @@ async fn test_claude_json_persistence_across_sessions(docker: Docker) {
         ClaudeCodeConfig::default(),
         CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
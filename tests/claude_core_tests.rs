@@ -554,6 +554,7 @@ async fn test_claude_config_persistence_between_sessions(docker_socket: Docker)
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -648,6 +649,7 @@ async fn test_claude_config_persistence_between_sessions(docker_socket: Docker)
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -716,6 +718,7 @@ async fn test_claude_config_isolation_between_users(docker_socket: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id_1.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -734,6 +737,7 @@ async fn test_claude_config_isolation_between_users(docker_socket: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id_2.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -1,10 +1,8 @@
 use bollard::Docker;
-use futures_util;
 use rstest::*;
 use telegram_bot::claude_code_client::ClaudeCodeConfig;
-use telegram_bot::github_client::{GithubClient, GithubClientConfig};
+use telegram_bot::github_client::{CloneOptions, GithubClient, GithubClientConfig};
 use telegram_bot::{container_utils, ClaudeCodeClient};
-use uuid;
 
 // =============================================================================
 // COMMON FIXTURES AND HELPER FUNCTIONS
@@ -729,7 +727,7 @@ async fn test_github_repo_list(#[future] test_container: (Docker, String, String
     let client = GithubClient::new(docker.clone(), container_id, GithubClientConfig::default());
 
     // Test GitHub repository list command
-    let repo_list_result = client.repo_list().await;
+    let repo_list_result = client.repo_list_detailed().await;
 
     // Cleanup
     cleanup_container(&docker, &container_name).await;
@@ -771,7 +769,11 @@ async fn test_github_repo_clone_invalid_repo(#[future] test_container: (Docker,
 
     // Test cloning with an invalid repository name
     let clone_result = client
-        .repo_clone("invalid/nonexistent-repo-12345", None)
+        .repo_clone_with_options(
+            "invalid/nonexistent-repo-12345",
+            None,
+            &CloneOptions::default(),
+        )
         .await;
 
     // Cleanup
@@ -809,7 +811,11 @@ async fn test_github_repo_clone_with_target_directory(
 
     // Test cloning with a target directory specification
     let clone_result = client
-        .repo_clone("octocat/Hello-World", Some("test-clone-dir"))
+        .repo_clone_with_options(
+            "octocat/Hello-World",
+            Some("test-clone-dir"),
+            &CloneOptions::default(),
+        )
         .await;
 
     // Cleanup
@@ -847,7 +853,11 @@ async fn test_github_repo_clone_empty_repository(
     // Test cloning an empty repository (using GitHub's official empty repo for testing)
     // Note: We use a known empty public repository that should exist
     let clone_result = client
-        .repo_clone("octocat/Hello-World-Template", None)
+        .repo_clone_with_options(
+            "octocat/Hello-World-Template",
+            None,
+            &CloneOptions::default(),
+        )
         .await;
 
     // Cleanup
@@ -885,7 +895,11 @@ async fn test_github_repo_clone_multi_branch_repository(
     // Test cloning a repository known to have multiple branches
     // Using a popular public repository that has multiple branches
     let clone_result = client
-        .repo_clone("microsoft/vscode", Some("vscode-clone"))
+        .repo_clone_with_options(
+            "microsoft/vscode",
+            Some("vscode-clone"),
+            &CloneOptions::default(),
+        )
         .await;
 
     // Cleanup
@@ -930,7 +944,9 @@ async fn test_github_repo_clone_malformed_urls(#[future] test_container: (Docker
     ];
 
     for repo in malformed_repos {
-        let clone_result = client.repo_clone(repo, None).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, None, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -988,7 +1004,9 @@ async fn test_github_repo_clone_target_directory_edge_cases(
     for (repo, target_dir, description) in test_cases {
         println!("Testing: {}", description);
 
-        let clone_result = client.repo_clone(repo, target_dir).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, target_dir, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -1042,7 +1060,9 @@ async fn test_github_repo_clone_nonexistent_variations(
     ];
 
     for repo in nonexistent_repos {
-        let clone_result = client.repo_clone(repo, None).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, None, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -1109,7 +1129,9 @@ async fn test_github_repo_clone_private_repo_without_auth(
     ];
 
     for repo in private_repos {
-        let clone_result = client.repo_clone(repo, None).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, None, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -1146,7 +1168,7 @@ async fn test_github_repo_clone_concurrent_operations(
     let client = GithubClient::new(docker.clone(), container_id, GithubClientConfig::default());
 
     // Test multiple concurrent clone operations
-    let repos = vec![
+    let repos = [
         ("octocat/Hello-World", Some("concurrent-1")),
         ("octocat/Hello-World", Some("concurrent-2")),
         ("invalid/nonexistent-repo-1", Some("concurrent-fail-1")),
@@ -1154,9 +1176,10 @@ async fn test_github_repo_clone_concurrent_operations(
     ];
 
     // Launch all clone operations concurrently
+    let options = CloneOptions::default();
     let mut clone_futures = Vec::new();
     for (repo, target_dir) in repos.iter() {
-        let future = client.repo_clone(repo, *target_dir);
+        let future = client.repo_clone_with_options(repo, *target_dir, &options);
         clone_futures.push(future);
     }
 
@@ -1210,7 +1233,9 @@ async fn test_github_repo_clone_special_characters_and_unicode(
     ];
 
     for repo in special_repos {
-        let clone_result = client.repo_clone(repo, None).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, None, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -1243,7 +1268,11 @@ async fn test_github_repo_clone_special_characters_and_unicode(
 
     for target_dir in special_target_dirs {
         let clone_result = client
-            .repo_clone("octocat/Hello-World", Some(&target_dir))
+            .repo_clone_with_options(
+                "octocat/Hello-World",
+                Some(target_dir),
+                &CloneOptions::default(),
+            )
             .await;
 
         assert!(
@@ -1279,7 +1308,11 @@ async fn test_github_repo_clone_large_repository(
     // Test cloning a large repository (this tests timeout handling and large transfers)
     // Using a well-known large repository
     let clone_result = client
-        .repo_clone("torvalds/linux", Some("linux-kernel-clone"))
+        .repo_clone_with_options(
+            "torvalds/linux",
+            Some("linux-kernel-clone"),
+            &CloneOptions::default(),
+        )
         .await;
 
     // Cleanup
@@ -1322,7 +1355,9 @@ async fn test_github_repo_clone_case_sensitivity(
     ];
 
     for repo in case_variants {
-        let clone_result = client.repo_clone(repo, None).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, None, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -1363,7 +1398,9 @@ async fn test_github_repo_clone_target_directory_default_behavior(
     ];
 
     for repo in test_repos {
-        let clone_result = client.repo_clone(repo, None).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, None, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -1376,7 +1413,7 @@ async fn test_github_repo_clone_target_directory_default_behavior(
         assert_eq!(clone_response.repository, repo);
 
         // Default target directory should be the repository name (last part after '/')
-        let expected_target = repo.split('/').last().unwrap_or(repo);
+        let expected_target = repo.split('/').next_back().unwrap_or(repo);
         assert_eq!(
             clone_response.target_directory, expected_target,
             "Default target directory should be repo name for: {}",
@@ -1423,7 +1460,11 @@ async fn test_github_clone_with_different_working_directories(
 
         // Test basic clone operation
         let clone_result = client
-            .repo_clone("octocat/Hello-World", Some("test-working-dir"))
+            .repo_clone_with_options(
+                "octocat/Hello-World",
+                Some("test-working-dir"),
+                &CloneOptions::default(),
+            )
             .await;
 
         assert!(
@@ -1473,9 +1514,10 @@ async fn test_github_clone_with_different_timeouts(
 
         // Test clone operation with this timeout
         let clone_result = client
-            .repo_clone(
+            .repo_clone_with_options(
                 "octocat/Hello-World",
                 Some(&format!("test-timeout-{}", timeout_secs)),
+                &CloneOptions::default(),
             )
             .await;
 
@@ -1518,7 +1560,9 @@ async fn test_github_clone_error_handling_and_recovery(
     ];
 
     for (repo, target_dir, should_potentially_succeed) in test_sequence {
-        let clone_result = client.repo_clone(repo, target_dir).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, target_dir, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -1595,7 +1639,9 @@ async fn test_github_clone_authentication_scenarios(
     };
 
     for (repo, description) in test_repos {
-        let clone_result = client.repo_clone(repo, None).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, None, &CloneOptions::default())
+            .await;
 
         assert!(
             clone_result.is_ok(),
@@ -1638,7 +1684,11 @@ async fn test_github_clone_repository_access_patterns(
 
     for (repo, description) in repository_types {
         let clone_result = client
-            .repo_clone(repo, Some(&format!("test-{}", repo.replace("/", "-"))))
+            .repo_clone_with_options(
+                repo,
+                Some(&format!("test-{}", repo.replace("/", "-"))),
+                &CloneOptions::default(),
+            )
             .await;
 
         assert!(
@@ -1701,11 +1751,11 @@ async fn test_github_clone_workflow_integration(
     println!("✅ Step 2: Authentication status checked");
 
     // Step 3: Attempt to list repositories (may fail if not authenticated)
-    let repo_list_result = client.repo_list().await;
+    let repo_list_result = client.repo_list_detailed().await;
     match repo_list_result {
         Ok(repos) => {
             println!(
-                "✅ Step 3: Repository list retrieved: {} chars",
+                "✅ Step 3: Repository list retrieved: {} repositories",
                 repos.len()
             );
         }
@@ -1719,7 +1769,11 @@ async fn test_github_clone_workflow_integration(
 
     // Step 4: Attempt to clone a public repository
     let clone_result = client
-        .repo_clone("octocat/Hello-World", Some("workflow-test"))
+        .repo_clone_with_options(
+            "octocat/Hello-World",
+            Some("workflow-test"),
+            &CloneOptions::default(),
+        )
         .await;
 
     assert!(
@@ -1742,7 +1796,9 @@ async fn test_github_clone_workflow_integration(
     ];
 
     for (repo, target) in multiple_clones {
-        let clone_result = client.repo_clone(repo, Some(target)).await;
+        let clone_result = client
+            .repo_clone_with_options(repo, Some(target), &CloneOptions::default())
+            .await;
         assert!(
             clone_result.is_ok(),
             "Multiple clone failed for {}: {:?}",
@@ -1827,7 +1883,7 @@ async fn test_github_exec_timeout_configuration(
     let simulated_timeout_error = format!(
         "Command timed out after {} seconds: {}",
         2,
-        vec!["gh", "auth", "login"].join(" ")
+        ["gh", "auth", "login"].join(" ")
     );
 
     assert!(simulated_timeout_error.contains("timed out after 2 seconds"));
@@ -2104,8 +2160,10 @@ async fn test_exec_command_allow_failure_working_directory(
     let (docker, container_id, container_name) = test_container.await;
 
     // Test with custom working directory
-    let mut config = GithubClientConfig::default();
-    config.working_directory = Some("/tmp".to_string());
+    let config = GithubClientConfig {
+        working_directory: Some("/tmp".to_string()),
+        ..Default::default()
+    };
     let client = GithubClient::new(docker.clone(), container_id, config);
 
     // Test command that shows current working directory
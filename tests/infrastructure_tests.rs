@@ -73,6 +73,7 @@ async fn test_volume_creation_and_persistence(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -136,6 +137,7 @@ async fn test_volume_creation_and_persistence(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -242,6 +244,7 @@ async fn test_volume_isolation_between_users(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(user_id_1.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -252,6 +255,7 @@ async fn test_volume_isolation_between_users(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(user_id_2.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -391,6 +395,7 @@ async fn test_use_persistant_volume_setting(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -429,6 +434,7 @@ async fn test_use_persistant_volume_setting(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: None,
+            github_token: None,
         },
     )
     .await;
@@ -472,7 +478,7 @@ async fn test_use_persistant_volume_setting(docker: Docker) {
 fn test_timeout_error_message_format() {
     // Test the timeout error message format that users would see
     let timeout_secs = 60;
-    let command = vec!["gh", "auth", "login", "--git-protocol", "https"];
+    let command = ["gh", "auth", "login", "--git-protocol", "https"];
 
     let timeout_error = format!(
         "Command timed out after {} seconds: {}",
@@ -632,7 +638,7 @@ async fn test_buffering_prevents_rapid_transitions() {
     println!("🔍 Testing that buffering prevents rapid state transitions:");
 
     // Without buffering, each chunk would trigger state parsing
-    let chunks_without_buffering = vec!["chunk1", "chunk2", "chunk3"];
+    let chunks_without_buffering = ["chunk1", "chunk2", "chunk3"];
     let state_transitions_without_buffering = chunks_without_buffering.len();
 
     // With buffering, chunks within 200ms are accumulated
@@ -789,6 +795,7 @@ async fn test_claude_settings_json_creation(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;
@@ -834,6 +841,7 @@ async fn test_claude_settings_json_creation(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: None,
+            github_token: None,
         },
     )
     .await;
@@ -886,6 +894,7 @@ async fn test_claude_settings_json_creation(docker: Docker) {
         ClaudeCodeConfig::default(),
         container_utils::CodingContainerConfig {
             persistent_volume_key: Some(test_user_id.to_string()),
+            github_token: None,
         },
     )
     .await;